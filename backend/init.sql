@@ -25,6 +25,10 @@ CREATE TABLE IF NOT EXISTS "diagram" (
 	-- 最近修改日期
 	"last_modified" TIME,
 	"pan" VARCHAR,
+	-- 自定义类型，JSON数组
+	"types" VARCHAR,
+	-- 枚举，JSON数组
+	"enums" VARCHAR,
 	PRIMARY KEY("id")
 );
 
@@ -37,7 +41,7 @@ CREATE TABLE IF NOT EXISTS "diagram_link" (
 	"table_id" VARCHAR,
 	"area_id" VARCHAR,
 	"note_id" VARCHAR,
-	"reference" VARCHAR,
+	"reference_id" VARCHAR,
 	PRIMARY KEY("id")
 );
 
@@ -55,9 +59,9 @@ CREATE TABLE IF NOT EXISTS "table" (
 	-- 表名称
 	"name" VARCHAR,
 	-- 无限画布上横向的位置
-	"x" NUMERIC,
+	"x" VARCHAR,
 	-- 无限画布上的纵向位置
-	"y" NUMERIC,
+	"y" VARCHAR,
 	PRIMARY KEY("id")
 );
 
@@ -76,7 +80,9 @@ CREATE TABLE IF NOT EXISTS "field" (
 	"not_null" BOOLEAN,
 	"primary" BOOLEAN,
 	-- 字段大小
-	"size" VARCHAR,
+	"size" INTEGER,
+	-- ENUM、SET类型的取值，JSON数组
+	"values" VARCHAR,
 	-- 类型字段
 	"type" VARCHAR,
 	-- 索引标记
@@ -100,6 +106,8 @@ CREATE TABLE IF NOT EXISTS "indice" (
 	"name" VARCHAR,
 	-- 唯一索引标记
 	"unique" BOOLEAN,
+	-- 索引所属的表
+	"table_id" VARCHAR,
 	PRIMARY KEY("id")
 );
 
@@ -107,6 +115,8 @@ CREATE TABLE IF NOT EXISTS "indice_link" (
 	"id" VARCHAR NOT NULL UNIQUE,
 	"field_id" VARCHAR,
 	"indice_id" VARCHAR,
+	-- 字段在索引中的顺序
+	"order" INTEGER,
 	PRIMARY KEY("id")
 );
 
@@ -130,15 +140,15 @@ CREATE TABLE IF NOT EXISTS "area" (
 	-- 颜色
 	"color" VARCHAR,
 	-- 主题域高度
-	"height" NUMERIC,
+	"height" VARCHAR,
 	-- 主题域名称
 	"name" VARCHAR,
 	-- 主题域宽度
-	"width" NUMERIC,
+	"width" VARCHAR,
 	-- 主题域的横坐标
-	"x" NUMERIC,
+	"x" VARCHAR,
 	-- 主题域的纵坐标
-	"y" NUMERIC,
+	"y" VARCHAR,
 	PRIMARY KEY("id")
 );
 
@@ -150,12 +160,40 @@ CREATE TABLE IF NOT EXISTS "note" (
 	-- 内容字段
 	"content" VARCHAR,
 	-- 高度字段
-	"height" NUMERIC,
+	"height" VARCHAR,
 	-- 注释标题
 	"title" VARCHAR,
 	-- 注释在画布的横坐标
-	"x" NUMERIC,
+	"x" VARCHAR,
 	-- 注释在画布的纵坐标
-	"y" NUMERIC,
+	"y" VARCHAR,
 	PRIMARY KEY("id")
 );
+
+/* 图表版本表，每次保存图表时记录完整结构的快照 */
+CREATE TABLE IF NOT EXISTS "diagram_version" (
+	"id" VARCHAR NOT NULL UNIQUE,
+	-- 图表id
+	"diagram_id" VARCHAR NOT NULL,
+	-- 版本号，同一图表内从1开始递增
+	"version" INTEGER NOT NULL,
+	-- 完整的图表结构，JSON
+	"snapshot" VARCHAR NOT NULL,
+	-- 保存人
+	"author" VARCHAR,
+	-- 版本说明
+	"message" VARCHAR,
+	-- 保存时间，RFC 3339
+	"created_at" VARCHAR NOT NULL,
+	PRIMARY KEY("id")
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS "diagram_version_index_0"
+ON "diagram_version" ("diagram_id", "version");
+
+/* 应用状态表，记录首次初始化等运行状态，代替修改配置文件 */
+CREATE TABLE IF NOT EXISTS "app_state" (
+	"key" VARCHAR NOT NULL UNIQUE,
+	"value" VARCHAR,
+	PRIMARY KEY("key")
+);
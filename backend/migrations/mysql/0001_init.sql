@@ -0,0 +1,198 @@
+/* MySQL从第6版结构开始支持，第1版直接创建完整的结构 */
+
+/* 代办事项表 */
+CREATE TABLE IF NOT EXISTS `task` (
+	-- 主键
+	`id` VARCHAR(64) NOT NULL,
+	-- 完成标记
+	`complete` TINYINT(1),
+	-- 排序号
+	`order` INTEGER,
+	-- 详情
+	`details` TEXT,
+	-- 标题
+	`title` VARCHAR(255),
+	PRIMARY KEY(`id`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+/* 图表数据结构 */
+CREATE TABLE IF NOT EXISTS `diagram` (
+	`id` VARCHAR(64) NOT NULL,
+	-- 空间缩放比例
+	`zoom` VARCHAR(64),
+	-- 设计名称
+	`name` VARCHAR(255),
+	-- 数据库名称
+	`database` VARCHAR(64),
+	-- 最近修改日期
+	`last_modified` VARCHAR(64),
+	`pan` VARCHAR(255),
+	-- 自定义类型，JSON数组
+	`types` TEXT,
+	-- 枚举，JSON数组
+	`enums` TEXT,
+	PRIMARY KEY(`id`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+/* 关联关系表 */
+CREATE TABLE IF NOT EXISTS `diagram_link` (
+	`id` VARCHAR(64) NOT NULL,
+	-- 图id
+	`diagram_id` VARCHAR(64),
+	`task_id` VARCHAR(64),
+	`table_id` VARCHAR(64),
+	`area_id` VARCHAR(64),
+	`note_id` VARCHAR(64),
+	`reference_id` VARCHAR(64),
+	PRIMARY KEY(`id`),
+	INDEX `diagram_link_index_0` (`diagram_id`, `task_id`, `table_id`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+/* 表的存储结构 */
+CREATE TABLE IF NOT EXISTS `table` (
+	`id` VARCHAR(64) NOT NULL,
+	-- 表的颜色样式
+	`color` VARCHAR(64),
+	-- 备注
+	`comment` TEXT,
+	-- 在无限画布上是否锁定
+	`locked` TINYINT(1),
+	-- 表名称
+	`name` VARCHAR(255),
+	-- 无限画布上横向的位置
+	`x` VARCHAR(64),
+	-- 无限画布上的纵向位置
+	`y` VARCHAR(64),
+	PRIMARY KEY(`id`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+/* 字段报表 */
+CREATE TABLE IF NOT EXISTS `field` (
+	`id` VARCHAR(64) NOT NULL,
+	-- 检查表达式字段
+	`check` TEXT,
+	-- 注释字段
+	`comment` TEXT,
+	-- 默认值字段
+	`default` TEXT,
+	-- 自增标记字段
+	`increment` TINYINT(1),
+	-- 非空标记
+	`not_null` TINYINT(1),
+	`primary` TINYINT(1),
+	-- 字段大小
+	`size` INTEGER,
+	-- ENUM、SET类型的取值，JSON数组
+	`values` TEXT,
+	-- 类型字段
+	`type` VARCHAR(255),
+	-- 索引标记
+	`unique` TINYINT(1),
+	`name` VARCHAR(255),
+	PRIMARY KEY(`id`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+CREATE TABLE IF NOT EXISTS `table_link` (
+	`id` VARCHAR(64) NOT NULL,
+	`field_id` VARCHAR(64),
+	`table_id` VARCHAR(64),
+	PRIMARY KEY(`id`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+/* 索引表 */
+CREATE TABLE IF NOT EXISTS `indice` (
+	-- 主键
+	`id` VARCHAR(64) NOT NULL,
+	-- 索引名称
+	`name` VARCHAR(255),
+	-- 唯一索引标记
+	`unique` TINYINT(1),
+	-- 索引所属的表
+	`table_id` VARCHAR(64),
+	PRIMARY KEY(`id`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+CREATE TABLE IF NOT EXISTS `indice_link` (
+	`id` VARCHAR(64) NOT NULL,
+	`field_id` VARCHAR(64),
+	`indice_id` VARCHAR(64),
+	-- 字段在索引中的顺序
+	`order` INTEGER,
+	PRIMARY KEY(`id`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+CREATE TABLE IF NOT EXISTS `reference` (
+	`id` VARCHAR(64) NOT NULL,
+	-- 关系映射
+	`cardinality` VARCHAR(64),
+	`deleteConstraint` VARCHAR(64),
+	`endFieldId` VARCHAR(64),
+	`endTableId` VARCHAR(64),
+	`name` VARCHAR(255),
+	`startFieldId` VARCHAR(64),
+	`startTableId` VARCHAR(64),
+	`updateConstraint` VARCHAR(64),
+	PRIMARY KEY(`id`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+/* 主题区域表结构 */
+CREATE TABLE IF NOT EXISTS `area` (
+	`id` VARCHAR(64) NOT NULL,
+	-- 颜色
+	`color` VARCHAR(64),
+	-- 主题域高度
+	`height` VARCHAR(64),
+	-- 主题域名称
+	`name` VARCHAR(255),
+	-- 主题域宽度
+	`width` VARCHAR(64),
+	-- 主题域的横坐标
+	`x` VARCHAR(64),
+	-- 主题域的纵坐标
+	`y` VARCHAR(64),
+	PRIMARY KEY(`id`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+/* 注释表结构 */
+CREATE TABLE IF NOT EXISTS `note` (
+	`id` VARCHAR(64) NOT NULL,
+	-- 颜色字段
+	`color` VARCHAR(64),
+	-- 内容字段
+	`content` TEXT,
+	-- 高度字段
+	`height` VARCHAR(64),
+	-- 注释标题
+	`title` VARCHAR(255),
+	-- 注释在画布的横坐标
+	`x` VARCHAR(64),
+	-- 注释在画布的纵坐标
+	`y` VARCHAR(64),
+	PRIMARY KEY(`id`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+/* 图表版本表，每次保存图表时记录完整结构的快照 */
+CREATE TABLE IF NOT EXISTS `diagram_version` (
+	`id` VARCHAR(64) NOT NULL,
+	-- 图表id
+	`diagram_id` VARCHAR(64) NOT NULL,
+	-- 版本号，同一图表内从1开始递增
+	`version` INTEGER NOT NULL,
+	-- 完整的图表结构，JSON
+	`snapshot` LONGTEXT NOT NULL,
+	-- 保存人
+	`author` VARCHAR(255),
+	-- 版本说明
+	`message` TEXT,
+	-- 保存时间，RFC 3339
+	`created_at` VARCHAR(64) NOT NULL,
+	PRIMARY KEY(`id`),
+	UNIQUE INDEX `diagram_version_index_0` (`diagram_id`, `version`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+/* 应用状态表，记录首次初始化等运行状态，代替修改配置文件 */
+CREATE TABLE IF NOT EXISTS `app_state` (
+	`key` VARCHAR(64) NOT NULL,
+	`value` TEXT,
+	PRIMARY KEY(`key`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
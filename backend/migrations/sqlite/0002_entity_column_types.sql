@@ -0,0 +1,93 @@
+/* 与实体对齐：diagram_link.reference改名为reference_id，坐标和尺寸改为VARCHAR，field.size改为INTEGER */
+ALTER TABLE "diagram_link" RENAME COLUMN "reference" TO "reference_id";
+
+/* SQLite不支持修改列类型，重建表并复制数据 */
+CREATE TABLE "_new_table" (
+	"id" VARCHAR NOT NULL UNIQUE,
+	-- 表的颜色样式
+	"color" VARCHAR,
+	-- 备注
+	"comment" VARCHAR,
+	-- 在无限画布上是否锁定
+	"locked" BOOLEAN,
+	-- 表名称
+	"name" VARCHAR,
+	-- 无限画布上横向的位置
+	"x" VARCHAR,
+	-- 无限画布上的纵向位置
+	"y" VARCHAR,
+	PRIMARY KEY("id")
+);
+INSERT INTO "_new_table" ("id", "color", "comment", "locked", "name", "x", "y")
+SELECT "id", "color", "comment", "locked", "name", CAST("x" AS TEXT), CAST("y" AS TEXT) FROM "table";
+DROP TABLE "table";
+ALTER TABLE "_new_table" RENAME TO "table";
+
+CREATE TABLE "_new_field" (
+	"id" VARCHAR NOT NULL UNIQUE,
+	-- 检查表达式字段
+	"check" VARCHAR,
+	-- 注释字段
+	"comment" VARCHAR,
+	-- 默认值字段
+	"default" VARCHAR,
+	-- 自增标记字段
+	"increment" BOOLEAN,
+	-- 非空标记
+	"not_null" BOOLEAN,
+	"primary" BOOLEAN,
+	-- 字段大小
+	"size" INTEGER,
+	-- 类型字段
+	"type" VARCHAR,
+	-- 索引标记
+	"unique" BOOLEAN,
+	"name" VARCHAR,
+	PRIMARY KEY("id")
+);
+INSERT INTO "_new_field" ("id", "check", "comment", "default", "increment", "not_null", "primary", "size", "type", "unique", "name")
+SELECT "id", "check", "comment", "default", "increment", "not_null", "primary", CAST(NULLIF(TRIM("size"), '') AS INTEGER), "type", "unique", "name" FROM "field";
+DROP TABLE "field";
+ALTER TABLE "_new_field" RENAME TO "field";
+
+CREATE TABLE "_new_area" (
+	"id" VARCHAR NOT NULL UNIQUE,
+	-- 颜色
+	"color" VARCHAR,
+	-- 主题域高度
+	"height" VARCHAR,
+	-- 主题域名称
+	"name" VARCHAR,
+	-- 主题域宽度
+	"width" VARCHAR,
+	-- 主题域的横坐标
+	"x" VARCHAR,
+	-- 主题域的纵坐标
+	"y" VARCHAR,
+	PRIMARY KEY("id")
+);
+INSERT INTO "_new_area" ("id", "color", "height", "name", "width", "x", "y")
+SELECT "id", "color", CAST("height" AS TEXT), "name", CAST("width" AS TEXT), CAST("x" AS TEXT), CAST("y" AS TEXT) FROM "area";
+DROP TABLE "area";
+ALTER TABLE "_new_area" RENAME TO "area";
+
+CREATE TABLE "_new_note" (
+	"id" VARCHAR NOT NULL UNIQUE,
+	-- 颜色字段
+	"color" VARCHAR,
+	-- 内容字段
+	"content" VARCHAR,
+	-- 高度字段
+	"height" VARCHAR,
+	-- 注释标题
+	"title" VARCHAR,
+	-- 注释在画布的横坐标
+	"x" VARCHAR,
+	-- 注释在画布的纵坐标
+	"y" VARCHAR,
+	PRIMARY KEY("id")
+);
+INSERT INTO "_new_note" ("id", "color", "content", "height", "title", "x", "y")
+SELECT "id", "color", "content", CAST("height" AS TEXT), "title", CAST("x" AS TEXT), CAST("y" AS TEXT) FROM "note";
+DROP TABLE "note";
+ALTER TABLE "_new_note" RENAME TO "note";
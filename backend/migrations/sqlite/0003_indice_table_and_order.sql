@@ -0,0 +1,5 @@
+/* 索引所属的表 */
+ALTER TABLE "indice" ADD COLUMN "table_id" VARCHAR;
+
+/* 字段在索引中的顺序 */
+ALTER TABLE "indice_link" ADD COLUMN "order" INTEGER;
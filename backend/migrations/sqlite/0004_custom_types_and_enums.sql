@@ -0,0 +1,6 @@
+/* 自定义类型和枚举，JSON数组 */
+ALTER TABLE "diagram" ADD COLUMN "types" VARCHAR;
+ALTER TABLE "diagram" ADD COLUMN "enums" VARCHAR;
+
+/* ENUM、SET类型的取值，JSON数组 */
+ALTER TABLE "field" ADD COLUMN "values" VARCHAR;
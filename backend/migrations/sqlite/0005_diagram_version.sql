@@ -0,0 +1,20 @@
+/* 图表版本表，每次保存图表时记录完整结构的快照 */
+CREATE TABLE IF NOT EXISTS "diagram_version" (
+	"id" VARCHAR NOT NULL UNIQUE,
+	-- 图表id
+	"diagram_id" VARCHAR NOT NULL,
+	-- 版本号，同一图表内从1开始递增
+	"version" INTEGER NOT NULL,
+	-- 完整的图表结构，JSON
+	"snapshot" VARCHAR NOT NULL,
+	-- 保存人
+	"author" VARCHAR,
+	-- 版本说明
+	"message" VARCHAR,
+	-- 保存时间，RFC 3339
+	"created_at" VARCHAR NOT NULL,
+	PRIMARY KEY("id")
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS "diagram_version_index_0"
+ON "diagram_version" ("diagram_id", "version");
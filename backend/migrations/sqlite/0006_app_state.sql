@@ -0,0 +1,6 @@
+/* 应用状态表，记录首次初始化等运行状态，代替修改配置文件 */
+CREATE TABLE IF NOT EXISTS "app_state" (
+	"key" VARCHAR NOT NULL UNIQUE,
+	"value" VARCHAR,
+	PRIMARY KEY("key")
+);
@@ -0,0 +1,65 @@
+use sea_orm::{ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseTransaction, EntityTrait, JoinType, QueryFilter, QuerySelect, RelationTrait};
+use crate::entity::{diagram_link, prelude::DiagramLink};
+use crate::entity::area::{ActiveModel as AreaActiveModel, Column, Entity as Area, Relation as AreaRelation};
+use crate::{entity::vo::AreaVo, error::DrawDBError};
+use crate::next_id;
+
+/// 查询图表关联的主题区域
+pub async fn query_areas<C: ConnectionTrait>(
+    conn: &C,
+    diagram_id: String
+) -> Result<Vec<AreaVo>, DrawDBError> {
+    let areas = Area::find()
+    .join(JoinType::InnerJoin, AreaRelation::DiagramLink.def())
+    .filter(diagram_link::Column::DiagramId.eq(diagram_id))
+    .all(conn)
+    .await?;
+    Ok(areas.iter().map(AreaVo::from).collect())
+}
+
+/// 新增主题区域
+/// 同时建立图表与主题区域的关联关系
+pub async fn add_area(
+    tx: &DatabaseTransaction,
+    diagram_id: String,
+    area_vo: AreaVo
+) -> Result<String, DrawDBError> {
+    let area_active_model = AreaActiveModel::from(area_vo.convert_to_area());
+    Area::insert(area_active_model).exec(tx).await?;
+    let diagram_link = diagram_link::Model::new(
+        next_id(),
+        Some(diagram_id),
+        None,
+        None,
+        Some(area_vo.id.clone()),
+        None,
+        None
+    );
+    DiagramLink::insert(diagram_link::ActiveModel::from(diagram_link)).exec(tx).await?;
+    Ok(area_vo.id)
+}
+
+/// 更新主题区域
+pub async fn update_area(
+    tx: &DatabaseTransaction,
+    area_vo: AreaVo
+) -> Result<bool, DrawDBError> {
+    let area_active_model = AreaActiveModel::from(area_vo.convert_to_area());
+    let result = Area::update_many()
+    .set(area_active_model.reset_all())
+    .filter(Column::Id.eq(area_vo.id))
+    .exec(tx)
+    .await?;
+    Ok(result.rows_affected > 0)
+}
+
+/// 删除主题区域
+/// 同时删除图表与主题区域的关联关系
+pub async fn delete_area(
+    tx: &DatabaseTransaction,
+    area_id: String
+) -> Result<bool, DrawDBError> {
+    DiagramLink::delete_many().filter(diagram_link::Column::AreaId.eq(area_id.clone())).exec(tx).await?;
+    let result = Area::delete_many().filter(Column::Id.eq(area_id)).exec(tx).await?;
+    Ok(result.rows_affected > 0)
+}
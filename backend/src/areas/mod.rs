@@ -0,0 +1,173 @@
+mod internal_api;
+
+pub use internal_api::*;
+use actix_web::{delete, get, post, web};
+use sea_orm::{DatabaseConnection, TransactionTrait};
+use crate::common::{CommonResponse, ResponseCode, ResponseMessage};
+use crate::entity::vo::{AreaAddVo, AreaVo};
+use crate::error::DrawDBError;
+use crate::next_id;
+
+/// 主题区域模块
+pub fn areas_routes(config: &mut web::ServiceConfig) {
+    config.service(query);
+    config.service(add);
+    config.service(update);
+    config.service(delete);
+}
+
+/// 根据diagram_id获取关联的主题区域
+#[get("/query/{diagram_id}")]
+async fn query(
+    db: web::Data<DatabaseConnection>,
+    diagram_id: web::Path<String>
+) -> Result<CommonResponse, DrawDBError> {
+    let result = query_areas(db.get_ref(), diagram_id.into_inner()).await?;
+    Ok(CommonResponse::new(
+        ResponseCode::Success,
+        ResponseMessage::Success,
+        Some(serde_json::to_value(result).unwrap()),
+    ))
+}
+
+/// 新增主题区域
+#[post("/add")]
+async fn add(
+    db: web::Data<DatabaseConnection>,
+    area: web::Json<AreaAddVo>
+) -> Result<CommonResponse, DrawDBError> {
+    let tx = db.begin().await?;
+    let area = area.into_inner();
+    let area_id = add_area(&tx, area.diagram_id.clone(), area.convert_to_area_vo(next_id())).await?;
+    tx.commit().await?;
+    Ok(CommonResponse::new(
+        ResponseCode::Success,
+        ResponseMessage::Success,
+        Some(serde_json::to_value(area_id).unwrap()),
+    ))
+}
+
+/// 更新主题区域
+#[post("/update")]
+async fn update(
+    db: web::Data<DatabaseConnection>,
+    area: web::Json<AreaVo>
+) -> Result<CommonResponse, DrawDBError> {
+    let tx = db.begin().await?;
+    let area = area.into_inner();
+    let area_id = area.id.clone();
+    if !update_area(&tx, area).await? {
+        tx.rollback().await?;
+        return Ok(CommonResponse::new(ResponseCode::NotFound, ResponseMessage::NotFound, None));
+    }
+    tx.commit().await?;
+    Ok(CommonResponse::new(
+        ResponseCode::Success,
+        ResponseMessage::Success,
+        Some(serde_json::to_value(area_id).unwrap()),
+    ))
+}
+
+/// 删除主题区域
+#[delete("/delete/{id}")]
+async fn delete(
+    db: web::Data<DatabaseConnection>,
+    id: web::Path<String>
+) -> Result<CommonResponse, DrawDBError> {
+    let tx = db.begin().await?;
+    let id = id.into_inner();
+    if !delete_area(&tx, id.clone()).await? {
+        tx.rollback().await?;
+        return Ok(CommonResponse::new(ResponseCode::NotFound, ResponseMessage::NotFound, None));
+    }
+    tx.commit().await?;
+    Ok(CommonResponse::new(
+        ResponseCode::Success,
+        ResponseMessage::Success,
+        Some(serde_json::to_value(id).unwrap()),
+    ))
+}
+
+
+#[cfg(test)]
+mod test {
+    use actix_web::{test, web, App};
+    use sea_orm::EntityTrait;
+    use serde_json::json;
+
+    use super::*;
+    use crate::diagrams::query_diagram_detail;
+    use crate::entity::{diagram, prelude::*};
+    use crate::init::init_test_db;
+
+    /// 主题区域的增删改查，并随图表一起加载
+    #[actix_web::test]
+    async fn test_areas_crud() {
+        let db = init_test_db().await;
+        Diagram::insert(diagram::ActiveModel::from(diagram::Model {
+            id: "1".to_string(),
+            database: None,
+            zoom: None,
+            name: Some("test".to_string()),
+            pan: None,
+            last_modified: None,
+            types: None,
+            enums: None,
+        })).exec(&db).await.unwrap();
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(db.clone()))
+                .configure(areas_routes)
+        ).await;
+        // 新增主题区域
+        let req = test::TestRequest::post()
+            .uri("/add")
+            .set_json(json!({
+                "diagram_id": "1",
+                "color": "#175e7a",
+                "height": "200",
+                "name": "订单域",
+                "width": "300",
+                "x": "10",
+                "y": "20"
+            }))
+            .to_request();
+        let resp: CommonResponse = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(resp.code, 200);
+        let area_id: String = serde_json::from_value(resp.data.unwrap()).unwrap();
+
+        // 更新主题区域
+        let req = test::TestRequest::post()
+            .uri("/update")
+            .set_json(json!({
+                "id": area_id,
+                "color": "#175e7a",
+                "height": "200",
+                "name": "用户域",
+                "width": "300",
+                "x": "10",
+                "y": "20"
+            }))
+            .to_request();
+        let resp: CommonResponse = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(resp.code, 200);
+
+        // 查询图表关联的主题区域
+        let req = test::TestRequest::get().uri("/query/1").to_request();
+        let resp: CommonResponse = test::call_and_read_body_json(&app, req).await;
+        let areas: Vec<AreaVo> = serde_json::from_value(resp.data.unwrap()).unwrap();
+        assert_eq!(areas.len(), 1);
+        assert_eq!(areas[0].name, Some("用户域".to_string()));
+
+        // 随图表一起加载
+        let diagram = query_diagram_detail(&db, "1".to_string()).await.unwrap().unwrap();
+        assert_eq!(diagram.areas.unwrap(), areas);
+
+        // 删除主题区域
+        let req = test::TestRequest::delete().uri(&format!("/delete/{}", area_id)).to_request();
+        let resp: CommonResponse = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(resp.code, 200);
+        assert!(DiagramLink::find().all(&db).await.unwrap().is_empty());
+        assert!(Area::find().all(&db).await.unwrap().is_empty());
+    }
+}
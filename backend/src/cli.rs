@@ -0,0 +1,144 @@
+use crate::error::DrawDBError;
+use crate::init::ConfigOverrides;
+
+/// 命令行用法
+pub const USAGE: &str = "用法:
+  backend [选项]                                启动服务
+  backend [选项] import-sqlite <路径> [名称]    从SQLite数据库文件逆向生成图表
+  backend help                                  显示帮助
+
+选项:
+  --config <路径>      配置文件，默认为config.toml
+  --database <路径>    数据库文件
+  --host <地址>        监听地址
+  --port <端口>        监听端口
+  --init-db            清空并重新创建数据库，同一个数据库只执行一次
+
+配置按默认值、配置文件、环境变量、命令行选项的顺序覆盖
+环境变量为DRAWDB_加上大写的节名和键名，如DRAWDB_SERVER_PORT、DRAWDB_DEPLOY_ALLOWED_PATHS(逗号分隔)";
+
+/// 命令行参数，包括子命令和覆盖配置的选项
+#[derive(Debug, PartialEq, Eq)]
+pub struct Cli {
+    pub command: Command,
+    pub overrides: ConfigOverrides,
+}
+
+impl Cli {
+    /// 解析命令行参数，不包含程序名称
+    /// 选项可以出现在子命令前后，值可以写成--port 8000或--port=8000
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, DrawDBError> {
+        let mut overrides = ConfigOverrides::default();
+        let mut positional = Vec::new();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let (flag, inline) = match arg.split_once('=') {
+                Some((flag, value)) if arg.starts_with("--") => (flag.to_string(), Some(value.to_string())),
+                _ => (arg.clone(), None),
+            };
+            match flag.as_str() {
+                "--config" => overrides.config_path = Some(flag_value(&flag, inline, &mut args)?),
+                "--database" => overrides.database_path = Some(flag_value(&flag, inline, &mut args)?),
+                "--host" => overrides.host = Some(flag_value(&flag, inline, &mut args)?),
+                "--port" => overrides.port = Some(flag_value(&flag, inline, &mut args)?),
+                "--init-db" if inline.is_none() => overrides.init_db = true,
+                "--help" => positional.push(arg),
+                _ if flag.starts_with("--") => {
+                    return Err(DrawDBError::ValidationError(format!("未知的选项: {}\n{}", arg, USAGE)));
+                }
+                _ => positional.push(arg),
+            }
+        }
+        Ok(Cli {
+            command: Command::parse(positional)?,
+            overrides,
+        })
+    }
+}
+
+/// 选项的值，紧跟在=后面或者是下一个参数
+fn flag_value(flag: &str, inline: Option<String>, args: &mut impl Iterator<Item = String>) -> Result<String, DrawDBError> {
+    inline.or_else(|| args.next())
+    .ok_or_else(|| DrawDBError::ValidationError(format!("选项{}缺少值\n{}", flag, USAGE)))
+}
+
+/// 命令行子命令
+#[derive(Debug, PartialEq, Eq)]
+pub enum Command {
+    /// 启动服务，未指定子命令时的默认值
+    Serve,
+    /// 从SQLite数据库文件逆向生成图表
+    ImportSqlite { path: String, name: Option<String> },
+    /// 显示帮助
+    Help,
+}
+
+impl Command {
+    /// 解析子命令和位置参数，选项已由Cli::parse取出
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, DrawDBError> {
+        let mut args = args.into_iter();
+        let Some(command) = args.next() else {
+            return Ok(Command::Serve);
+        };
+        let command = match command.as_str() {
+            "serve" => Command::Serve,
+            "import-sqlite" => {
+                let path = args.next()
+                .ok_or_else(|| DrawDBError::ValidationError(format!("缺少数据库文件路径\n{}", USAGE)))?;
+                Command::ImportSqlite { path, name: args.next() }
+            }
+            "help" | "-h" | "--help" => Command::Help,
+            other => return Err(DrawDBError::ValidationError(format!("未知的子命令: {}\n{}", other, USAGE))),
+        };
+        match args.next() {
+            Some(extra) => Err(DrawDBError::ValidationError(format!("多余的参数: {}\n{}", extra, USAGE))),
+            None => Ok(command),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Command, DrawDBError> {
+        Command::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    fn parse_cli(args: &[&str]) -> Result<Cli, DrawDBError> {
+        Cli::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn test_parse(){
+        assert_eq!(parse(&[]).unwrap(), Command::Serve);
+        assert_eq!(parse(&["import-sqlite", "prod.sqlite"]).unwrap(), Command::ImportSqlite {
+            path: "prod.sqlite".to_string(),
+            name: None,
+        });
+        assert_eq!(parse(&["import-sqlite", "prod.sqlite", "prod"]).unwrap(), Command::ImportSqlite {
+            path: "prod.sqlite".to_string(),
+            name: Some("prod".to_string()),
+        });
+        assert_eq!(parse(&["--help"]).unwrap(), Command::Help);
+        assert!(parse(&["import-sqlite"]).is_err());
+        assert!(parse(&["export"]).is_err());
+        assert!(parse(&["serve", "now"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_options(){
+        let cli = parse_cli(&["--config", "/etc/drawdb.toml", "import-sqlite", "prod.sqlite", "--port=8000", "--init-db"]).unwrap();
+        assert_eq!(cli.command, Command::ImportSqlite { path: "prod.sqlite".to_string(), name: None });
+        assert_eq!(cli.overrides, ConfigOverrides {
+            config_path: Some("/etc/drawdb.toml".to_string()),
+            port: Some("8000".to_string()),
+            init_db: true,
+            ..Default::default()
+        });
+        assert_eq!(parse_cli(&["--host", "0.0.0.0"]).unwrap().command, Command::Serve);
+        assert_eq!(parse_cli(&["--help"]).unwrap().command, Command::Help);
+        assert!(parse_cli(&["--port"]).is_err());
+        assert!(parse_cli(&["--verbose"]).is_err());
+    }
+}
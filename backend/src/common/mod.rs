@@ -37,16 +37,16 @@ pub enum ResponseMessage {
     NotFound,
     Default(String),
 }
-impl ResponseMessage {
-    // 将ResponseMessage转换为String
-    pub fn to_string(&self) -> String {
+// 将ResponseMessage转换为String
+impl std::fmt::Display for ResponseMessage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            ResponseMessage::Success => "成功".to_string(),
-            ResponseMessage::Failed => "失败".to_string(),
-            ResponseMessage::Unauthorized => "未授权".to_string(),
-            ResponseMessage::Forbidden => "禁止访问".to_string(),
-            ResponseMessage::NotFound => "未找到".to_string(),
-            ResponseMessage::Default(message) => message.clone(),
+            ResponseMessage::Success => write!(f, "成功"),
+            ResponseMessage::Failed => write!(f, "失败"),
+            ResponseMessage::Unauthorized => write!(f, "未授权"),
+            ResponseMessage::Forbidden => write!(f, "禁止访问"),
+            ResponseMessage::NotFound => write!(f, "未找到"),
+            ResponseMessage::Default(message) => write!(f, "{}", message),
         }
     }
 }
@@ -79,7 +79,7 @@ impl ResponseCode {
     }
 }
 impl Responder for CommonResponse {
-    fn respond_to(self, req: &HttpRequest) -> HttpResponse {
+    fn respond_to(self, _req: &HttpRequest) -> HttpResponse {
         HttpResponse::Ok().json(self)
     }
     type Body = BoxBody;
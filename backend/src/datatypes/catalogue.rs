@@ -0,0 +1,634 @@
+use serde::Serialize;
+use crate::entity::vo::FieldVo;
+use crate::export::Dialect;
+use DefaultKind::*;
+
+/// 默认值的校验方式，与前端datatypes.js中各类型的checkDefault一致
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DefaultKind {
+    /// 整数
+    Integer,
+    /// 整数或小数
+    Decimal,
+    /// 0或1
+    Bit,
+    /// true、false、0、1
+    Boolean,
+    /// 字符串，长度不超过size
+    Text,
+    /// 01组成的二进制串，长度不超过size
+    Binary,
+    /// 十六进制串
+    Hex,
+    /// YYYY-MM-DD
+    Date,
+    /// HH:MM:SS
+    Time,
+    /// CURRENT_TIMESTAMP或YYYY-MM-DD HH:MM:SS
+    DateTime,
+    /// 四位年份
+    Year,
+    /// 取值之一
+    Enum,
+    /// 逗号分隔的多个取值
+    Set,
+    /// 不做检查
+    Any,
+    /// 不支持默认值，前端不提供默认值的输入，不做检查
+    NoDefault,
+}
+
+/// 数据库支持的数据类型
+/// sized为true时size表示长度，precision为true时size表示精度，两者都为false时不使用size
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct DataType {
+    pub name: &'static str,
+    pub sized: bool,
+    pub precision: bool,
+    // 未指定长度时使用的默认长度
+    pub default_size: Option<i32>,
+    // 是否支持自增
+    pub increment: bool,
+    // 是否支持UNSIGNED
+    pub signed: bool,
+    // 默认值是否需要加引号
+    pub quoted: bool,
+    pub default: DefaultKind,
+}
+
+impl DataType {
+    const fn new(name: &'static str, default: DefaultKind) -> Self {
+        Self {
+            name,
+            sized: false,
+            precision: false,
+            default_size: None,
+            increment: false,
+            signed: false,
+            quoted: false,
+            default,
+        }
+    }
+
+    const fn sized(mut self, default_size: Option<i32>) -> Self {
+        self.sized = true;
+        self.default_size = default_size;
+        self
+    }
+
+    const fn precision(mut self) -> Self {
+        self.precision = true;
+        self
+    }
+
+    const fn increment(mut self) -> Self {
+        self.increment = true;
+        self
+    }
+
+    const fn signed(mut self) -> Self {
+        self.signed = true;
+        self
+    }
+
+    const fn quoted(mut self) -> Self {
+        self.quoted = true;
+        self
+    }
+
+    /// 默认值是否与类型匹配
+    /// 空值、函数调用以及NULL不做检查，非空字段默认为NULL的情况由校验模块单独报告
+    pub fn check_default(&self, field: &FieldVo) -> bool {
+        let default = field.default.clone().unwrap_or_default();
+        let default = default.trim();
+        if default.is_empty() || is_function(default) || default.eq_ignore_ascii_case("null") {
+            return true;
+        }
+        let unquoted = unquote(default);
+        let size = field.size.filter(|size| *size > 0);
+        match self.default {
+            DefaultKind::Integer => is_integer(default),
+            DefaultKind::Decimal => default.parse::<f64>().is_ok(),
+            DefaultKind::Bit => default == "0" || default == "1",
+            DefaultKind::Boolean => ["true", "false", "0", "1"].contains(&default.to_lowercase().as_str()),
+            DefaultKind::Text => size.is_none_or(|size| unquoted.chars().count() <= size as usize),
+            DefaultKind::Binary => {
+                !unquoted.is_empty()
+                    && unquoted.chars().all(|c| c == '0' || c == '1')
+                    && size.is_none_or(|size| unquoted.len() <= size as usize)
+            }
+            DefaultKind::Hex => unquoted.chars().all(|c| c.is_ascii_hexdigit()),
+            DefaultKind::Date => is_date(unquoted),
+            DefaultKind::Time => is_time(unquoted),
+            DefaultKind::DateTime => {
+                default.eq_ignore_ascii_case("CURRENT_TIMESTAMP")
+                    || unquoted.split_once(' ').is_some_and(|(date, time)| is_date(date) && is_time(time))
+            }
+            DefaultKind::Year => unquoted.len() == 4 && unquoted.chars().all(|c| c.is_ascii_digit()),
+            DefaultKind::Enum => field.values.as_ref().is_some_and(|values| values.iter().any(|value| value == unquoted)),
+            DefaultKind::Set => field.values.as_ref().is_some_and(|values| {
+                unquoted.split(',').all(|item| values.iter().any(|value| value == item.trim()))
+            }),
+            DefaultKind::Any | DefaultKind::NoDefault => true,
+        }
+    }
+}
+
+const fn integer(name: &'static str) -> DataType {
+    DataType::new(name, Integer).increment()
+}
+
+const fn decimal(name: &'static str) -> DataType {
+    DataType::new(name, Decimal).precision()
+}
+
+const fn text(name: &'static str, default_size: i32) -> DataType {
+    DataType::new(name, Text).sized(Some(default_size)).quoted()
+}
+
+const fn binary(name: &'static str, default_size: i32) -> DataType {
+    DataType::new(name, Binary).sized(Some(default_size)).quoted()
+}
+
+/// 默认值需要加引号的类型
+const fn literal(name: &'static str, default: DefaultKind) -> DataType {
+    DataType::new(name, default).quoted()
+}
+
+const fn no_default(name: &'static str) -> DataType {
+    DataType::new(name, NoDefault)
+}
+
+/// 未指定数据库时使用的通用类型
+const GENERIC: &[DataType] = &[
+    integer("INT"),
+    integer("SMALLINT"),
+    integer("BIGINT"),
+    decimal("DECIMAL"),
+    decimal("NUMERIC"),
+    decimal("NUMBER"),
+    decimal("FLOAT"),
+    decimal("DOUBLE"),
+    DataType::new("REAL", Decimal),
+    text("CHAR", 1),
+    text("VARCHAR", 255),
+    text("VARCHAR2", 225),
+    DataType::new("TEXT", Any).sized(Some(65535)).quoted(),
+    literal("TIME", Time),
+    literal("TIMESTAMP", DateTime),
+    literal("DATE", Date),
+    literal("DATETIME", DateTime),
+    DataType::new("BOOLEAN", Boolean),
+    binary("BINARY", 1),
+    binary("VARBINARY", 255),
+    no_default("BLOB"),
+    no_default("CLOB"),
+    no_default("NCLOB"),
+    no_default("JSON"),
+    DataType::new("UUID", Any),
+    DataType::new("ENUM", Enum).quoted(),
+    DataType::new("SET", Set),
+];
+
+const MYSQL: &[DataType] = &[
+    integer("TINYINT").signed(),
+    integer("SMALLINT").signed(),
+    integer("MEDIUMINT").signed(),
+    integer("INTEGER").signed(),
+    integer("BIGINT").signed(),
+    decimal("DECIMAL"),
+    decimal("NUMERIC"),
+    decimal("FLOAT"),
+    decimal("DOUBLE"),
+    DataType::new("BIT", Bit).precision(),
+    DataType::new("BOOLEAN", Boolean),
+    literal("TIME", Time),
+    literal("TIMESTAMP", DateTime),
+    literal("DATE", Date),
+    literal("DATETIME", DateTime),
+    DataType::new("YEAR", Year),
+    text("CHAR", 1),
+    text("VARCHAR", 255),
+    binary("BINARY", 1),
+    binary("VARBINARY", 255),
+    no_default("TINYBLOB"),
+    no_default("BLOB"),
+    no_default("MEDIUMBLOB"),
+    no_default("LONGBLOB"),
+    text("TINYTEXT", 65535),
+    text("TEXT", 65535),
+    text("MEDIUMTEXT", 65535),
+    text("LONGTEXT", 65535),
+    DataType::new("ENUM", Enum).quoted(),
+    DataType::new("SET", Set),
+    no_default("GEOMETRY"),
+    no_default("POINT"),
+    no_default("LINESTRING"),
+    no_default("POLYGON"),
+    no_default("MULTIPOINT"),
+    no_default("MULTILINESTRING"),
+    no_default("MULTIPOLYGON"),
+    no_default("GEOMETRYCOLLECTION"),
+    no_default("JSON"),
+];
+
+/// MariaDB在MySQL的基础上增加的类型
+const MARIADB: &[DataType] = &[
+    DataType::new("UUID", Any),
+    DataType::new("INET4", Any),
+    DataType::new("INET6", Any),
+];
+
+const POSTGRES: &[DataType] = &[
+    integer("SMALLINT"),
+    integer("INTEGER"),
+    integer("BIGINT"),
+    decimal("DECIMAL"),
+    decimal("NUMERIC"),
+    decimal("REAL"),
+    decimal("DOUBLE PRECISION"),
+    DataType::new("SMALLSERIAL", Integer),
+    DataType::new("SERIAL", Integer),
+    DataType::new("BIGSERIAL", Integer),
+    decimal("MONEY"),
+    text("CHAR", 1),
+    text("VARCHAR", 255),
+    DataType::new("TEXT", Text).quoted(),
+    literal("BYTEA", Hex),
+    literal("DATE", Date),
+    literal("TIME", Time),
+    literal("TIMETZ", Any),
+    literal("TIMESTAMP", DateTime),
+    literal("TIMESTAMPTZ", Any),
+    literal("INTERVAL", Any),
+    DataType::new("BOOLEAN", Boolean),
+    DataType::new("POINT", Any),
+    DataType::new("LINE", Any),
+    DataType::new("LSEG", Any),
+    literal("BOX", Any),
+    literal("PATH", Any),
+    literal("POLYGON", Any),
+    literal("CIRCLE", Any),
+    literal("CIDR", Any),
+    literal("INET", Any),
+    literal("MACADDR", Any),
+    literal("MACADDR8", Any),
+    DataType::new("BIT", Binary).sized(Some(1)),
+    DataType::new("VARBIT", Binary).sized(Some(1)),
+    DataType::new("VECTOR", Any).sized(None).quoted(),
+    DataType::new("HALFVEC", Any).sized(None).quoted(),
+    DataType::new("SPARSEVEC", Any).sized(None).quoted(),
+    DataType::new("TSVECTOR", Any),
+    DataType::new("TSQUERY", Any),
+    no_default("JSON").quoted(),
+    no_default("JSONB").quoted(),
+    literal("UUID", Any),
+    no_default("XML").quoted(),
+];
+
+const SQLITE: &[DataType] = &[
+    integer("INTEGER"),
+    decimal("REAL"),
+    decimal("NUMERIC"),
+    DataType::new("BOOLEAN", Boolean),
+    text("VARCHAR", 255),
+    DataType::new("TEXT", Any).sized(Some(65535)).quoted(),
+    no_default("BLOB"),
+    literal("TIME", Time),
+    literal("TIMESTAMP", DateTime),
+    literal("DATE", Date),
+    literal("DATETIME", DateTime),
+];
+
+const MSSQL: &[DataType] = &[
+    integer("TINYINT"),
+    integer("SMALLINT"),
+    integer("INTEGER"),
+    integer("BIGINT"),
+    DataType::new("BIT", Bit).precision(),
+    decimal("DECIMAL"),
+    decimal("NUMERIC"),
+    decimal("FLOAT"),
+    decimal("DOUBLE"),
+    DataType::new("REAL", Decimal),
+    decimal("MONEY"),
+    decimal("SMALLMONEY"),
+    literal("DATE", Date),
+    literal("DATETIME", DateTime),
+    literal("DATETIME2", DateTime).precision(),
+    literal("DATETIMEOFFSET", Any).precision(),
+    literal("SMALLDATETIME", Any),
+    literal("TIME", Time),
+    literal("TIMESTAMP", DateTime),
+    text("CHAR", 1),
+    text("VARCHAR", 255),
+    DataType::new("TEXT", Any).sized(Some(65535)).quoted(),
+    text("NCHAR", 1),
+    text("NVARCHAR", 255),
+    DataType::new("NTEXT", Any).sized(Some(65535)).quoted(),
+    binary("BINARY", 1),
+    binary("VARBINARY", 255),
+    no_default("IMAGE").quoted(),
+    no_default("UNIQUEIDENTIFIER"),
+    no_default("XML").quoted(),
+    no_default("CURSOR"),
+    no_default("SQL_VARIANT"),
+    no_default("JSON").quoted(),
+];
+
+const ORACLE: &[DataType] = &[
+    integer("INTEGER"),
+    decimal("NUMBER"),
+    decimal("FLOAT"),
+    integer("LONG"),
+    text("VARCHAR2", 255),
+    text("NVARCHAR2", 255),
+    text("CHAR", 1),
+    text("NCHAR", 1),
+    no_default("CLOB"),
+    no_default("NCLOB"),
+    no_default("BLOB"),
+    no_default("BFILE"),
+    no_default("JSON"),
+    no_default("VECTOR"),
+    literal("DATE", Date),
+    literal("TIMESTAMP", DateTime).precision(),
+    literal("INTERVAL", Any),
+    DataType::new("BOOLEAN", Boolean),
+    DataType::new("RAW", Hex).sized(Some(255)),
+];
+
+/// 常见的类型别名，数据库不支持别名本身时按对应的类型处理
+const ALIASES: [(&str, &str); 14] = [
+    ("INT", "INTEGER"),
+    ("INTEGER", "INT"),
+    ("INT2", "SMALLINT"),
+    ("INT4", "INTEGER"),
+    ("INT8", "BIGINT"),
+    ("BOOL", "BOOLEAN"),
+    ("DEC", "DECIMAL"),
+    ("DOUBLE PRECISION", "DOUBLE"),
+    ("DOUBLE", "DOUBLE PRECISION"),
+    ("FLOAT8", "DOUBLE PRECISION"),
+    ("FLOAT4", "REAL"),
+    ("CHARACTER VARYING", "VARCHAR"),
+    ("CHARACTER", "CHAR"),
+    ("TIMESTAMP WITH TIME ZONE", "TIMESTAMPTZ"),
+];
+
+/// 某个数据库支持的全部数据类型
+/// dialect为None时使用通用类型，对应前端的generic
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DataTypes {
+    pub dialect: Option<Dialect>,
+}
+
+impl DataTypes {
+    pub fn new(dialect: Option<Dialect>) -> Self {
+        Self { dialect }
+    }
+
+    /// 根据diagram.database确定类型表，未知的数据库使用通用类型
+    pub fn for_database(database: Option<&str>) -> Self {
+        Self::new(database.and_then(|database| database.parse().ok()))
+    }
+
+    /// 按前端datatypes.js中的顺序列出所有类型
+    pub fn all(&self) -> Vec<DataType> {
+        match self.dialect {
+            None => GENERIC.to_vec(),
+            Some(Dialect::MySql) => MYSQL.to_vec(),
+            Some(Dialect::MariaDb) => MYSQL.iter().chain(MARIADB).copied().collect(),
+            Some(Dialect::Postgres) => POSTGRES.to_vec(),
+            Some(Dialect::Sqlite) => SQLITE.to_vec(),
+            Some(Dialect::MsSql) => MSSQL.to_vec(),
+            Some(Dialect::OracleSql) => ORACLE.to_vec(),
+        }
+    }
+
+    /// 按名称查找类型，忽略大小写、多余的空白以及括号中的参数，支持常见的别名
+    pub fn find(&self, name: &str) -> Option<DataType> {
+        let name = name.split('(').next().unwrap_or_default();
+        let name = name.split_whitespace().collect::<Vec<&str>>().join(" ").to_uppercase();
+        let types = self.all();
+        types.iter()
+        .find(|data_type| data_type.name == name)
+        .or_else(|| {
+            ALIASES.iter()
+            .filter(|(alias, _)| *alias == name)
+            .find_map(|(_, target)| types.iter().find(|data_type| data_type.name == *target))
+        })
+        .copied()
+    }
+
+    /// 字段的默认值是否与类型匹配，未知的类型视为匹配
+    pub fn check_default(&self, field: &FieldVo) -> bool {
+        let (name, _) = split_type(field.r#type.as_deref().unwrap_or_default());
+        self.find(&name).is_none_or(|data_type| data_type.check_default(field))
+    }
+
+    /// 校验并规范化字段
+    /// 类型名称统一为类型表中的写法，写在类型中的长度移到size，不使用size的类型清空size，未指定长度时使用默认长度
+    /// custom_types为图表中自定义的类型和枚举，这些类型只检查名称
+    /// 返回不满足约束的原因
+    pub fn normalize_field(&self, field: &mut FieldVo, custom_types: &[String]) -> Result<(), String> {
+        let r#type = field.r#type.clone().unwrap_or_default();
+        let (name, args) = split_type(&r#type);
+        if name.is_empty() {
+            return Err("未指定类型".to_string());
+        }
+        if let Some(custom) = custom_types.iter().find(|custom| custom.eq_ignore_ascii_case(&name)) {
+            field.r#type = Some(custom.clone());
+            return Ok(());
+        }
+        let Some(data_type) = self.find(&name) else {
+            return Err(format!("{}不支持类型{}", self.database_name(), name));
+        };
+        //1、类型名称和长度
+        let mut size = field.size.filter(|size| *size > 0);
+        field.r#type = Some(data_type.name.to_string());
+        match args {
+            Some(TypeSize::Size(value)) => size = Some(value),
+            // 精度和小数位等无法用size表示的参数保留在类型中
+            Some(TypeSize::Raw(args)) => field.r#type = Some(format!("{}({})", data_type.name, args)),
+            None => {}
+        }
+        field.size = if data_type.sized || data_type.precision {
+            size.or(data_type.default_size)
+        } else {
+            None
+        };
+        //2、自增
+        if field.increment.unwrap_or(false) && !data_type.increment {
+            return Err(format!("类型{}不支持自增", data_type.name));
+        }
+        //3、默认值
+        if !data_type.check_default(field) {
+            return Err(format!("默认值{}与类型{}不匹配", field.default.clone().unwrap_or_default(), data_type.name));
+        }
+        Ok(())
+    }
+
+    fn database_name(&self) -> String {
+        match self.dialect {
+            Some(dialect) => dialect.dbml_database_type().to_string(),
+            None => "通用数据库".to_string(),
+        }
+    }
+}
+
+/// 写在类型名称中的参数
+#[derive(Debug, PartialEq, Eq)]
+enum TypeSize {
+    /// 单个正整数，如VARCHAR(255)
+    Size(i32),
+    /// 其余参数，如DECIMAL(10,2)
+    Raw(String),
+}
+
+/// 拆分类型名称和括号中的参数
+fn split_type(r#type: &str) -> (String, Option<TypeSize>) {
+    let r#type = r#type.trim();
+    let Some((name, rest)) = r#type.split_once('(') else {
+        return (r#type.to_string(), None);
+    };
+    let Some(args) = rest.trim_end().strip_suffix(')') else {
+        return (r#type.to_string(), None);
+    };
+    let args = args.trim();
+    let size = match args.parse::<i32>() {
+        Ok(size) if size > 0 => TypeSize::Size(size),
+        _ => TypeSize::Raw(args.to_string()),
+    };
+    (name.trim().to_string(), Some(size))
+}
+
+/// 形如name(...)的函数调用
+pub fn is_function(value: &str) -> bool {
+    let Some(open) = value.find('(') else {
+        return false;
+    };
+    value.ends_with(')')
+        && open > 0
+        && value[..open].chars().last().is_some_and(|c| c.is_alphanumeric() || c == '_')
+}
+
+/// 去掉两端成对的引号
+pub fn unquote(value: &str) -> &str {
+    let quoted = value.len() >= 2
+        && ['\'', '"', '`'].iter().any(|quote| value.starts_with(*quote) && value.ends_with(*quote));
+    if quoted {
+        &value[1..value.len() - 1]
+    } else {
+        value
+    }
+}
+
+fn is_integer(value: &str) -> bool {
+    let digits = value.strip_prefix('-').unwrap_or(value);
+    !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit())
+}
+
+/// YYYY-MM-DD
+fn is_date(value: &str) -> bool {
+    let parts = value.split('-').collect::<Vec<&str>>();
+    parts.len() == 3
+        && [4, 2, 2].iter().zip(&parts).all(|(len, part)| part.len() == *len && part.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// HH:MM:SS
+fn is_time(value: &str) -> bool {
+    let parts = value.split(':').collect::<Vec<&str>>();
+    let numbers = parts.iter().filter_map(|part| {
+        (!part.is_empty() && part.len() <= 2).then(|| part.parse::<u32>().ok()).flatten()
+    }).collect::<Vec<u32>>();
+    parts.len() == 3 && numbers.len() == 3 && numbers[0] < 24 && numbers[1] < 60 && numbers[2] < 60
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::entity::vo::fixtures::build_field;
+
+    fn typed_field(r#type: &str, size: Option<i32>, default: Option<&str>) -> FieldVo {
+        FieldVo {
+            size,
+            default: default.map(str::to_string),
+            ..build_field("f1", "f", r#type)
+        }
+    }
+
+    #[test]
+    fn test_find(){
+        let mysql = DataTypes::new(Some(Dialect::MySql));
+        assert_eq!(mysql.find("int").unwrap().name, "INTEGER");
+        assert_eq!(mysql.find(" varchar(20) ").unwrap().name, "VARCHAR");
+        assert!(mysql.find("INTEGER").unwrap().signed);
+        assert!(mysql.find("JSONB").is_none());
+        assert_eq!(DataTypes::new(Some(Dialect::MariaDb)).find("uuid").unwrap().name, "UUID");
+        let postgres = DataTypes::for_database(Some("postgresql"));
+        assert_eq!(postgres.find("double  precision").unwrap().name, "DOUBLE PRECISION");
+        assert_eq!(postgres.find("double").unwrap().name, "DOUBLE PRECISION");
+        assert!(postgres.find("UUID").unwrap().quoted);
+        assert!(!postgres.find("INTEGER").unwrap().quoted);
+        assert_eq!(DataTypes::for_database(Some("generic")), DataTypes::new(None));
+    }
+
+    #[test]
+    fn test_normalize_field(){
+        let mysql = DataTypes::new(Some(Dialect::MySql));
+        // 类型中的长度移到size
+        let mut field = typed_field("varchar(20)", None, None);
+        mysql.normalize_field(&mut field, &[]).unwrap();
+        assert_eq!(field.r#type.as_deref(), Some("VARCHAR"));
+        assert_eq!(field.size, Some(20));
+        // 未指定长度时使用默认长度，不使用size的类型清空size
+        let mut field = typed_field("VARCHAR", None, None);
+        mysql.normalize_field(&mut field, &[]).unwrap();
+        assert_eq!(field.size, Some(255));
+        let mut field = typed_field("int", Some(11), None);
+        mysql.normalize_field(&mut field, &[]).unwrap();
+        assert_eq!(field.r#type.as_deref(), Some("INTEGER"));
+        assert_eq!(field.size, None);
+        // 精度和小数位保留在类型中
+        let mut field = typed_field("decimal(10, 2)", None, None);
+        mysql.normalize_field(&mut field, &[]).unwrap();
+        assert_eq!(field.r#type.as_deref(), Some("DECIMAL(10, 2)"));
+        // 自定义类型
+        let mut field = typed_field("mood", None, None);
+        mysql.normalize_field(&mut field, &["Mood".to_string()]).unwrap();
+        assert_eq!(field.r#type.as_deref(), Some("Mood"));
+
+        assert!(mysql.normalize_field(&mut typed_field("JSONB", None, None), &[]).is_err());
+        assert!(mysql.normalize_field(&mut typed_field(" ", None, None), &[]).is_err());
+        assert!(mysql.normalize_field(&mut typed_field("VARCHAR", Some(2), Some("'abc'")), &[]).is_err());
+        let mut field = typed_field("VARCHAR", None, None);
+        field.increment = Some(true);
+        assert!(mysql.normalize_field(&mut field, &[]).is_err());
+        assert!(DataTypes::new(Some(Dialect::Postgres)).normalize_field(&mut typed_field("JSONB", None, None), &[]).is_ok());
+    }
+
+    #[test]
+    fn test_check_default(){
+        let generic = DataTypes::new(None);
+        let check = |r#type: &str, default: &str| generic.check_default(&typed_field(r#type, None, Some(default)));
+        assert!(check("INT", "-12"));
+        assert!(check("DECIMAL", "1.5"));
+        assert!(check("BOOLEAN", "false"));
+        assert!(check("DATE", "'2024-01-31'"));
+        assert!(check("TIMESTAMP", "CURRENT_TIMESTAMP"));
+        assert!(check("DATETIME", "'2024-01-31 08:30:00'"));
+        assert!(check("INT", "nextval(seq)"));
+        assert!(check("UNKNOWN", "anything"));
+        assert!(!check("INT", "abc"));
+        assert!(!check("BOOLEAN", "yes"));
+        assert!(!check("TIME", "25:00:00"));
+        assert!(DataTypes::new(Some(Dialect::MySql)).check_default(&typed_field("YEAR", None, Some("2024"))));
+        let mut set = typed_field("SET", None, Some("'a,b'"));
+        set.values = Some(vec!["a".to_string(), "b".to_string()]);
+        assert!(generic.check_default(&set));
+        set.default = Some("'a,c'".to_string());
+        assert!(!generic.check_default(&set));
+    }
+}
@@ -0,0 +1,28 @@
+mod catalogue;
+pub use catalogue::*;
+use actix_web::{get, web};
+use crate::common::{CommonResponse, ResponseCode, ResponseMessage};
+use crate::error::DrawDBError;
+
+/// 数据类型模块
+pub fn datatypes_routes(config: &mut web::ServiceConfig) {
+    config.service(query_datatypes);
+}
+
+/// 查询数据库支持的数据类型
+/// database与diagram.database的取值一致，generic返回通用类型
+#[get("/{database}")]
+async fn query_datatypes(
+    database: web::Path<String>
+) -> Result<CommonResponse, DrawDBError> {
+    let database = database.into_inner();
+    let data_types = match database.as_str() {
+        "generic" => DataTypes::new(None),
+        database => DataTypes::new(Some(database.parse()?)),
+    };
+    Ok(CommonResponse::new(
+        ResponseCode::Success,
+        ResponseMessage::Success,
+        Some(serde_json::to_value(data_types.all()).unwrap()),
+    ))
+}
@@ -0,0 +1,274 @@
+use std::path::{Path, PathBuf};
+use actix_web::{post, web};
+use sea_orm::{ConnectionTrait, DatabaseConnection, DbBackend, Statement, TransactionTrait};
+use serde::Deserialize;
+use sqlx::sqlite::SqliteConnectOptions;
+use crate::common::{CommonResponse, ResponseCode, ResponseMessage};
+use crate::diagrams::query_diagram_detail;
+use crate::entity::vo::{DeployVo, DiagramVo, ReferenceVo, TableVo};
+use crate::error::DrawDBError;
+use crate::export::{migration_statements, Dialect};
+use crate::import::{connect_sqlite, introspect_sqlite};
+use crate::init::DeployConfig;
+
+/// 部署模块，挂载在/diagrams下
+pub fn deploy_routes(config: &mut web::ServiceConfig) {
+    config.service(deploy);
+}
+
+/// 部署参数
+#[derive(Debug, Deserialize)]
+pub struct DeployQuery {
+    // 目标SQLite数据库文件的路径，必须在config.toml的部署白名单中
+    pub path: String,
+    // 为true时只返回语句，不执行
+    pub dry_run: Option<bool>,
+    // 为true时删除数据库中有而图表中没有的表，默认保留
+    pub drop_tables: Option<bool>,
+}
+
+/// 将图表的结构部署到SQLite数据库
+/// 对比数据库的现有结构与图表，在事务中执行生成的DDL，数据库文件不存在时创建
+/// 图表中没有的表默认保留，?drop_tables=true时删除
+#[post("/{id}/deploy")]
+async fn deploy(
+    db: web::Data<DatabaseConnection>,
+    deploy_config: web::Data<DeployConfig>,
+    id: web::Path<String>,
+    query: web::Query<DeployQuery>
+) -> Result<CommonResponse, DrawDBError> {
+    let query = query.into_inner();
+    let path = resolve_target(&query.path, &deploy_config.allowed_paths)?;
+    let Some(diagram) = query_diagram_detail(db.get_ref(), id.into_inner()).await? else {
+        return Ok(CommonResponse::new(
+            ResponseCode::NotFound,
+            ResponseMessage::NotFound,
+            None,
+        ));
+    };
+    let result = deploy_diagram(&diagram, &path, query.dry_run.unwrap_or(false), query.drop_tables.unwrap_or(false)).await?;
+    Ok(CommonResponse::new(
+        ResponseCode::Success,
+        ResponseMessage::Success,
+        Some(serde_json::to_value(result).unwrap()),
+    ))
+}
+
+/// 对比目标数据库与图表的结构并执行迁移语句
+/// 表和字段按名称匹配，数据库中有而图表中没有的表只在drop_tables为true时删除
+pub async fn deploy_diagram(diagram: &DiagramVo, path: &Path, dry_run: bool, drop_tables: bool) -> Result<DeployVo, DrawDBError> {
+    //1、读取现有结构，预览时不创建数据库文件
+    let exists = path.exists();
+    let target = match (exists, dry_run) {
+        (false, true) => None,
+        _ => {
+            let options = SqliteConnectOptions::new().filename(path).read_only(dry_run).create_if_missing(!dry_run);
+            Some(connect_sqlite(options).await?)
+        }
+    };
+    let (mut tables, mut references) = match &target {
+        Some(target) => introspect_sqlite(target).await?,
+        None => (Vec::new(), Vec::new()),
+    };
+    if !drop_tables {
+        retain_modelled_tables(&mut tables, &mut references, diagram);
+    }
+    let live = DiagramVo {
+        id: String::new(),
+        zoom: None,
+        database: Some(Dialect::Sqlite.database().to_string()),
+        name: None,
+        tables: Some(tables),
+        areas: None,
+        references: Some(references),
+        indices: None,
+        notes: None,
+        tasks: None,
+        pan: None,
+        last_modified: None,
+        types: None,
+        enums: None,
+    };
+    let statements = migration_statements(&live, diagram, Dialect::Sqlite);
+    let result = DeployVo {
+        path: path.display().to_string(),
+        dry_run,
+        statements,
+    };
+    let Some(target) = target else {
+        return Ok(result);
+    };
+    if dry_run || result.statements.is_empty() {
+        target.close().await?;
+        return Ok(result);
+    }
+
+    //2、关闭外键检查后在事务中执行，提交前检查外键约束
+    target.execute_unprepared("PRAGMA foreign_keys = OFF").await?;
+    let tx = target.begin().await?;
+    for statement in result.statements.iter() {
+        tx.execute_unprepared(statement).await?;
+    }
+    let violations = tx.query_all(Statement::from_string(DbBackend::Sqlite, "PRAGMA foreign_key_check")).await?;
+    if !violations.is_empty() {
+        tx.rollback().await?;
+        return Err(DrawDBError::ValidationError(format!("部署后有{}行数据违反外键约束，已回滚", violations.len())));
+    }
+    tx.commit().await?;
+    target.execute_unprepared("PRAGMA foreign_keys = ON").await?;
+    target.close().await?;
+    Ok(result)
+}
+
+/// 从现有结构中去掉图表中没有的表以及这些表上的外键，迁移时不会删除这些表
+/// SQLite的表名不区分大小写
+fn retain_modelled_tables(tables: &mut Vec<TableVo>, references: &mut Vec<ReferenceVo>, diagram: &DiagramVo) {
+    let modelled = diagram.tables.iter().flatten()
+    .filter_map(|table| table.name.as_deref())
+    .collect::<Vec<&str>>();
+    tables.retain(|table| table.name.as_deref().is_some_and(|name| modelled.iter().any(|modelled| modelled.eq_ignore_ascii_case(name))));
+    references.retain(|reference| [&reference.start_table_id, &reference.end_table_id].iter()
+        .all(|table_id| tables.iter().any(|table| Some(&table.id) == table_id.as_ref())));
+}
+
+/// 校验目标路径在白名单中，返回绝对路径
+/// 白名单中的目录允许部署到目录下的任意文件，从SQLite文件导入时同样使用该白名单
+pub(crate) fn resolve_target(path: &str, allowed_paths: &[String]) -> Result<PathBuf, DrawDBError> {
+    let target = absolute(Path::new(path))
+    .ok_or_else(|| DrawDBError::ValidationError(format!("数据库路径无效或目录不存在: {}", path)))?;
+    let allowed = allowed_paths.iter()
+    .filter_map(|allowed| absolute(Path::new(allowed)))
+    .any(|allowed| target == allowed || (allowed.is_dir() && target.starts_with(&allowed)));
+    if !allowed {
+        return Err(DrawDBError::ValidationError(format!("数据库路径不在白名单中: {}", path)));
+    }
+    Ok(target)
+}
+
+/// 存在的路径取规范路径，不存在的文件取所在目录的规范路径加文件名
+fn absolute(path: &Path) -> Option<PathBuf> {
+    if let Ok(path) = path.canonicalize() {
+        return Some(path);
+    }
+    let file_name = path.file_name()?;
+    let parent = path.parent().filter(|parent| !parent.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    Some(parent.canonicalize().ok()?.join(file_name))
+}
+
+#[cfg(test)]
+mod test {
+    use actix_web::{test, App};
+    use sea_orm::{Database, QueryResult};
+    use super::*;
+    use crate::entity::vo::DiagramSaveVo;
+    use crate::entity::vo::fixtures::{build_diagram, build_primary_field, build_table};
+    use crate::import::import_routes;
+    use crate::init::init_test_db;
+
+    async fn query_values(path: &Path, sql: &str) -> Vec<QueryResult> {
+        let conn = connect_sqlite(SqliteConnectOptions::new().filename(path).read_only(true)).await.unwrap();
+        let rows = conn.query_all(Statement::from_string(DbBackend::Sqlite, sql)).await.unwrap();
+        conn.close().await.unwrap();
+        rows
+    }
+
+    /// 预览、部署、重复部署没有变化，修改字段后重建表并保留数据
+    #[actix_web::test]
+    async fn test_deploy() {
+        let dir = std::env::temp_dir().join(format!("drawdb_deploy_{}", crate::next_id()));
+        std::fs::create_dir(&dir).unwrap();
+        let target = dir.join("shop.sqlite");
+        let db = init_test_db().await;
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(db))
+                .app_data(web::Data::new(DeployConfig { allowed_paths: vec![dir.to_string_lossy().to_string()] }))
+                .configure(import_routes)
+                .configure(deploy_routes)
+        ).await;
+        let sql = r#"
+            CREATE TABLE "users" (
+                "id" INTEGER PRIMARY KEY AUTOINCREMENT,
+                "email" VARCHAR(255) NOT NULL UNIQUE DEFAULT 'nobody',
+                "age" INTEGER CHECK(age>0)
+            );
+            CREATE TABLE "orders" (
+                "id" INTEGER NOT NULL,
+                "user_id" INTEGER REFERENCES "users" ("id") ON DELETE CASCADE,
+                "price" NUMERIC(10,2),
+                PRIMARY KEY ("id")
+            );
+            CREATE INDEX "idx_orders_user" ON "orders" ("user_id");
+        "#;
+        let req = test::TestRequest::post().uri("/import/sql?dialect=sqlite").set_payload(sql).to_request();
+        let resp: CommonResponse = test::call_and_read_body_json(&app, req).await;
+        let saved: DiagramSaveVo = serde_json::from_value(resp.data.unwrap()).unwrap();
+        let id = saved.diagram.id.clone();
+        let request = |dry_run: bool| test::TestRequest::post()
+            .uri(&format!("/{}/deploy?path={}&dry_run={}", id, target.display(), dry_run))
+            .to_request();
+
+        //1、预览不创建数据库文件
+        let resp: CommonResponse = test::call_and_read_body_json(&app, request(true)).await;
+        let result: DeployVo = serde_json::from_value(resp.data.unwrap()).unwrap();
+        assert!(result.dry_run);
+        assert_eq!(result.statements.len(), 2);
+        assert!(!target.exists());
+
+        //2、部署后再次部署没有变化
+        let resp: CommonResponse = test::call_and_read_body_json(&app, request(false)).await;
+        assert_eq!(resp.code, 200);
+        let rows = query_values(&target, "SELECT name FROM sqlite_master WHERE type IN ('table', 'index') AND name NOT LIKE 'sqlite_%' ORDER BY name").await;
+        let names = rows.iter().map(|row| row.try_get::<String>("", "name").unwrap()).collect::<Vec<String>>();
+        assert_eq!(names, vec!["idx_orders_user", "orders", "users"]);
+        let resp: CommonResponse = test::call_and_read_body_json(&app, request(false)).await;
+        let result: DeployVo = serde_json::from_value(resp.data.unwrap()).unwrap();
+        assert_eq!(result.statements, Vec::<String>::new());
+
+        //3、修改字段类型并删除列，重建表后数据保留
+        let conn = Database::connect(format!("sqlite://{}", target.display())).await.unwrap();
+        conn.execute_unprepared("INSERT INTO users (id, email, age) VALUES (1, 'a@b.c', 20); INSERT INTO orders (id, user_id) VALUES (1, 1);").await.unwrap();
+        conn.close().await.unwrap();
+        let mut diagram = saved.diagram.clone();
+        let users = diagram.tables.as_mut().unwrap().iter_mut().find(|table| table.name.as_deref() == Some("users")).unwrap();
+        users.fields.as_mut().unwrap().retain(|field| field.name.as_deref() != Some("age"));
+        users.fields.as_mut().unwrap()[1].size = Some(320);
+        let result = deploy_diagram(&diagram, &target, false, false).await.unwrap();
+        assert!(result.statements.iter().any(|statement| statement.starts_with("CREATE TABLE IF NOT EXISTS \"_new_users\"")));
+        let rows = query_values(&target, "SELECT u.email FROM users u JOIN orders o ON o.user_id = u.id").await;
+        assert_eq!(rows[0].try_get::<String>("", "email").unwrap(), "a@b.c");
+        assert!(deploy_diagram(&diagram, &target, true, false).await.unwrap().statements.is_empty());
+
+        //4、白名单之外的路径
+        let req = test::TestRequest::post().uri(&format!("/{}/deploy?path=other.sqlite", id)).to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 400);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    /// 数据库中有而图表中没有的表默认保留，drop_tables为true时删除
+    /// 文件名中的?、#、%不影响打开数据库
+    #[actix_web::test]
+    async fn test_deploy_keeps_unmodelled_tables() {
+        let dir = std::env::temp_dir().join(format!("drawdb_deploy_{}", crate::next_id()));
+        std::fs::create_dir(&dir).unwrap();
+        let target = dir.join("shop?mode=ro#%20.sqlite");
+        let conn = connect_sqlite(SqliteConnectOptions::new().filename(&target).create_if_missing(true)).await.unwrap();
+        conn.execute_unprepared("CREATE TABLE \"legacy\" (\"id\" INTEGER PRIMARY KEY); INSERT INTO legacy (id) VALUES (1);").await.unwrap();
+        conn.close().await.unwrap();
+        let diagram = build_diagram(vec![build_table("t1", "users", vec![build_primary_field("u1", "id", "INTEGER")], vec![])], vec![]);
+
+        let result = deploy_diagram(&diagram, &target, false, false).await.unwrap();
+        assert!(result.statements.iter().all(|statement| !statement.contains("DROP TABLE")));
+        let rows = query_values(&target, "SELECT id FROM legacy").await;
+        assert_eq!(rows.len(), 1);
+        let rows = query_values(&target, "SELECT name FROM sqlite_master WHERE type = 'table' AND name = 'users'").await;
+        assert_eq!(rows.len(), 1);
+
+        let result = deploy_diagram(&diagram, &target, false, true).await.unwrap();
+        assert_eq!(result.statements, vec!["DROP TABLE \"legacy\";\n".to_string()]);
+        let rows = query_values(&target, "SELECT name FROM sqlite_master WHERE type = 'table' AND name = 'legacy'").await;
+        assert!(rows.is_empty());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,359 @@
+use std::collections::{HashMap, HashSet};
+use sea_orm::{ActiveModelTrait, ColumnTrait, Condition, ConnectionTrait, DatabaseTransaction, EntityTrait, QueryFilter, QueryOrder};
+use crate::areas::{add_area, delete_area, query_areas, update_area};
+use crate::entity::{area, diagram_link, diagram_version, field, indice, indice_link, note, prelude::*, reference, table, table_link, task};
+use crate::entity::vo::{AreaVo, DiagramDeleteVo, DiagramSaveVo, DiagramVo, IdMappingVo, IndiceVo, NoteVo, ReferenceVo, TableVo, TaskVo};
+use crate::error::DrawDBError;
+use crate::next_id;
+use crate::notes::{add_note, delete_note, query_notes, update_note};
+use crate::references::{add_references, delete_references, query_references, update_references};
+use crate::tables::{add_table, delete_table, query_tables, update_table};
+
+/// 查询图表的完整结构
+/// 包含表、字段、关联关系、主题区域、注释、索引和代办事项
+pub async fn query_diagram_detail<C: ConnectionTrait>(
+    conn: &C,
+    diagram_id: String
+) -> Result<Option<DiagramVo>, DrawDBError> {
+    //1、查询图表本身
+    let Some(diagram) = Diagram::find_by_id(diagram_id.clone()).one(conn).await? else {
+        return Ok(None);
+    };
+    let mut diagram_vo = DiagramVo::from(&diagram);
+    //2、查询表和字段
+    let tables = query_tables(conn, diagram_id.clone()).await?;
+    //3、汇总各表的索引
+    let indices = tables.iter()
+    .flat_map(|table| table.indices.iter().flatten())
+    .cloned()
+    .collect::<Vec<IndiceVo>>();
+    //4、查询关联关系、主题区域、注释
+    let references = query_references(conn, diagram_id.clone()).await?;
+    let areas = query_areas(conn, diagram_id.clone()).await?;
+    let notes = query_notes(conn, diagram_id.clone()).await?;
+    //5、查询代办事项
+    let tasks = Task::find()
+    .inner_join(DiagramLink)
+    .filter(diagram_link::Column::DiagramId.eq(diagram_id.clone()))
+    .order_by_asc(task::Column::Order)
+    .all(conn)
+    .await?
+    .iter()
+    .map(|task| TaskVo::from_option(task, diagram_id.clone()))
+    .collect::<Vec<TaskVo>>();
+
+    diagram_vo.tables = Some(tables);
+    diagram_vo.indices = Some(indices);
+    diagram_vo.references = Some(references);
+    diagram_vo.areas = Some(areas);
+    diagram_vo.notes = Some(notes);
+    diagram_vo.tasks = Some(tasks);
+    Ok(Some(diagram_vo))
+}
+
+/// 按id对比出的新增、更新、删除集合
+#[derive(Debug)]
+pub struct ChangeSet<T> {
+    pub inserts: Vec<T>,
+    pub updates: Vec<T>,
+    pub deletes: Vec<T>,
+}
+
+/// 按id对比已存储的数据与提交的数据
+/// 只有内容发生变化的数据才会进入更新集合
+pub fn diff_by_id<T, F>(origin: &[T], incoming: &[T], id: F) -> ChangeSet<T>
+where
+    T: Clone + PartialEq,
+    F: Fn(&T) -> &String,
+{
+    let origin_map: HashMap<&String, &T> = origin.iter().map(|item| (id(item), item)).collect();
+    let incoming_ids: HashSet<&String> = incoming.iter().map(&id).collect();
+    let mut change_set = ChangeSet { inserts: vec![], updates: vec![], deletes: vec![] };
+    for item in incoming {
+        match origin_map.get(id(item)) {
+            None => change_set.inserts.push(item.clone()),
+            Some(origin_item) if *origin_item != item => change_set.updates.push(item.clone()),
+            Some(_) => {}
+        }
+    }
+    change_set.deletes = origin.iter()
+    .filter(|item| !incoming_ids.contains(id(item)))
+    .cloned()
+    .collect();
+    change_set
+}
+
+/// 批量新增图表与元素的关联关系
+/// column: 元素在diagram_link中对应的列，如table_id、note_id
+pub async fn add_diagram_links(
+    tx: &DatabaseTransaction,
+    diagram_id: &str,
+    column: diagram_link::Column,
+    ids: Vec<String>
+) -> Result<(), DrawDBError> {
+    if ids.is_empty() {
+        return Ok(());
+    }
+    let link_ams = ids.into_iter().map(|id| {
+        let link = diagram_link::Model::new(next_id(), Some(diagram_id.to_string()), None, None, None, None, None);
+        let mut link_am = diagram_link::ActiveModel::from(link);
+        link_am.set(column, Some(id).into());
+        link_am
+    }).collect::<Vec<diagram_link::ActiveModel>>();
+    DiagramLink::insert_many(link_ams).exec(tx).await?;
+    Ok(())
+}
+
+/// 批量删除图表与元素的关联关系
+pub async fn delete_diagram_links(
+    tx: &DatabaseTransaction,
+    column: diagram_link::Column,
+    ids: Vec<String>
+) -> Result<(), DrawDBError> {
+    DiagramLink::delete_many().filter(column.is_in(ids)).exec(tx).await?;
+    Ok(())
+}
+
+/// 保存图表的完整结构
+/// 将提交的DiagramVo与已存储的结构按id对比，在同一个事务中完成表、字段、关联关系、主题区域、注释和索引的增删改
+/// 索引随TableVo.indices保存，DiagramVo.indices仅用于查询
+/// 为None的集合保持不变，代办事项通过/todos维护
+/// 新增的表、字段、关联关系、主题区域和注释使用后端生成的id，返回前端id与后端id的映射
+/// 删除表时同时删除引用该表的关联关系，提交的关联关系仍引用被删除的表时不保存
+/// 图表不存在时返回None
+pub async fn save_diagram_detail(
+    tx: &DatabaseTransaction,
+    diagram_vo: DiagramVo
+) -> Result<Option<DiagramSaveVo>, DrawDBError> {
+    let diagram_id = diagram_vo.id.clone();
+    //1、查询已存储的结构
+    let Some(origin) = query_diagram_detail(tx, diagram_id.clone()).await? else {
+        return Ok(None);
+    };
+    //2、更新图表本身
+    diagram_vo.convert_to_active_model().update(tx).await?;
+    let mut id_mapping = IdMappingVo::default();
+    //3、先删除被移除的关联关系并更新修改的关联关系，使表更新时可以删除不再被引用的字段
+    let reference_changes = diagram_vo.references.as_ref().map(|references| {
+        diff_by_id(origin.references.as_deref().unwrap_or_default(), references, |reference| &reference.id)
+    });
+    if let Some(changes) = &reference_changes {
+        if !changes.deletes.is_empty() {
+            let ids = changes.deletes.iter().map(|reference| reference.id.clone()).collect();
+            delete_diagram_links(tx, diagram_link::Column::ReferenceId, ids).await?;
+            delete_references(tx, changes.deletes.clone()).await?;
+        }
+        update_references(tx, changes.updates.clone()).await?;
+    }
+    //4、表和字段
+    if let Some(tables) = &diagram_vo.tables {
+        // 统一归属关系，避免仅因diagram_id、table_id缺省而被判定为更新
+        let tables = tables.iter().map(|table| {
+            let mut table = table.clone();
+            table.diagram_id = diagram_id.clone();
+            if let Some(fields) = table.fields.as_mut() {
+                fields.iter_mut().for_each(|field| field.table_id = Some(table.id.clone()));
+            }
+            table
+        }).collect::<Vec<TableVo>>();
+        let changes = diff_by_id(origin.tables.as_deref().unwrap_or_default(), &tables, |table| &table.id);
+        let deleted_table_ids = changes.deletes.iter().map(|table| table.id.clone()).collect::<Vec<String>>();
+        delete_table_references(tx, diagram_vo.references.as_deref(), &deleted_table_ids).await?;
+        for table in changes.deletes {
+            delete_table(tx, table.id).await?;
+        }
+        for table in changes.updates {
+            id_mapping.merge(update_table(tx, table).await?);
+        }
+        for table in changes.inserts {
+            id_mapping.merge(add_table(tx, table).await?);
+        }
+    }
+    //5、新增关联关系，引用新增表和字段的前端id替换为后端id，已更新的关联关系引用了新增表或字段时再次更新
+    if let Some(changes) = reference_changes {
+        let updates = changes.updates.into_iter().filter_map(|reference| {
+            let mut remapped = reference.clone();
+            id_mapping.apply_to_reference(&mut remapped);
+            (remapped != reference).then_some(remapped)
+        }).collect();
+        update_references(tx, updates).await?;
+        if !changes.inserts.is_empty() {
+            let inserts = changes.inserts.into_iter().map(|mut reference| {
+                id_mapping.apply_to_reference(&mut reference);
+                id_mapping.assign_reference_id(&mut reference, next_id());
+                reference
+            }).collect::<Vec<ReferenceVo>>();
+            let ids = inserts.iter().map(|reference| reference.id.clone()).collect();
+            add_references(tx, inserts).await?;
+            add_diagram_links(tx, &diagram_id, diagram_link::Column::ReferenceId, ids).await?;
+        }
+    }
+    //6、主题区域
+    if let Some(areas) = &diagram_vo.areas {
+        let changes = diff_by_id(origin.areas.as_deref().unwrap_or_default(), areas, |area| &area.id);
+        for area in changes.deletes {
+            delete_area(tx, area.id).await?;
+        }
+        for area in changes.updates {
+            update_area(tx, area).await?;
+        }
+        for area in changes.inserts {
+            let id = next_id();
+            id_mapping.areas.insert(area.id.clone(), id.clone());
+            add_area(tx, diagram_id.clone(), AreaVo { id, ..area }).await?;
+        }
+    }
+    //7、注释
+    if let Some(notes) = &diagram_vo.notes {
+        let changes = diff_by_id(origin.notes.as_deref().unwrap_or_default(), notes, |note| &note.id);
+        for note in changes.deletes {
+            delete_note(tx, note.id).await?;
+        }
+        for note in changes.updates {
+            update_note(tx, note).await?;
+        }
+        for note in changes.inserts {
+            let id = next_id();
+            id_mapping.notes.insert(note.id.clone(), id.clone());
+            add_note(tx, diagram_id.clone(), NoteVo { id, ..note }).await?;
+        }
+    }
+    //8、返回保存后的完整结构以及id映射
+    let diagram = query_diagram_detail(tx, diagram_id).await?;
+    Ok(diagram.map(|diagram| DiagramSaveVo { diagram, id_mapping }))
+}
+
+/// 删除引用被删除表的关联关系以及图表与关联关系的关联
+/// references为提交的关联关系，其中仍引用被删除的表时返回校验错误
+async fn delete_table_references(
+    tx: &DatabaseTransaction,
+    references: Option<&[ReferenceVo]>,
+    deleted_table_ids: &[String]
+) -> Result<(), DrawDBError> {
+    if deleted_table_ids.is_empty() {
+        return Ok(());
+    }
+    let references_deleted = |start: &Option<String>, end: &Option<String>| [start, end].iter()
+    .any(|table_id| table_id.as_ref().is_some_and(|table_id| deleted_table_ids.contains(table_id)));
+    if let Some(reference) = references.unwrap_or_default().iter()
+        .find(|reference| references_deleted(&reference.start_table_id, &reference.end_table_id)) {
+        return Err(DrawDBError::ValidationError(format!(
+            "关联关系{}引用了被删除的表",
+            reference.name.clone().unwrap_or(reference.id.clone())
+        )));
+    }
+    let ids = Reference::find()
+    .filter(
+        Condition::any()
+        .add(reference::Column::StartTableId.is_in(deleted_table_ids.to_vec()))
+        .add(reference::Column::EndTableId.is_in(deleted_table_ids.to_vec()))
+    )
+    .all(tx)
+    .await?
+    .into_iter()
+    .map(|reference| reference.id)
+    .collect::<Vec<String>>();
+    if !ids.is_empty() {
+        delete_diagram_links(tx, diagram_link::Column::ReferenceId, ids.clone()).await?;
+        Reference::delete_many().filter(reference::Column::Id.is_in(ids)).exec(tx).await?;
+    }
+    Ok(())
+}
+
+/// 级联删除图表
+/// 通过diagram_link、table_link、indice_link找到图表拥有的全部数据并在同一个事务中删除
+/// 版本记录默认保留，purge_versions为true时一并删除
+/// 图表不存在时返回None
+pub async fn delete_diagram_detail(
+    tx: &DatabaseTransaction,
+    diagram_id: String,
+    purge_versions: bool
+) -> Result<Option<DiagramDeleteVo>, DrawDBError> {
+    if Diagram::find_by_id(diagram_id.clone()).one(tx).await?.is_none() {
+        return Ok(None);
+    }
+    let mut result = DiagramDeleteVo { id: diagram_id.clone(), ..Default::default() };
+    //1、查询图表的所有关联关系
+    let links = DiagramLink::find()
+    .filter(diagram_link::Column::DiagramId.eq(diagram_id.clone()))
+    .all(tx)
+    .await?;
+    let collect_ids = |get: fn(&diagram_link::Model) -> &Option<String>| {
+        links.iter().filter_map(|link| get(link).clone()).collect::<Vec<String>>()
+    };
+    let table_ids = collect_ids(|link| &link.table_id);
+    let reference_ids = collect_ids(|link| &link.reference_id);
+    let note_ids = collect_ids(|link| &link.note_id);
+    let area_ids = collect_ids(|link| &link.area_id);
+    let task_ids = collect_ids(|link| &link.task_id);
+    //2、查询表拥有的字段
+    let field_ids = TableLink::find()
+    .filter(table_link::Column::TableId.is_in(table_ids.clone()))
+    .all(tx)
+    .await?
+    .into_iter()
+    .filter_map(|link| link.field_id)
+    .collect::<Vec<String>>();
+    //3、删除表和字段上的索引
+    let mut indice_ids = IndiceLink::find()
+    .filter(indice_link::Column::FieldId.is_in(field_ids.clone()))
+    .all(tx)
+    .await?
+    .into_iter()
+    .filter_map(|link| link.indice_id)
+    .collect::<Vec<String>>();
+    indice_ids.extend(Indice::find()
+    .filter(indice::Column::TableId.is_in(table_ids.clone()))
+    .all(tx)
+    .await?
+    .into_iter()
+    .map(|indice| indice.id));
+    indice_ids.sort();
+    indice_ids.dedup();
+    result.indice_links = IndiceLink::delete_many()
+    .filter(indice_link::Column::IndiceId.is_in(indice_ids.clone()))
+    .exec(tx).await?.rows_affected;
+    result.indices = Indice::delete_many()
+    .filter(indice::Column::Id.is_in(indice_ids))
+    .exec(tx).await?.rows_affected;
+    //4、删除关联关系，包括未建立diagram_link但指向图表中表的关联关系
+    result.references = Reference::delete_many()
+    .filter(
+        Condition::any()
+        .add(reference::Column::Id.is_in(reference_ids))
+        .add(reference::Column::StartTableId.is_in(table_ids.clone()))
+        .add(reference::Column::EndTableId.is_in(table_ids.clone()))
+    )
+    .exec(tx).await?.rows_affected;
+    //5、删除字段、表与字段的关联关系、表
+    result.fields = Field::delete_many()
+    .filter(field::Column::Id.is_in(field_ids))
+    .exec(tx).await?.rows_affected;
+    result.table_links = TableLink::delete_many()
+    .filter(table_link::Column::TableId.is_in(table_ids.clone()))
+    .exec(tx).await?.rows_affected;
+    result.tables = Table::delete_many()
+    .filter(table::Column::Id.is_in(table_ids))
+    .exec(tx).await?.rows_affected;
+    //6、删除注释、主题区域、代办事项
+    result.notes = Note::delete_many()
+    .filter(note::Column::Id.is_in(note_ids))
+    .exec(tx).await?.rows_affected;
+    result.areas = Area::delete_many()
+    .filter(area::Column::Id.is_in(area_ids))
+    .exec(tx).await?.rows_affected;
+    result.tasks = Task::delete_many()
+    .filter(task::Column::Id.is_in(task_ids))
+    .exec(tx).await?.rows_affected;
+    //7、删除图表的版本、关联关系和图表本身
+    if purge_versions {
+        result.versions = DiagramVersion::delete_many()
+        .filter(diagram_version::Column::DiagramId.eq(diagram_id.clone()))
+        .exec(tx).await?.rows_affected;
+    }
+    result.diagram_links = DiagramLink::delete_many()
+    .filter(diagram_link::Column::DiagramId.eq(diagram_id.clone()))
+    .exec(tx).await?.rows_affected;
+    Diagram::delete_by_id(diagram_id).exec(tx).await?;
+    Ok(Some(result))
+}
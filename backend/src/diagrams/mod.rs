@@ -1,18 +1,24 @@
+mod internal_api;
+pub use internal_api::*;
 use actix_web::{delete, post};
 use actix_web::{get, web};
 use sea_orm::{ActiveModelTrait, DatabaseConnection, TransactionTrait};
 use sea_orm::EntityTrait;
 use crate::common::ResponseCode;
 use crate::common::ResponseMessage;
-use crate::entity::diagram::{self, ActiveModel};
+use crate::entity::diagram::ActiveModel;
 use crate::entity::prelude::*;
 use crate::entity::vo::DiagramVo;
 use crate::next_id;
+use crate::validate::{has_errors, validate_diagram};
+use crate::versions::add_diagram_version;
 use crate::{common::CommonResponse, error::DrawDBError};
+use serde::Deserialize;
 
 /// 图表模块
 pub fn diagrams_routes(config: &mut web::ServiceConfig) {
     config.service(query_all_diagrams);
+    config.service(query_diagram);
     config.service(add_diagram);
     config.service(update_diagram);
     config.service(delete_diagram);
@@ -27,7 +33,7 @@ async fn query_all_diagrams(
     let diagrams = Diagram::find().all(conn).await?;
     let diagram_vos:Vec<DiagramVo> = diagrams
     .iter()
-    .map(|diagram| DiagramVo::from(diagram)).collect();
+    .map(DiagramVo::from).collect();
     Ok(CommonResponse::new(
         ResponseCode::Success,
         ResponseMessage::Success,
@@ -36,6 +42,7 @@ async fn query_all_diagrams(
 }
 
 /// 查询图表
+/// 返回图表及其关联的表、字段、关联关系、主题区域、注释、索引和代办事项
 #[get("/query/{id}")]
 async fn query_diagram(
     db: web::Data<DatabaseConnection>,
@@ -43,11 +50,17 @@ async fn query_diagram(
 ) -> Result<CommonResponse, DrawDBError> {
     let conn = db.get_ref();
     let id = id.into_inner();
-    let diagram = Diagram::find_by_id(id).one(conn).await?;
+    let Some(diagram_vo) = query_diagram_detail(conn, id).await? else {
+        return Ok(CommonResponse::new(
+            ResponseCode::NotFound,
+            ResponseMessage::NotFound,
+            None,
+        ));
+    };
     Ok(CommonResponse::new(
         ResponseCode::Success,
         ResponseMessage::Success,
-        Some(serde_json::to_value(diagram).unwrap()),
+        Some(serde_json::to_value(diagram_vo).unwrap()),
     ))
 }
 
@@ -73,56 +86,578 @@ async fn add_diagram(
           Some(serde_json::to_value(result).unwrap())))
 }
 
+/// 更新图表的参数
+#[derive(Debug, Deserialize)]
+pub struct UpdateQuery {
+    // 为true时先校验图表结构，存在error级别的问题时不保存
+    pub validate: Option<bool>,
+    // 保存人，记录在版本中
+    pub author: Option<String>,
+    // 版本说明
+    pub message: Option<String>,
+}
+
 ///更新图表
+/// 提交完整的DiagramVo，与已存储的结构对比后在同一个事务中保存
+/// 开启校验且存在错误时返回400以及问题列表，保存成功时记录新版本
 #[post("/update")]
 async fn update_diagram(
     db: web::Data<DatabaseConnection>,
+    query: web::Query<UpdateQuery>,
     diagram: web::Json<DiagramVo>
 ) -> Result<CommonResponse, DrawDBError>{
+    let diagram = diagram.into_inner();
     //开启事务
     let tx = db.begin().await?;
-    let diagram_model = diagram.convert_to_active_model();
-    let result = diagram_model.update(&tx).await?;
-    // TODO：
-    // 1、删除与表的关联关系
-    // 2、删除与引用的关联关系
-    // 3、重新构建与表的关联关系
-    // 4、重新构建与引用的关联关系
-    // 5、更新图表
-    // 6、更新引用
+    if query.validate.unwrap_or(false) {
+        // 未提交的集合沿用已存储的内容
+        if let Some(origin) = query_diagram_detail(&tx, diagram.id.clone()).await? {
+            let merged = DiagramVo {
+                tables: diagram.tables.clone().or(origin.tables),
+                references: diagram.references.clone().or(origin.references),
+                types: diagram.types.clone().or(origin.types),
+                enums: diagram.enums.clone().or(origin.enums),
+                ..origin
+            };
+            let issues = validate_diagram(&merged);
+            if has_errors(&issues) {
+                tx.rollback().await?;
+                return Ok(CommonResponse::new(ResponseCode::Failed,
+                    ResponseMessage::Default("图表结构存在错误，未保存".to_string()),
+                    Some(serde_json::to_value(issues).unwrap())));
+            }
+        }
+    }
+    let Some(result) = save_diagram_detail(&tx, diagram).await? else {
+        tx.rollback().await?;
+        return Ok(CommonResponse::new(ResponseCode::NotFound,
+            ResponseMessage::NotFound,
+            None));
+    };
+    let query = query.into_inner();
+    add_diagram_version(&tx, &result.diagram, query.author, query.message).await?;
     tx.commit().await?;
     Ok(CommonResponse::new(ResponseCode::Success,
         ResponseMessage::Success,
          Some(serde_json::to_value(result).unwrap())))
 }
 
+/// 删除图表的参数
+#[derive(Debug, Deserialize)]
+pub struct DeleteQuery {
+    // 为true时同时删除图表的版本记录，默认保留
+    pub purge_versions: Option<bool>,
+}
+
 ///删除图表
-#[delete("/detele/{id}")]
+/// 级联删除图表拥有的表、字段、索引、关联关系、注释、主题区域和代办事项，返回各类数据的删除数量
+/// 版本记录默认保留，?purge_versions=true时一并删除
+#[delete("/delete/{id}")]
 async fn delete_diagram(
     db: web::Data<DatabaseConnection>,
-    id: web::Path<String>
+    id: web::Path<String>,
+    query: web::Query<DeleteQuery>
 )->Result<CommonResponse, DrawDBError>{
     let tx = db.begin().await?;
     let id = id.into_inner();
-    Diagram::delete_by_id(&id).exec(&tx).await?;
+    let Some(result) = delete_diagram_detail(&tx, id, query.purge_versions.unwrap_or(false)).await? else {
+        tx.rollback().await?;
+        return Ok(CommonResponse::new(ResponseCode::NotFound,
+            ResponseMessage::NotFound,
+            None));
+    };
     tx.commit().await?;
     Ok(CommonResponse::new(ResponseCode::Success,
         ResponseMessage::Success,
-         Some(serde_json::to_value(id).unwrap())))
+         Some(serde_json::to_value(result).unwrap())))
 }
 
 
 #[cfg(test)]
 mod tests{
     use super::*;
-    use std::collections::HashMap;
-    use itertools::{self, Itertools};
-    use crate::entity::{prelude::*, table, task,vo::TableVo,vo::TaskVo};
-    use sea_orm::{Database, PaginatorTrait, QueryTrait, Related};
+    use actix_web::{test, App};
+    use itertools::Itertools;
+    use crate::entity::{area, diagram, diagram_link, field, indice, indice_link, note, reference, table, table_link, task, vo::build_table_link, vo::AreaVo, vo::DiagramDeleteVo, vo::NoteVo, vo::DiagramSaveVo, vo::IssueVo, vo::TableVo, vo::TaskVo};
+    use crate::init::init_test_db;
+    use sea_orm::{ColumnTrait, PaginatorTrait, QueryFilter};
+
+    /// 构建一个包含所有关联数据的图表
+    async fn seed_diagram(db: &DatabaseConnection) {
+        let link = |id: &str| diagram_link::Model::new(id.to_string(), Some("d1".to_string()), None, None, None, None, None);
+        Diagram::insert(diagram::ActiveModel::from(diagram::Model {
+            id: "d1".to_string(),
+            database: Some("generic".to_string()),
+            zoom: Some("1".to_string()),
+            name: Some("demo".to_string()),
+            pan: None,
+            last_modified: None,
+            types: None,
+            enums: None,
+        })).exec(db).await.unwrap();
+        Table::insert(table::ActiveModel::from(table::Model {
+            id: "t1".to_string(),
+            color: None,
+            comment: None,
+            locked: Some(false),
+            name: Some("users".to_string()),
+            x: Some("0".to_string()),
+            y: Some("0".to_string()),
+        })).exec(db).await.unwrap();
+        Field::insert_many(["f1", "f2"].iter().map(|id| field::ActiveModel::from(field::Model {
+            id: id.to_string(),
+            check: None,
+            comment: None,
+            default: None,
+            increment: Some(false),
+            not_null: Some(true),
+            primary: Some(*id == "f1"),
+            size: Some(255),
+            r#type: Some("VARCHAR".to_string()),
+            unique: Some(false),
+            name: Some(id.to_string()),
+            values: None,
+        }))).exec(db).await.unwrap();
+        TableLink::insert_many(["f1", "f2"].iter().map(|id| table_link::ActiveModel::from(
+            build_table_link(format!("tl_{}", id), "t1".to_string(), id.to_string())
+        ))).exec(db).await.unwrap();
+        Reference::insert(reference::ActiveModel::from(reference::Model {
+            id: "r1".to_string(),
+            cardinality: Some("one_to_one".to_string()),
+            delete_constraint: None,
+            end_field_id: Some("f1".to_string()),
+            end_table_id: Some("t1".to_string()),
+            name: Some("fk".to_string()),
+            start_field_id: Some("f2".to_string()),
+            start_table_id: Some("t1".to_string()),
+            update_constraint: None,
+        })).exec(db).await.unwrap();
+        Note::insert(note::ActiveModel::from(note::Model {
+            id: "n1".to_string(),
+            color: None,
+            content: Some("note".to_string()),
+            height: None,
+            title: Some("title".to_string()),
+            x: None,
+            y: None,
+        })).exec(db).await.unwrap();
+        Area::insert(area::ActiveModel::from(area::Model {
+            id: "a1".to_string(),
+            color: None,
+            height: None,
+            name: Some("area".to_string()),
+            width: None,
+            x: None,
+            y: None,
+        })).exec(db).await.unwrap();
+        Task::insert(task::ActiveModel::from(task::Model {
+            id: "k1".to_string(),
+            complete: Some(false),
+            order: Some(0),
+            details: None,
+            title: Some("todo".to_string()),
+        })).exec(db).await.unwrap();
+        Indice::insert(indice::ActiveModel::from(indice::Model {
+            id: "i1".to_string(),
+            name: Some("idx_f1".to_string()),
+            unique: Some(true),
+            table_id: Some("t1".to_string()),
+        })).exec(db).await.unwrap();
+        IndiceLink::insert(indice_link::ActiveModel::from(indice_link::Model {
+            id: "il1".to_string(),
+            field_id: Some("f1".to_string()),
+            indice_id: Some("i1".to_string()),
+            order: Some(0),
+        })).exec(db).await.unwrap();
+        DiagramLink::insert_many(vec![
+            diagram_link::Model { table_id: Some("t1".to_string()), ..link("l1") },
+            diagram_link::Model { reference_id: Some("r1".to_string()), ..link("l2") },
+            diagram_link::Model { note_id: Some("n1".to_string()), ..link("l3") },
+            diagram_link::Model { area_id: Some("a1".to_string()), ..link("l4") },
+            diagram_link::Model { task_id: Some("k1".to_string()), ..link("l5") },
+        ].into_iter().map(diagram_link::ActiveModel::from)).exec(db).await.unwrap();
+    }
+
+    #[actix_web::test]
+    async fn test_query_diagram(){
+        let db = init_test_db().await;
+        seed_diagram(&db).await;
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(db))
+                .configure(diagrams_routes)
+        ).await;
+        let req = test::TestRequest::get().uri("/query/d1").to_request();
+        let resp: CommonResponse = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(resp.code, 200);
+        let diagram: DiagramVo = serde_json::from_value(resp.data.unwrap()).unwrap();
+        let tables = diagram.tables.unwrap();
+        assert_eq!(tables.len(), 1);
+        assert_eq!(tables[0].fields.as_ref().unwrap().len(), 2);
+        assert_eq!(diagram.references.unwrap()[0].id, "r1");
+        assert_eq!(diagram.notes.unwrap()[0].id, "n1");
+        assert_eq!(diagram.areas.unwrap()[0].id, "a1");
+        assert_eq!(diagram.tasks.unwrap()[0].id, "k1");
+        assert_eq!(diagram.indices.unwrap()[0].id, "i1");
+
+        let req = test::TestRequest::get().uri("/query/missing").to_request();
+        let resp: CommonResponse = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(resp.code, 404);
+    }
+
+    #[actix_web::test]
+    async fn test_update_diagram(){
+        let db = init_test_db().await;
+        seed_diagram(&db).await;
+        let mut diagram = query_diagram_detail(&db, "d1".to_string()).await.unwrap().unwrap();
+        // 修改表名并新增一张表
+        let mut tables = diagram.tables.take().unwrap();
+        tables[0].name = Some("accounts".to_string());
+        let mut new_table = tables[0].clone();
+        new_table.id = "t2".to_string();
+        new_table.name = Some("orders".to_string());
+        tables.push(new_table);
+        diagram.tables = Some(tables);
+        diagram.name = Some("renamed".to_string());
+        // 删除关联关系和主题区域，更新注释
+        diagram.references = Some(vec![]);
+        diagram.areas = Some(vec![]);
+        diagram.notes.as_mut().unwrap()[0].content = Some("changed".to_string());
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(db.clone()))
+                .configure(diagrams_routes)
+        ).await;
+        let req = test::TestRequest::post().uri("/update").set_json(&diagram).to_request();
+        let resp: CommonResponse = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(resp.code, 200);
+
+        let saved = query_diagram_detail(&db, "d1".to_string()).await.unwrap().unwrap();
+        assert_eq!(saved.name, Some("renamed".to_string()));
+        let tables = saved.tables.unwrap();
+        assert_eq!(tables.len(), 2);
+        assert!(tables.iter().any(|t| t.name == Some("accounts".to_string())));
+        assert!(tables.iter().all(|t| t.fields.as_ref().unwrap().len() == 2));
+        // 索引随表保存，字段id替换为新的字段id
+        assert!(tables.iter().all(|t| t.indices.as_ref().unwrap().len() == 1));
+        assert_eq!(saved.indices.unwrap().len(), 2);
+        assert!(saved.references.unwrap().is_empty());
+        assert!(saved.areas.unwrap().is_empty());
+        assert_eq!(saved.notes.unwrap()[0].content, Some("changed".to_string()));
+        assert_eq!(Area::find().all(&db).await.unwrap().len(), 0);
+        assert_eq!(Reference::find().all(&db).await.unwrap().len(), 0);
+
+        let mut missing = DiagramVo::from(&diagram::Model {
+            id: "missing".to_string(),
+            database: None,
+            zoom: None,
+            name: None,
+            pan: None,
+            last_modified: None,
+            types: None,
+            enums: None,
+        });
+        missing.tables = Some(vec![]);
+        let req = test::TestRequest::post().uri("/update").set_json(&missing).to_request();
+        let resp: CommonResponse = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(resp.code, 404);
+    }
+
+    /// 新增的表和关联关系使用后端id，关联关系引用的前端id被替换
+    #[actix_web::test]
+    async fn test_update_diagram_remaps_new_ids(){
+        let db = init_test_db().await;
+        seed_diagram(&db).await;
+        let mut diagram = query_diagram_detail(&db, "d1".to_string()).await.unwrap().unwrap();
+        let mut new_table = diagram.tables.as_ref().unwrap()[0].clone();
+        new_table.id = "t2".to_string();
+        new_table.name = Some("orders".to_string());
+        diagram.tables.as_mut().unwrap().push(new_table);
+        let mut new_reference = diagram.references.as_ref().unwrap()[0].clone();
+        new_reference.id = "r2".to_string();
+        new_reference.start_table_id = Some("t2".to_string());
+        new_reference.start_field_id = Some("f2".to_string());
+        new_reference.end_table_id = Some("t1".to_string());
+        new_reference.end_field_id = Some("f1".to_string());
+        diagram.references.as_mut().unwrap().push(new_reference);
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(db.clone()))
+                .configure(diagrams_routes)
+        ).await;
+        let req = test::TestRequest::post().uri("/update").set_json(&diagram).to_request();
+        let resp: CommonResponse = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(resp.code, 200);
+        let saved: DiagramSaveVo = serde_json::from_value(resp.data.unwrap()).unwrap();
+        let id_mapping = saved.id_mapping;
+        let table_id = id_mapping.tables["t2"].clone();
+        let field_id = id_mapping.fields["t2"]["f2"].clone();
+        let reference_id = id_mapping.references["r2"].clone();
+        assert_ne!(table_id, "t2");
+        assert_ne!(reference_id, "r2");
+
+        let references = saved.diagram.references.unwrap();
+        assert_eq!(references.len(), 2);
+        let reference = references.iter().find(|r| r.id == reference_id).unwrap();
+        assert_eq!(reference.start_table_id, Some(table_id));
+        assert_eq!(reference.start_field_id, Some(field_id));
+        // 已存在的表不在映射中，保持原id
+        assert_eq!(reference.end_table_id, Some("t1".to_string()));
+        assert_eq!(reference.end_field_id, Some("f1".to_string()));
+    }
+
+    /// 在已有表中新增字段并在同一次保存中引用该字段，关联关系使用字段的后端id
+    #[actix_web::test]
+    async fn test_update_diagram_remaps_new_fields(){
+        let db = init_test_db().await;
+        seed_diagram(&db).await;
+        let mut diagram = query_diagram_detail(&db, "d1".to_string()).await.unwrap().unwrap();
+        let table = &mut diagram.tables.as_mut().unwrap()[0];
+        let mut new_field = table.fields.as_ref().unwrap()[0].clone();
+        new_field.id = "f3".to_string();
+        new_field.name = Some("parent_id".to_string());
+        new_field.primary = Some(false);
+        table.fields.as_mut().unwrap().push(new_field);
+        let mut new_reference = diagram.references.as_ref().unwrap()[0].clone();
+        new_reference.id = "r2".to_string();
+        new_reference.start_table_id = Some("t1".to_string());
+        new_reference.start_field_id = Some("f3".to_string());
+        new_reference.end_table_id = Some("t1".to_string());
+        new_reference.end_field_id = Some("f1".to_string());
+        diagram.references.as_mut().unwrap().push(new_reference);
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(db.clone()))
+                .configure(diagrams_routes)
+        ).await;
+        let req = test::TestRequest::post().uri("/update").set_json(&diagram).to_request();
+        let resp: CommonResponse = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(resp.code, 200);
+        let saved: DiagramSaveVo = serde_json::from_value(resp.data.unwrap()).unwrap();
+        let field_id = saved.id_mapping.fields["t1"]["f3"].clone();
+        assert_ne!(field_id, "f3");
+        let reference_id = saved.id_mapping.references["r2"].clone();
+
+        let fields = saved.diagram.tables.unwrap()[0].fields.clone().unwrap();
+        assert!(fields.iter().any(|field| field.id == field_id));
+        let references = saved.diagram.references.unwrap();
+        let reference = references.iter().find(|r| r.id == reference_id).unwrap();
+        assert_eq!(reference.start_table_id, Some("t1".to_string()));
+        assert_eq!(reference.start_field_id, Some(field_id));
+    }
+
+    /// 同一次保存中把关联关系移到新增字段并删除原字段，删除检查使用移动后的关联关系
+    #[actix_web::test]
+    async fn test_update_diagram_moves_reference_off_deleted_field(){
+        let db = init_test_db().await;
+        seed_diagram(&db).await;
+        let mut diagram = query_diagram_detail(&db, "d1".to_string()).await.unwrap().unwrap();
+        let fields = diagram.tables.as_mut().unwrap()[0].fields.as_mut().unwrap();
+        let mut new_field = fields.iter().find(|field| field.id == "f2").unwrap().clone();
+        new_field.id = "f3".to_string();
+        new_field.name = Some("parent_id".to_string());
+        fields.retain(|field| field.id != "f2");
+        fields.push(new_field);
+        diagram.references.as_mut().unwrap()[0].start_field_id = Some("f3".to_string());
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(db.clone()))
+                .configure(diagrams_routes)
+        ).await;
+        let req = test::TestRequest::post().uri("/update").set_json(&diagram).to_request();
+        let resp: CommonResponse = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(resp.code, 200);
+        let saved: DiagramSaveVo = serde_json::from_value(resp.data.unwrap()).unwrap();
+        let field_id = saved.id_mapping.fields["t1"]["f3"].clone();
+        let references = saved.diagram.references.unwrap();
+        assert_eq!(references.len(), 1);
+        assert_eq!(references[0].id, "r1");
+        assert_eq!(references[0].start_field_id, Some(field_id));
+        assert!(Field::find_by_id("f2").one(&db).await.unwrap().is_none());
+    }
+
+    /// 新增的主题区域和注释使用后端id，不同图表提交相同的前端id不会冲突
+    #[actix_web::test]
+    async fn test_update_diagram_new_areas_and_notes(){
+        let db = init_test_db().await;
+        seed_diagram(&db).await;
+        Diagram::insert(diagram::ActiveModel::from(diagram::Model {
+            id: "d2".to_string(),
+            database: Some("generic".to_string()),
+            zoom: None,
+            name: Some("other".to_string()),
+            pan: None,
+            last_modified: None,
+            types: None,
+            enums: None,
+        })).exec(&db).await.unwrap();
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(db.clone()))
+                .configure(diagrams_routes)
+        ).await;
+        let mut area = AreaVo::from(&Area::find_by_id("a1").one(&db).await.unwrap().unwrap());
+        area.id = "0".to_string();
+        let mut note = NoteVo::from(&Note::find_by_id("n1").one(&db).await.unwrap().unwrap());
+        note.id = "0".to_string();
+        let mut area_ids = Vec::new();
+        for diagram_id in ["d1", "d2"] {
+            let mut diagram = query_diagram_detail(&db, diagram_id.to_string()).await.unwrap().unwrap();
+            diagram.areas = Some(vec![area.clone()]);
+            diagram.notes = Some(vec![note.clone()]);
+            let req = test::TestRequest::post().uri("/update").set_json(&diagram).to_request();
+            let resp: CommonResponse = test::call_and_read_body_json(&app, req).await;
+            assert_eq!(resp.code, 200);
+            let saved: DiagramSaveVo = serde_json::from_value(resp.data.unwrap()).unwrap();
+            let area_id = saved.id_mapping.areas["0"].clone();
+            assert_eq!(saved.diagram.areas.unwrap()[0].id, area_id);
+            assert_eq!(saved.diagram.notes.unwrap()[0].id, saved.id_mapping.notes["0"]);
+            area_ids.push(area_id);
+        }
+        assert_ne!(area_ids[0], area_ids[1]);
+        assert_eq!(Area::find().count(&db).await.unwrap(), 2);
+    }
+
+    /// 删除表时删除引用该表的关联关系，提交的关联关系仍引用被删除的表时不保存
+    #[actix_web::test]
+    async fn test_update_diagram_deletes_table_references(){
+        let db = init_test_db().await;
+        seed_diagram(&db).await;
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(db.clone()))
+                .configure(diagrams_routes)
+        ).await;
+        let mut diagram = query_diagram_detail(&db, "d1".to_string()).await.unwrap().unwrap();
+        diagram.tables = Some(vec![]);
+        let req = test::TestRequest::post().uri("/update").set_json(&diagram).to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 400);
+        assert_eq!(Table::find().count(&db).await.unwrap(), 1);
+
+        diagram.references = None;
+        let req = test::TestRequest::post().uri("/update").set_json(&diagram).to_request();
+        let resp: CommonResponse = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(resp.code, 200);
+        assert_eq!(Reference::find().count(&db).await.unwrap(), 0);
+        assert_eq!(DiagramLink::find().filter(diagram_link::Column::ReferenceId.is_not_null()).count(&db).await.unwrap(), 0);
+    }
+
+    #[actix_web::test]
+    async fn test_update_diagram_with_validate(){
+        let db = init_test_db().await;
+        seed_diagram(&db).await;
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(db.clone()))
+                .configure(diagrams_routes)
+                .configure(crate::validate::validate_routes)
+        ).await;
+        let req = test::TestRequest::get().uri("/d1/issues").to_request();
+        let resp: CommonResponse = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(resp.code, 200);
+        assert_eq!(resp.data.unwrap(), serde_json::json!([]));
+
+        // 字段名重复，开启校验时不保存
+        let mut diagram = query_diagram_detail(&db, "d1".to_string()).await.unwrap().unwrap();
+        diagram.tables.as_mut().unwrap()[0].fields.as_mut().unwrap()[1].name = Some("f1".to_string());
+        let req = test::TestRequest::post().uri("/update?validate=true").set_json(&diagram).to_request();
+        let resp: CommonResponse = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(resp.code, 400);
+        let issues: Vec<IssueVo> = serde_json::from_value(resp.data.unwrap()).unwrap();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].code, "duplicate_fields");
+        assert_eq!(issues[0].field_id.as_deref(), Some("f2"));
+        assert_eq!(Field::find_by_id("f2").one(&db).await.unwrap().unwrap().name, Some("f2".to_string()));
+
+        // 不开启校验时照常保存
+        let req = test::TestRequest::post().uri("/update").set_json(&diagram).to_request();
+        let resp: CommonResponse = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(resp.code, 200);
+        let req = test::TestRequest::get().uri("/d1/issues").to_request();
+        let resp: CommonResponse = test::call_and_read_body_json(&app, req).await;
+        let issues: Vec<IssueVo> = serde_json::from_value(resp.data.unwrap()).unwrap();
+        assert_eq!(issues.len(), 1);
+
+        let req = test::TestRequest::get().uri("/missing/issues").to_request();
+        let resp: CommonResponse = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(resp.code, 404);
+    }
+
+    #[actix_web::test]
+    async fn test_delete_diagram(){
+        let db = init_test_db().await;
+        seed_diagram(&db).await;
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(db.clone()))
+                .configure(diagrams_routes)
+        ).await;
+        let req = test::TestRequest::delete().uri("/delete/d1").to_request();
+        let resp: CommonResponse = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(resp.code, 200);
+        let result: DiagramDeleteVo = serde_json::from_value(resp.data.unwrap()).unwrap();
+        assert_eq!(result.tables, 1);
+        assert_eq!(result.fields, 2);
+        assert_eq!(result.table_links, 2);
+        assert_eq!(result.indices, 1);
+        assert_eq!(result.indice_links, 1);
+        assert_eq!(result.references, 1);
+        assert_eq!(result.notes, 1);
+        assert_eq!(result.areas, 1);
+        assert_eq!(result.tasks, 1);
+        assert_eq!(result.diagram_links, 5);
+        assert_eq!(result.versions, 0);
+        assert!(Diagram::find().all(&db).await.unwrap().is_empty());
+        assert!(DiagramLink::find().all(&db).await.unwrap().is_empty());
+        assert!(Field::find().all(&db).await.unwrap().is_empty());
+        assert!(TableLink::find().all(&db).await.unwrap().is_empty());
+
+        let req = test::TestRequest::delete().uri("/delete/d1").to_request();
+        let resp: CommonResponse = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(resp.code, 404);
+    }
+
+    /// 删除图表时默认保留版本记录，purge_versions=true时一并删除
+    #[actix_web::test]
+    async fn test_delete_diagram_versions(){
+        let db = init_test_db().await;
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(db.clone()))
+                .configure(diagrams_routes)
+        ).await;
+        let versions = || DiagramVersion::find().count(&db);
+        for (uri, deleted, remaining) in [("/delete/d1", 0, 1), ("/delete/d1?purge_versions=true", 2, 0)] {
+            seed_diagram(&db).await;
+            let diagram = query_diagram_detail(&db, "d1".to_string()).await.unwrap().unwrap();
+            add_diagram_version(&db, &diagram, None, None).await.unwrap();
+            let req = test::TestRequest::delete().uri(uri).to_request();
+            let resp: CommonResponse = test::call_and_read_body_json(&app, req).await;
+            assert_eq!(resp.code, 200);
+            let result: DiagramDeleteVo = serde_json::from_value(resp.data.unwrap()).unwrap();
+            assert_eq!(result.versions, deleted);
+            assert_eq!(versions().await.unwrap(), remaining);
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_diff_by_id(){
+        let origin = vec!["a".to_string(), "b".to_string()];
+        let incoming = vec!["b".to_string(), "c".to_string()];
+        let changes = diff_by_id(&origin, &incoming, |item| item);
+        assert_eq!(changes.inserts, vec!["c".to_string()]);
+        assert!(changes.updates.is_empty());
+        assert_eq!(changes.deletes, vec!["a".to_string()]);
+    }
 
     #[actix_web::test]
     async fn test_query_related(){
-        let db = Database::connect("sqlite://test.sqlite").await.unwrap();
+        let db = init_test_db().await;
         let db = web::Data::new(db);
         let tx = db.get_ref();
 
@@ -146,7 +681,7 @@ mod tests{
         .await.unwrap()
         .iter()
         .filter_map(|(diagram, table)| {
-            table.as_ref().map(|table| TableVo::from(table, diagram.id.clone(),None))
+            table.as_ref().map(|table| TableVo::build_from_table(table.clone(), None, diagram.id.clone()))
         })
         .collect::<Vec<TableVo>>()
         .into_iter()
@@ -0,0 +1,372 @@
+use serde::Serialize;
+use crate::entity::vo::{
+    ColumnSummaryVo, DiagramDiffVo, DiagramVo, FieldDiffVo, FieldVo, IndexDiffVo, IndexSummaryVo, IndiceVo,
+    PropertyChangeVo, ReferenceDiffVo, ReferenceSummaryVo, ReferenceVo, RenameVo, TableDiffVo, TableVo,
+};
+
+/// 对比两个图表的结构
+/// 同一图表的不同版本按id匹配，不同图表之间按名称匹配
+pub fn diff_diagrams(from: &DiagramVo, to: &DiagramVo) -> DiagramDiffVo {
+    let from_tables = from.tables.clone().unwrap_or_default();
+    let to_tables = to.tables.clone().unwrap_or_default();
+    let mut diff = DiagramDiffVo::default();
+
+    //1、表
+    let tables = match_items(&from_tables, &to_tables, |table| &table.id, |table| table.name.clone());
+    diff.added_tables = tables.added.iter().map(|table| table_name(table)).collect();
+    diff.removed_tables = tables.removed.iter().map(|table| table_name(table)).collect();
+    for (from_table, to_table) in tables.pairs {
+        if table_name(from_table) != table_name(to_table) {
+            diff.renamed_tables.push(RenameVo { from: table_name(from_table), to: table_name(to_table) });
+        }
+        let table_diff = diff_table(from_table, to_table);
+        if !table_diff.is_empty() {
+            diff.modified_tables.push(table_diff);
+        }
+    }
+
+    //2、关联关系，表和字段替换为名称后比较
+    let from_references = from.references.clone().unwrap_or_default();
+    let to_references = to.references.clone().unwrap_or_default();
+    let from_summaries = from_references.iter()
+    .map(|reference| reference_summary(reference, &from_tables))
+    .collect::<Vec<ReferenceSummaryVo>>();
+    let to_summaries = to_references.iter()
+    .map(|reference| reference_summary(reference, &to_tables))
+    .collect::<Vec<ReferenceSummaryVo>>();
+    let from_items = from_references.iter().zip(&from_summaries).collect::<Vec<_>>();
+    let to_items = to_references.iter().zip(&to_summaries).collect::<Vec<_>>();
+    let references = match_items(&from_items, &to_items, |(reference, _)| &reference.id, |(_, summary)| Some(reference_key(summary)));
+    diff.added_references = references.added.iter().map(|(_, summary)| (*summary).clone()).collect();
+    diff.removed_references = references.removed.iter().map(|(_, summary)| (*summary).clone()).collect();
+    for ((_, from_summary), (_, to_summary)) in references.pairs {
+        let mut changes = Vec::new();
+        change(&mut changes, "name", &from_summary.name, &to_summary.name);
+        change(&mut changes, "start", &endpoint(&from_summary.start_table, &from_summary.start_field), &endpoint(&to_summary.start_table, &to_summary.start_field));
+        change(&mut changes, "end", &endpoint(&from_summary.end_table, &from_summary.end_field), &endpoint(&to_summary.end_table, &to_summary.end_field));
+        change(&mut changes, "cardinality", &from_summary.cardinality, &to_summary.cardinality);
+        change(&mut changes, "update_constraint", &from_summary.update_constraint, &to_summary.update_constraint);
+        change(&mut changes, "delete_constraint", &from_summary.delete_constraint, &to_summary.delete_constraint);
+        if !changes.is_empty() {
+            diff.modified_references.push(ReferenceDiffVo { name: to_summary.name.clone(), changes });
+        }
+    }
+    diff
+}
+
+/// 同一张表的字段、索引以及注释的差异
+fn diff_table(from: &TableVo, to: &TableVo) -> TableDiffVo {
+    let from_fields = from.fields.clone().unwrap_or_default();
+    let to_fields = to.fields.clone().unwrap_or_default();
+    let mut diff = TableDiffVo { name: table_name(to), ..Default::default() };
+    change(&mut diff.changes, "comment", &text(&from.comment), &text(&to.comment));
+
+    //1、字段
+    let fields = match_items(&from_fields, &to_fields, |field| &field.id, |field| field.name.clone());
+    diff.added_fields = fields.added.iter().map(|field| column_summary(field)).collect();
+    diff.removed_fields = fields.removed.iter().map(|field| column_summary(field)).collect();
+    for (from_field, to_field) in fields.pairs {
+        if field_name(from_field) != field_name(to_field) {
+            diff.renamed_fields.push(RenameVo { from: field_name(from_field), to: field_name(to_field) });
+        }
+        let mut changes = Vec::new();
+        change(&mut changes, "type", &field_type(from_field), &field_type(to_field));
+        change(&mut changes, "size", &from_field.size, &to_field.size);
+        change(&mut changes, "not_null", &flag(from_field.not_null), &flag(to_field.not_null));
+        change(&mut changes, "default", &text(&from_field.default), &text(&to_field.default));
+        change(&mut changes, "primary", &flag(from_field.primary), &flag(to_field.primary));
+        change(&mut changes, "unique", &flag(from_field.unique), &flag(to_field.unique));
+        change(&mut changes, "increment", &flag(from_field.increment), &flag(to_field.increment));
+        change(&mut changes, "check", &text(&from_field.check), &text(&to_field.check));
+        change(&mut changes, "comment", &text(&from_field.comment), &text(&to_field.comment));
+        change(&mut changes, "values", &from_field.values, &to_field.values);
+        if !changes.is_empty() {
+            diff.modified_fields.push(FieldDiffVo { name: field_name(to_field), changes });
+        }
+    }
+
+    //2、索引，字段替换为名称后比较
+    let from_indices = from.indices.clone().unwrap_or_default();
+    let to_indices = to.indices.clone().unwrap_or_default();
+    let indices = match_items(&from_indices, &to_indices, |indice| &indice.id, |indice| indice.name.clone());
+    diff.added_indices = indices.added.iter().map(|indice| index_summary(indice, to)).collect();
+    diff.removed_indices = indices.removed.iter().map(|indice| index_summary(indice, from)).collect();
+    for (from_indice, to_indice) in indices.pairs {
+        let from_summary = index_summary(from_indice, from);
+        let to_summary = index_summary(to_indice, to);
+        let mut changes = Vec::new();
+        change(&mut changes, "name", &from_summary.name, &to_summary.name);
+        change(&mut changes, "unique", &from_summary.unique, &to_summary.unique);
+        change(&mut changes, "fields", &from_summary.fields, &to_summary.fields);
+        if !changes.is_empty() {
+            diff.modified_indices.push(IndexDiffVo { name: to_summary.name, changes });
+        }
+    }
+    diff
+}
+
+/// 匹配结果，pairs按目标图表中的顺序排列
+pub(crate) struct Matched<'a, T> {
+    pub(crate) pairs: Vec<(&'a T, &'a T)>,
+    pub(crate) removed: Vec<&'a T>,
+    pub(crate) added: Vec<&'a T>,
+}
+
+/// 先按id匹配，剩余的项再按key匹配，key为None的项不参与第二轮匹配
+pub(crate) fn match_items<'a, T>(
+    from: &'a [T],
+    to: &'a [T],
+    id: impl Fn(&T) -> &str,
+    key: impl Fn(&T) -> Option<String>
+) -> Matched<'a, T> {
+    let mut matched_from = vec![false; from.len()];
+    let mut matched_to = vec![None; to.len()];
+    for (to_index, item) in to.iter().enumerate() {
+        if let Some(from_index) = from.iter().position(|origin| id(origin) == id(item)) {
+            matched_from[from_index] = true;
+            matched_to[to_index] = Some(from_index);
+        }
+    }
+    for (to_index, item) in to.iter().enumerate() {
+        if matched_to[to_index].is_some() {
+            continue;
+        }
+        let Some(item_key) = key(item) else {
+            continue;
+        };
+        let found = from.iter().enumerate()
+        .position(|(from_index, origin)| !matched_from[from_index] && key(origin).as_ref() == Some(&item_key));
+        if let Some(from_index) = found {
+            matched_from[from_index] = true;
+            matched_to[to_index] = Some(from_index);
+        }
+    }
+    Matched {
+        pairs: matched_to.iter().enumerate()
+        .filter_map(|(to_index, from_index)| from_index.map(|from_index| (&from[from_index], &to[to_index])))
+        .collect(),
+        removed: from.iter().zip(matched_from).filter(|(_, matched)| !matched).map(|(item, _)| item).collect(),
+        added: to.iter().zip(matched_to).filter(|(_, matched)| matched.is_none()).map(|(item, _)| item).collect(),
+    }
+}
+
+/// 值不同时记录属性的变化
+fn change<T: Serialize + PartialEq>(changes: &mut Vec<PropertyChangeVo>, property: &str, from: &T, to: &T) {
+    if from != to {
+        changes.push(PropertyChangeVo {
+            property: property.to_string(),
+            from: serde_json::to_value(from).unwrap(),
+            to: serde_json::to_value(to).unwrap(),
+        });
+    }
+}
+
+fn column_summary(field: &FieldVo) -> ColumnSummaryVo {
+    let mut r#type = field_type(field);
+    if let Some(size) = field.size.filter(|size| *size > 0) {
+        r#type = format!("{}({})", r#type, size);
+    }
+    ColumnSummaryVo {
+        name: field_name(field),
+        r#type,
+        not_null: flag(field.not_null),
+        default: text(&field.default),
+    }
+}
+
+fn index_summary(indice: &IndiceVo, table: &TableVo) -> IndexSummaryVo {
+    IndexSummaryVo {
+        name: indice.name.clone().unwrap_or_else(|| indice.id.clone()),
+        unique: flag(indice.unique),
+        fields: indice.fields.clone().unwrap_or_default().iter()
+        .map(|field_id| field_name_by_id(table, field_id))
+        .collect(),
+    }
+}
+
+/// 关联关系引用的表或字段不存在时使用id
+fn reference_summary(reference: &ReferenceVo, tables: &[TableVo]) -> ReferenceSummaryVo {
+    let resolve = |table_id: &Option<String>, field_id: &Option<String>| {
+        let table_id = table_id.clone().unwrap_or_default();
+        let field_id = field_id.clone().unwrap_or_default();
+        match tables.iter().find(|table| table.id == table_id) {
+            Some(table) => (table_name(table), field_name_by_id(table, &field_id)),
+            None => (table_id, field_id),
+        }
+    };
+    let (start_table, start_field) = resolve(&reference.start_table_id, &reference.start_field_id);
+    let (end_table, end_field) = resolve(&reference.end_table_id, &reference.end_field_id);
+    ReferenceSummaryVo {
+        name: text(&reference.name),
+        start_table,
+        start_field,
+        end_table,
+        end_field,
+        cardinality: text(&reference.cardinality),
+        update_constraint: text(&reference.update_constraint),
+        delete_constraint: text(&reference.delete_constraint),
+    }
+}
+
+/// 关联关系按名称匹配，未命名的按起止字段匹配
+fn reference_key(summary: &ReferenceSummaryVo) -> String {
+    summary.name.clone().unwrap_or_else(|| {
+        format!("{} -> {}", endpoint(&summary.start_table, &summary.start_field), endpoint(&summary.end_table, &summary.end_field))
+    })
+}
+
+fn endpoint(table: &str, field: &str) -> String {
+    format!("{}.{}", table, field)
+}
+
+/// 空字符串与未设置视为相同
+fn text(value: &Option<String>) -> Option<String> {
+    value.clone().filter(|value| !value.trim().is_empty())
+}
+
+fn flag(value: Option<bool>) -> bool {
+    value.unwrap_or(false)
+}
+
+/// 类型名称不区分大小写
+fn field_type(field: &FieldVo) -> String {
+    field.r#type.clone().unwrap_or_default().trim().to_uppercase()
+}
+
+fn table_name(table: &TableVo) -> String {
+    table.name.clone().unwrap_or_else(|| table.id.clone())
+}
+
+fn field_name(field: &FieldVo) -> String {
+    field.name.clone().unwrap_or_else(|| field.id.clone())
+}
+
+fn field_name_by_id(table: &TableVo, field_id: &str) -> String {
+    table.fields.as_ref()
+    .and_then(|fields| fields.iter().find(|field| field.id == field_id))
+    .map(field_name)
+    .unwrap_or_else(|| field_id.to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+    use crate::entity::vo::fixtures::{build_diagram, build_field, build_table};
+
+    /// 以1结尾的字段id为主键
+    fn field(id: &str, name: &str, r#type: &str) -> FieldVo {
+        FieldVo {
+            primary: Some(id.ends_with('1')),
+            ..build_field(id, name, r#type)
+        }
+    }
+
+    fn build_indice(id: &str, name: &str, unique: bool, fields: &[&str]) -> IndiceVo {
+        IndiceVo {
+            id: id.to_string(),
+            name: Some(name.to_string()),
+            unique: Some(unique),
+            table_id: None,
+            fields: Some(fields.iter().map(|field| field.to_string()).collect()),
+        }
+    }
+
+    fn build_reference(id: &str, delete_constraint: &str) -> ReferenceVo {
+        ReferenceVo {
+            id: id.to_string(),
+            name: Some("fk_orders_users".to_string()),
+            start_table_id: Some("t2".to_string()),
+            end_table_id: Some("t1".to_string()),
+            cardinality: Some("many_to_one".to_string()),
+            delete_constraint: Some(delete_constraint.to_string()),
+            end_field_id: Some("u1".to_string()),
+            start_field_id: Some("o2".to_string()),
+            update_constraint: None,
+        }
+    }
+
+    fn orders() -> TableVo {
+        build_table("t2", "orders", vec![field("o1", "id", "INT"), field("o2", "user_id", "INT")], vec![])
+    }
+
+    #[test]
+    fn test_diff_versions(){
+        let mut email = field("u2", "email", "VARCHAR");
+        email.size = Some(255);
+        let from = build_diagram(vec![
+            build_table("t1", "users", vec![field("u1", "id", "INT"), email, field("u3", "age", "INT")],
+                vec![build_indice("i1", "idx_email", false, &["u2"])]),
+            orders(),
+            build_table("t3", "logs", vec![field("l1", "id", "INT")], vec![]),
+        ], vec![build_reference("r1", "Cascade")]);
+
+        let mut mail = field("u2", "mail", "varchar");
+        mail.size = Some(320);
+        mail.not_null = Some(true);
+        let mut created_at = field("u4", "created_at", "DATE");
+        created_at.default = Some("CURRENT_DATE".to_string());
+        let to = build_diagram(vec![
+            build_table("t1", "accounts", vec![field("u1", "id", "INT"), mail, created_at],
+                vec![build_indice("i1", "idx_email", true, &["u2", "u1"])]),
+            orders(),
+            build_table("t9", "audit", vec![field("a1", "id", "INT")], vec![]),
+        ], vec![build_reference("r1", "Restrict")]);
+
+        let diff = diff_diagrams(&from, &to);
+        assert_eq!(diff.added_tables, vec!["audit"]);
+        assert_eq!(diff.removed_tables, vec!["logs"]);
+        assert_eq!(diff.renamed_tables, vec![RenameVo { from: "users".to_string(), to: "accounts".to_string() }]);
+        assert_eq!(diff.modified_tables.len(), 1);
+        let table = &diff.modified_tables[0];
+        assert_eq!(table.name, "accounts");
+        assert_eq!(table.added_fields, vec![ColumnSummaryVo {
+            name: "created_at".to_string(),
+            r#type: "DATE".to_string(),
+            not_null: false,
+            default: Some("CURRENT_DATE".to_string()),
+        }]);
+        assert_eq!(table.removed_fields.iter().map(|field| field.name.as_str()).collect::<Vec<&str>>(), vec!["age"]);
+        assert_eq!(table.renamed_fields, vec![RenameVo { from: "email".to_string(), to: "mail".to_string() }]);
+        // 类型只有大小写不同时不算变化
+        assert_eq!(table.modified_fields, vec![FieldDiffVo {
+            name: "mail".to_string(),
+            changes: vec![
+                PropertyChangeVo { property: "size".to_string(), from: json!(255), to: json!(320) },
+                PropertyChangeVo { property: "not_null".to_string(), from: json!(false), to: json!(true) },
+            ],
+        }]);
+        assert_eq!(table.modified_indices, vec![IndexDiffVo {
+            name: "idx_email".to_string(),
+            changes: vec![
+                PropertyChangeVo { property: "unique".to_string(), from: json!(false), to: json!(true) },
+                PropertyChangeVo { property: "fields".to_string(), from: json!(["email"]), to: json!(["mail", "id"]) },
+            ],
+        }]);
+        assert!(diff.added_references.is_empty() && diff.removed_references.is_empty());
+        assert_eq!(diff.modified_references, vec![ReferenceDiffVo {
+            name: Some("fk_orders_users".to_string()),
+            changes: vec![
+                PropertyChangeVo { property: "end".to_string(), from: json!("users.id"), to: json!("accounts.id") },
+                PropertyChangeVo { property: "delete_constraint".to_string(), from: json!("Cascade"), to: json!("Restrict") },
+            ],
+        }]);
+    }
+
+    /// 不同图表之间id不同，按名称匹配
+    #[test]
+    fn test_diff_by_name(){
+        let from = build_diagram(vec![orders()], vec![]);
+        let mut other = orders();
+        other.id = "x2".to_string();
+        other.fields.as_mut().unwrap().iter_mut().for_each(|field| field.id = format!("x{}", field.id));
+        other.fields.as_mut().unwrap()[1].r#type = Some("BIGINT".to_string());
+        let to = build_diagram(vec![other], vec![]);
+        let diff = diff_diagrams(&from, &to);
+        assert!(diff.added_tables.is_empty() && diff.removed_tables.is_empty() && diff.renamed_tables.is_empty());
+        assert_eq!(diff.modified_tables[0].modified_fields, vec![FieldDiffVo {
+            name: "user_id".to_string(),
+            changes: vec![PropertyChangeVo { property: "type".to_string(), from: json!("INT"), to: json!("BIGINT") }],
+        }]);
+    }
+}
@@ -0,0 +1,204 @@
+mod compare;
+pub use compare::*;
+use actix_web::{get, web, HttpResponse};
+use sea_orm::{ConnectionTrait, DatabaseConnection};
+use serde::Deserialize;
+use crate::common::{CommonResponse, ResponseCode, ResponseMessage};
+use crate::diagrams::query_diagram_detail;
+use crate::entity::vo::DiagramVo;
+use crate::error::DrawDBError;
+use crate::export::{to_migration, Dialect, MigrationFormat};
+use crate::versions::query_diagram_version;
+
+/// 结构对比模块，挂载在/diagrams下
+pub fn diff_routes(config: &mut web::ServiceConfig) {
+    config.service(diff);
+    config.service(diff_migration);
+}
+
+/// 对比参数
+/// 取值为图表id表示图表当前的结构，图表id@版本号表示图表的某个版本
+#[derive(Debug, Deserialize)]
+pub struct DiffQuery {
+    pub from: String,
+    pub to: String,
+}
+
+/// 迁移脚本参数
+#[derive(Debug, Deserialize)]
+pub struct MigrationQuery {
+    pub from: String,
+    pub to: String,
+    // 数据库方言，未指定时使用目标图表的数据库类型
+    pub dialect: Option<String>,
+    // 输出格式，sql为单个脚本，migrate、flyway为打包的编号文件，默认为sql
+    pub format: Option<String>,
+    // 迁移编号，默认为1
+    pub number: Option<u32>,
+    // 迁移名称，默认为migration
+    pub name: Option<String>,
+}
+
+/// 对比两个图表或同一图表两个版本的结构
+/// 返回新增、删除、重命名的表，字段、索引和关联关系的变化
+#[get("/diff")]
+async fn diff(
+    db: web::Data<DatabaseConnection>,
+    query: web::Query<DiffQuery>
+) -> Result<CommonResponse, DrawDBError> {
+    let query = query.into_inner();
+    let conn = db.get_ref();
+    let (Some(from), Some(to)) = (load_diagram(conn, &query.from).await?, load_diagram(conn, &query.to).await?) else {
+        return Ok(CommonResponse::new(
+            ResponseCode::NotFound,
+            ResponseMessage::NotFound,
+            None,
+        ));
+    };
+    let mut result = diff_diagrams(&from, &to);
+    result.from = query.from;
+    result.to = query.to;
+    Ok(CommonResponse::new(
+        ResponseCode::Success,
+        ResponseMessage::Success,
+        Some(serde_json::to_value(result).unwrap()),
+    ))
+}
+
+/// 生成从from迁移到to的升级和回滚脚本
+/// sql格式返回纯文本，其余格式返回zip
+#[get("/diff/migration")]
+async fn diff_migration(
+    db: web::Data<DatabaseConnection>,
+    query: web::Query<MigrationQuery>
+) -> Result<HttpResponse, DrawDBError> {
+    let query = query.into_inner();
+    let format = match query.format.as_deref() {
+        Some(format) => format.parse()?,
+        None => MigrationFormat::Sql,
+    };
+    let conn = db.get_ref();
+    let (Some(from), Some(to)) = (load_diagram(conn, &query.from).await?, load_diagram(conn, &query.to).await?) else {
+        return Ok(HttpResponse::Ok().json(CommonResponse::new(
+            ResponseCode::NotFound,
+            ResponseMessage::NotFound,
+            None,
+        )));
+    };
+    let dialect = Dialect::resolve(query.dialect.as_deref(), to.database.as_deref())?;
+    let migration = to_migration(&from, &to, dialect);
+    let name = query.name.unwrap_or_else(|| "migration".to_string());
+    if format == MigrationFormat::Sql {
+        return Ok(HttpResponse::Ok()
+            .content_type("text/plain; charset=utf-8")
+            .body(migration.to_script()));
+    }
+    let zip = migration.to_zip(format, query.number.unwrap_or(1), &name)?;
+    Ok(HttpResponse::Ok()
+        .content_type("application/zip")
+        .insert_header(("Content-Disposition", format!("attachment; filename=\"{}.zip\"", name.replace('"', ""))))
+        .body(zip))
+}
+
+/// 按对比参数查询图表结构，图表或版本不存在时返回None
+async fn load_diagram<C: ConnectionTrait>(
+    conn: &C,
+    spec: &str
+) -> Result<Option<DiagramVo>, DrawDBError> {
+    let Some((diagram_id, version)) = spec.rsplit_once('@') else {
+        return query_diagram_detail(conn, spec.to_string()).await;
+    };
+    let version = version.parse::<i32>()
+    .map_err(|_| DrawDBError::ValidationError(format!("版本号无效: {}", version)))?;
+    Ok(query_diagram_version(conn, diagram_id.to_string(), version).await?
+    .and_then(|version| version.snapshot))
+}
+
+#[cfg(test)]
+mod test {
+    use actix_web::{test, App};
+    use sea_orm::EntityTrait;
+    use serde_json::json;
+    use super::*;
+    use crate::diagrams::diagrams_routes;
+    use crate::entity::{diagram, prelude::*};
+    use crate::init::init_test_db;
+
+    /// 对比图表的历史版本与当前结构
+    #[actix_web::test]
+    async fn test_diff_version() {
+        let db = init_test_db().await;
+        Diagram::insert(diagram::ActiveModel::from(diagram::Model {
+            id: "d1".to_string(),
+            database: Some("postgresql".to_string()),
+            zoom: None,
+            name: Some("demo".to_string()),
+            pan: None,
+            last_modified: None,
+            types: None,
+            enums: None,
+        })).exec(&db).await.unwrap();
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(db.clone()))
+                .configure(diagrams_routes)
+                .configure(diff_routes)
+        ).await;
+        let table = |name: &str| json!({
+            "id": "0", "color": null, "comment": null, "locked": false, "name": name,
+            "x": "0", "y": "0", "indices": [], "diagram_id": "d1",
+            "fields": [{
+                "id": "0", "table_id": null, "check": null, "comment": null, "default": null,
+                "increment": false, "not_null": true, "primary": true, "size": null,
+                "type": "INT", "unique": false, "name": "id"
+            }]
+        });
+        let diagram = |tables: Vec<serde_json::Value>| json!({
+            "id": "d1", "zoom": null, "database": null, "name": null, "tables": tables,
+            "areas": null, "references": null, "indices": null, "notes": null, "tasks": null,
+            "pan": null, "lastModified": null, "types": null, "enums": null
+        });
+        let req = test::TestRequest::post().uri("/update").set_json(diagram(vec![table("users")])).to_request();
+        let resp: CommonResponse = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(resp.code, 200);
+        let req = test::TestRequest::post().uri("/update").set_json(diagram(vec![table("orders")])).to_request();
+        let resp: CommonResponse = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(resp.code, 200);
+
+        //1、新建的表id不同，按名称匹配为删除和新增
+        let req = test::TestRequest::get().uri("/diff?from=d1@1&to=d1").to_request();
+        let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(body["code"], 200);
+        assert_eq!(body["data"]["from"], "d1@1");
+        assert_eq!(body["data"]["removed_tables"], json!(["users"]));
+        assert_eq!(body["data"]["added_tables"], json!(["orders"]));
+
+        //2、同一版本没有差异
+        let req = test::TestRequest::get().uri("/diff?from=d1@2&to=d1").to_request();
+        let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(body["data"]["added_tables"], json!([]));
+        assert_eq!(body["data"]["modified_tables"], json!([]));
+
+        //3、版本或图表不存在
+        let req = test::TestRequest::get().uri("/diff?from=d1@9&to=d1").to_request();
+        let resp: CommonResponse = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(resp.code, 404);
+        let req = test::TestRequest::get().uri("/diff?from=d1@x&to=d1").to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 400);
+
+        //4、迁移脚本，方言取图表的数据库类型
+        let req = test::TestRequest::get().uri("/diff/migration?from=d1@1&to=d1").to_request();
+        let body = String::from_utf8(test::call_and_read_body(&app, req).await.to_vec()).unwrap();
+        assert!(body.starts_with("-- migrate:up\nDROP TABLE \"users\";"));
+        assert!(body.contains("-- migrate:down\nDROP TABLE \"orders\";"));
+        let req = test::TestRequest::get().uri("/diff/migration?from=d1@1&to=d1&dialect=mysql&format=migrate&number=2&name=orders").to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.headers().get("Content-Disposition").unwrap(), "attachment; filename=\"orders.zip\"");
+        let body = test::read_body(resp).await;
+        assert!(body.starts_with(b"PK"));
+        let req = test::TestRequest::get().uri("/diff/migration?from=d1@1&to=d1&format=liquibase").to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 400);
+    }
+}
@@ -10,14 +10,10 @@ pub struct Model {
     #[sea_orm(primary_key, auto_increment = false)]
     pub id: String,
     pub color: Option<String>,
-    #[sea_orm(column_type = "custom(\"NUMERIC\")", nullable)]
     pub height: Option<String>,
     pub name: Option<String>,
-    #[sea_orm(column_type = "custom(\"NUMERIC\")", nullable)]
     pub width: Option<String>,
-    #[sea_orm(column_type = "custom(\"NUMERIC\")", nullable)]
     pub x: Option<String>,
-    #[sea_orm(column_type = "custom(\"NUMERIC\")", nullable)]
     pub y: Option<String>,
 }
 
@@ -14,6 +14,10 @@ pub struct Model {
     pub name: Option<String>,
     pub pan: Option<String>,
     pub last_modified: Option<String>,
+    // 自定义类型，JSON数组
+    pub types: Option<String>,
+    // 枚举，JSON数组
+    pub enums: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -25,12 +25,12 @@ impl Model {
         note_id: Option<String>,
         reference_id: Option<String>) -> Self {
         Self { id, 
-            diagram_id: diagram_id, 
-            task_id: task_id, 
-            table_id: table_id,
-            area_id: area_id,
-            note_id: note_id,
-            reference_id:reference_id
+            diagram_id, 
+            task_id, 
+            table_id,
+            area_id,
+            note_id,
+            reference_id
         }
     }
 }
@@ -12,6 +12,7 @@ pub struct FieldWithTable {
     pub not_null: Option<bool>,
     pub primary: Option<bool>,
     pub size: Option<i32>,
+    pub values: Option<String>,
     pub r#type: Option<String>,
     pub unique: Option<bool>,
     pub table_id: Option<String>,
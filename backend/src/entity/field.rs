@@ -19,6 +19,8 @@ pub struct Model {
     pub not_null: Option<bool>,
     pub primary: Option<bool>,
     pub size: Option<i32>,
+    // ENUM、SET类型的取值，JSON数组
+    pub values: Option<String>,
     pub r#type: Option<String>,
     pub unique: Option<bool>,
     pub name: Option<String>,
@@ -1,6 +1,7 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.0
 
 use sea_orm::entity::prelude::*;
+use crate::entity::{prelude::*, table};
 
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
 #[sea_orm(table_name = "indice")]
@@ -9,9 +10,31 @@ pub struct Model {
     pub id: String,
     pub name: Option<String>,
     pub unique: Option<bool>,
+    pub table_id: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
-pub enum Relation {}
+pub enum Relation {
+    #[sea_orm(has_many = "IndiceLink")]
+    IndiceLink,
+    #[sea_orm(
+        belongs_to = "Table",
+        from = "Column::TableId",
+        to = "table::Column::Id"
+    )]
+    Table,
+}
+
+impl Related<IndiceLink> for Entity {
+    fn to() -> RelationDef {
+        Relation::IndiceLink.def()
+    }
+}
+
+impl Related<Table> for Entity {
+    fn to() -> RelationDef {
+        Relation::Table.def()
+    }
+}
 
 impl ActiveModelBehavior for ActiveModel {}
@@ -9,6 +9,7 @@ pub struct Model {
     pub id: String,
     pub field_id: Option<String>,
     pub indice_id: Option<String>,
+    pub order: Option<i32>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -5,6 +5,7 @@ pub mod prelude;
 pub mod area;
 pub mod diagram;
 pub mod diagram_link;
+pub mod diagram_version;
 pub mod field;
 pub mod indice;
 pub mod indice_link;
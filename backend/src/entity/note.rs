@@ -11,12 +11,9 @@ pub struct Model {
     pub id: String,
     pub color: Option<String>,
     pub content: Option<String>,
-    #[sea_orm(column_type = "custom(\"NUMERIC\")", nullable)]
     pub height: Option<String>,
     pub title: Option<String>,
-    #[sea_orm(column_type = "custom(\"NUMERIC\")", nullable)]
     pub x: Option<String>,
-    #[sea_orm(column_type = "custom(\"NUMERIC\")", nullable)]
     pub y: Option<String>,
 }
 
@@ -3,6 +3,7 @@
 pub use super::area::Entity as Area;
 pub use super::diagram::Entity as Diagram;
 pub use super::diagram_link::Entity as DiagramLink;
+pub use super::diagram_version::Entity as DiagramVersion;
 pub use super::field::Entity as Field;
 pub use super::indice::Entity as Indice;
 pub use super::indice_link::Entity as IndiceLink;
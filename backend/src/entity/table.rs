@@ -12,9 +12,7 @@ pub struct Model {
     pub comment: Option<String>,
     pub locked: Option<bool>,
     pub name: Option<String>,
-    #[sea_orm(column_type = "custom(\"NUMERIC\")", nullable)]
     pub x: Option<String>,
-    #[sea_orm(column_type = "custom(\"NUMERIC\")", nullable)]
     pub y: Option<String>,
 }
 
@@ -24,4 +24,41 @@ impl AreaVo {
             y: self.y.clone(),
         }
     }
-}
\ No newline at end of file
+
+    pub fn from(area: &AreaModel) -> Self {
+        Self {
+            id: area.id.clone(),
+            color: area.color.clone(),
+            height: area.height.clone(),
+            name: area.name.clone(),
+            width: area.width.clone(),
+            x: area.x.clone(),
+            y: area.y.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AreaAddVo {
+    pub diagram_id: String,
+    pub color: Option<String>,
+    pub height: Option<String>,
+    pub name: Option<String>,
+    pub width: Option<String>,
+    pub x: Option<String>,
+    pub y: Option<String>,
+}
+
+impl AreaAddVo {
+    pub fn convert_to_area_vo(&self, id: String) -> AreaVo {
+        AreaVo {
+            id,
+            color: self.color.clone(),
+            height: self.height.clone(),
+            name: self.name.clone(),
+            width: self.width.clone(),
+            x: self.x.clone(),
+            y: self.y.clone(),
+        }
+    }
+}
@@ -0,0 +1,12 @@
+use serde::{Serialize, Deserialize};
+
+/// 部署结果
+/// dry_run为true时只返回语句，不执行
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeployVo {
+    // 目标数据库文件的绝对路径
+    pub path: String,
+    pub dry_run: bool,
+    // 按执行顺序排列的DDL语句，结构一致时为空
+    pub statements: Vec<String>,
+}
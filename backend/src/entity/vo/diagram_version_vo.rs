@@ -0,0 +1,39 @@
+use serde::{Serialize, Deserialize};
+use crate::entity::diagram_version::Model as DiagramVersionModel;
+use crate::entity::vo::DiagramVo;
+
+/// 图表版本
+/// 版本列表中不包含快照，查询单个版本时返回完整的图表结构
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiagramVersionVo {
+    pub id: String,
+    pub diagram_id: String,
+    pub version: i32,
+    pub author: Option<String>,
+    pub message: Option<String>,
+    pub created_at: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snapshot: Option<DiagramVo>,
+}
+
+impl DiagramVersionVo {
+    pub fn from(version: &DiagramVersionModel) -> Self {
+        Self {
+            id: version.id.clone(),
+            diagram_id: version.diagram_id.clone(),
+            version: version.version,
+            author: version.author.clone(),
+            message: version.message.clone(),
+            created_at: version.created_at.clone(),
+            snapshot: None,
+        }
+    }
+
+    /// 包含快照的版本，快照无法解析时为None
+    pub fn from_with_snapshot(version: &DiagramVersionModel) -> Self {
+        Self {
+            snapshot: serde_json::from_str(&version.snapshot).ok(),
+            ..Self::from(version)
+        }
+    }
+}
@@ -6,7 +6,7 @@ use crate::entity::vo::area_vo::AreaVo;
 use crate::entity::vo::reference_vo::ReferenceVo;
 use crate::entity::vo::indice_vo::IndiceVo;
 use crate::entity::vo::note_vo::NoteVo;
-use crate::entity::vo::TaskVo;
+use crate::entity::vo::{EnumVo, IdMappingVo, TaskVo, TypeVo};
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct DiagramVo{
@@ -28,7 +28,11 @@ pub struct DiagramVo{
     pub tasks: Option<Vec<TaskVo>>,
     pub pan: Option<String>,
     #[serde(rename = "lastModified")]
-    pub last_modified: Option<String>
+    pub last_modified: Option<String>,
+    // 自定义类型
+    pub types: Option<Vec<TypeVo>>,
+    // 枚举
+    pub enums: Option<Vec<EnumVo>>,
 }
 
 impl DiagramVo {
@@ -40,7 +44,9 @@ impl DiagramVo {
             zoom: self.zoom.clone(),
             name: self.name.clone(),
             pan: self.pan.clone(),
-            last_modified: self.last_modified.clone()
+            last_modified: self.last_modified.clone(),
+            types: self.types.as_ref().map(|types| serde_json::to_string(types).unwrap()),
+            enums: self.enums.as_ref().map(|enums| serde_json::to_string(enums).unwrap()),
         }
     }
 
@@ -57,7 +63,9 @@ impl DiagramVo {
             notes: None,
             tasks:None,
             pan: diagram.pan.clone(),
-            last_modified: diagram.last_modified.clone()
+            last_modified: diagram.last_modified.clone(),
+            types: diagram.types.as_deref().and_then(|types| serde_json::from_str(types).ok()),
+            enums: diagram.enums.as_deref().and_then(|enums| serde_json::from_str(enums).ok()),
         }
     }
 
@@ -69,21 +77,52 @@ impl DiagramVo {
             id,
             ..Default::default()
         };
-        if let Some(_) = &self.database{
+        if self.database.is_some(){
             am.database = ActiveValue::Set(self.database.clone());
         }
-        if let Some(_) = &self.name  {
+        if self.name.is_some()  {
             am.name  = ActiveValue::Set(self.name.clone());
         }
-        if let Some(_) = &self.zoom{
+        if self.zoom.is_some(){
             am.zoom = ActiveValue::Set(self.zoom.clone());
         }
-        if let Some(_) = &self.pan{
+        if self.pan.is_some(){
             am.pan = ActiveValue::Set(self.pan.clone());
         }
-        if let Some(_) = &self.last_modified{
+        if self.last_modified.is_some(){
             am.last_modified = ActiveValue::Set(self.last_modified.clone());
         }
+        if let Some(types) = &self.types {
+            am.types = ActiveValue::Set(Some(serde_json::to_string(types).unwrap()));
+        }
+        if let Some(enums) = &self.enums {
+            am.enums = ActiveValue::Set(Some(serde_json::to_string(enums).unwrap()));
+        }
         am
     }
-}
\ No newline at end of file
+}
+
+/// 保存图表的结果
+/// id_mapping为新增数据的前端id与后端id的映射
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiagramSaveVo {
+    pub diagram: DiagramVo,
+    pub id_mapping: IdMappingVo,
+}
+
+/// 删除图表时各类数据的删除数量
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiagramDeleteVo {
+    pub id: String,
+    pub tables: u64,
+    pub fields: u64,
+    pub table_links: u64,
+    pub indices: u64,
+    pub indice_links: u64,
+    pub references: u64,
+    pub notes: u64,
+    pub areas: u64,
+    pub tasks: u64,
+    pub diagram_links: u64,
+    pub versions: u64,
+}
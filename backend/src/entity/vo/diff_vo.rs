@@ -0,0 +1,108 @@
+use serde::{Serialize, Deserialize};
+use serde_json::Value;
+
+/// 两个图表结构之间的差异
+/// 表、字段、索引、关联关系先按id匹配，再按名称匹配，输出中均使用名称
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiagramDiffVo {
+    pub from: String,
+    pub to: String,
+    pub added_tables: Vec<String>,
+    pub removed_tables: Vec<String>,
+    pub renamed_tables: Vec<RenameVo>,
+    pub modified_tables: Vec<TableDiffVo>,
+    pub added_references: Vec<ReferenceSummaryVo>,
+    pub removed_references: Vec<ReferenceSummaryVo>,
+    pub modified_references: Vec<ReferenceDiffVo>,
+}
+
+/// 重命名
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RenameVo {
+    pub from: String,
+    pub to: String,
+}
+
+/// 属性的变化，属性名称与FieldVo等结构中的字段名一致
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PropertyChangeVo {
+    pub property: String,
+    pub from: Value,
+    pub to: Value,
+}
+
+/// 同一张表在两个图表中的差异，name为目标图表中的表名
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TableDiffVo {
+    pub name: String,
+    pub changes: Vec<PropertyChangeVo>,
+    pub added_fields: Vec<ColumnSummaryVo>,
+    pub removed_fields: Vec<ColumnSummaryVo>,
+    pub renamed_fields: Vec<RenameVo>,
+    pub modified_fields: Vec<FieldDiffVo>,
+    pub added_indices: Vec<IndexSummaryVo>,
+    pub removed_indices: Vec<IndexSummaryVo>,
+    pub modified_indices: Vec<IndexDiffVo>,
+}
+
+impl TableDiffVo {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+            && self.added_fields.is_empty()
+            && self.removed_fields.is_empty()
+            && self.renamed_fields.is_empty()
+            && self.modified_fields.is_empty()
+            && self.added_indices.is_empty()
+            && self.removed_indices.is_empty()
+            && self.modified_indices.is_empty()
+    }
+}
+
+/// 新增或删除的字段
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ColumnSummaryVo {
+    pub name: String,
+    pub r#type: String,
+    pub not_null: bool,
+    pub default: Option<String>,
+}
+
+/// 字段的变化，name为目标图表中的字段名
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FieldDiffVo {
+    pub name: String,
+    pub changes: Vec<PropertyChangeVo>,
+}
+
+/// 新增或删除的索引，fields为字段名称
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexSummaryVo {
+    pub name: String,
+    pub unique: bool,
+    pub fields: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexDiffVo {
+    pub name: String,
+    pub changes: Vec<PropertyChangeVo>,
+}
+
+/// 新增或删除的关联关系，表和字段均为名称
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReferenceSummaryVo {
+    pub name: Option<String>,
+    pub start_table: String,
+    pub start_field: String,
+    pub end_table: String,
+    pub end_field: String,
+    pub cardinality: Option<String>,
+    pub update_constraint: Option<String>,
+    pub delete_constraint: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReferenceDiffVo {
+    pub name: Option<String>,
+    pub changes: Vec<PropertyChangeVo>,
+}
@@ -0,0 +1,341 @@
+use serde::{Serialize, Deserialize};
+use serde_json::Value;
+use crate::entity::vo::{AreaVo, DiagramVo, EnumVo, FieldVo, IndiceVo, NoteVo, ReferenceVo, TableVo, TaskAddVo, TypeVo};
+
+/// 前端编辑器保存、导出的图表JSON文档，与database_design.json的格式一致
+/// 表、字段的id为字符串，关联关系、主题区域、注释的id为数组下标
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DiagramDocumentVo {
+    pub title: Option<String>,
+    pub database: Option<String>,
+    pub tables: Vec<DocumentTableVo>,
+    pub relationships: Vec<DocumentReferenceVo>,
+    pub notes: Vec<DocumentNoteVo>,
+    pub subject_areas: Vec<DocumentAreaVo>,
+    pub todo_items: Vec<DocumentTaskVo>,
+    pub types: Vec<TypeVo>,
+    pub enums: Vec<EnumVo>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DocumentTableVo {
+    pub id: Value,
+    pub name: String,
+    pub x: f64,
+    pub y: f64,
+    pub locked: bool,
+    pub fields: Vec<DocumentFieldVo>,
+    pub comment: String,
+    pub indices: Vec<DocumentIndiceVo>,
+    pub color: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DocumentFieldVo {
+    pub id: Value,
+    pub name: String,
+    pub r#type: String,
+    pub default: String,
+    pub check: String,
+    pub primary: bool,
+    pub unique: bool,
+    pub not_null: bool,
+    pub increment: bool,
+    pub comment: String,
+    // 数字或字符串，未设置时为空字符串
+    pub size: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub values: Option<Vec<String>>,
+}
+
+/// 索引，fields为字段名称
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DocumentIndiceVo {
+    pub id: Value,
+    pub name: String,
+    pub unique: bool,
+    pub fields: Vec<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DocumentReferenceVo {
+    pub id: Value,
+    pub name: String,
+    pub start_table_id: Value,
+    pub start_field_id: Value,
+    pub end_table_id: Value,
+    pub end_field_id: Value,
+    pub cardinality: String,
+    pub update_constraint: String,
+    pub delete_constraint: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DocumentAreaVo {
+    pub id: Value,
+    pub name: String,
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+    pub color: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DocumentNoteVo {
+    pub id: Value,
+    pub x: f64,
+    pub y: f64,
+    pub title: String,
+    pub content: String,
+    pub color: String,
+    pub height: f64,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DocumentTaskVo {
+    pub complete: bool,
+    pub details: String,
+    pub title: String,
+    // 后端不保存优先级，导出时为0（无）
+    pub priority: i32,
+    pub order: i32,
+}
+
+impl DiagramDocumentVo {
+    /// 由图表的完整结构生成文档
+    pub fn from(diagram: &DiagramVo) -> Self {
+        Self {
+            title: diagram.name.clone(),
+            database: diagram.database.clone(),
+            tables: diagram.tables.clone().unwrap_or_default().iter().map(DocumentTableVo::from).collect(),
+            relationships: diagram.references.clone().unwrap_or_default().into_iter().enumerate()
+            .map(|(index, reference)| DocumentReferenceVo {
+                id: Value::from(index),
+                name: reference.name.unwrap_or_default(),
+                start_table_id: optional_id(reference.start_table_id),
+                start_field_id: optional_id(reference.start_field_id),
+                end_table_id: optional_id(reference.end_table_id),
+                end_field_id: optional_id(reference.end_field_id),
+                cardinality: reference.cardinality.unwrap_or_default(),
+                update_constraint: reference.update_constraint.unwrap_or_default(),
+                delete_constraint: reference.delete_constraint.unwrap_or_default(),
+            }).collect(),
+            notes: diagram.notes.clone().unwrap_or_default().into_iter().enumerate()
+            .map(|(index, note)| DocumentNoteVo {
+                id: Value::from(index),
+                x: number(&note.x),
+                y: number(&note.y),
+                title: note.title.unwrap_or_default(),
+                content: note.content.unwrap_or_default(),
+                color: note.color.unwrap_or_default(),
+                height: number(&note.height),
+            }).collect(),
+            subject_areas: diagram.areas.clone().unwrap_or_default().into_iter().enumerate()
+            .map(|(index, area)| DocumentAreaVo {
+                id: Value::from(index),
+                name: area.name.clone().unwrap_or_default(),
+                x: number(&area.x),
+                y: number(&area.y),
+                width: number(&area.width),
+                height: number(&area.height),
+                color: area.color.unwrap_or_default(),
+            }).collect(),
+            todo_items: diagram.tasks.clone().unwrap_or_default().into_iter()
+            .map(|task| DocumentTaskVo {
+                complete: task.complete.unwrap_or(false),
+                details: task.details.unwrap_or_default(),
+                title: task.title.unwrap_or_default(),
+                priority: 0,
+                order: task.order.unwrap_or_default(),
+            }).collect(),
+            types: diagram.types.clone().unwrap_or_default(),
+            enums: diagram.enums.clone().unwrap_or_default(),
+        }
+    }
+}
+
+impl DocumentTableVo {
+    pub fn from(table: &TableVo) -> Self {
+        let fields = table.fields.clone().unwrap_or_default();
+        Self {
+            id: Value::from(table.id.clone()),
+            name: table.name.clone().unwrap_or_default(),
+            x: number(&table.x),
+            y: number(&table.y),
+            locked: table.locked.unwrap_or(false),
+            indices: table.indices.clone().unwrap_or_default().into_iter().enumerate()
+            .map(|(index, indice)| DocumentIndiceVo {
+                id: Value::from(index),
+                name: indice.name.unwrap_or_default(),
+                unique: indice.unique.unwrap_or(false),
+                fields: indice.fields.unwrap_or_default().iter()
+                .filter_map(|field_id| fields.iter().find(|field| &field.id == field_id))
+                .filter_map(|field| field.name.clone())
+                .collect(),
+            }).collect(),
+            fields: fields.iter().map(DocumentFieldVo::from).collect(),
+            comment: table.comment.clone().unwrap_or_default(),
+            color: table.color.clone().unwrap_or_default(),
+        }
+    }
+
+    /// 转换为前端id的表，索引的字段名称转换为字段id
+    pub fn convert_to_table_vo(&self) -> TableVo {
+        let id = json_id(&self.id);
+        let fields = self.fields.iter()
+        .map(|field| field.convert_to_field_vo(&id))
+        .collect::<Vec<FieldVo>>();
+        let indices = self.indices.iter().map(|indice| IndiceVo {
+            id: json_id(&indice.id),
+            name: Some(indice.name.clone()),
+            unique: Some(indice.unique),
+            table_id: Some(id.clone()),
+            fields: Some(indice.fields.iter()
+                .filter_map(|name| fields.iter().find(|field| field.name.as_deref() == Some(name.as_str())))
+                .map(|field| field.id.clone())
+                .collect()),
+        }).collect();
+        TableVo {
+            id: id.clone(),
+            color: non_empty(&self.color),
+            comment: non_empty(&self.comment),
+            locked: Some(self.locked),
+            name: Some(self.name.clone()),
+            x: Some(self.x.to_string()),
+            y: Some(self.y.to_string()),
+            fields: Some(fields),
+            indices: Some(indices),
+            diagram_id: String::new(),
+        }
+    }
+}
+
+impl DocumentFieldVo {
+    pub fn from(field: &FieldVo) -> Self {
+        Self {
+            id: Value::from(field.id.clone()),
+            name: field.name.clone().unwrap_or_default(),
+            r#type: field.r#type.clone().unwrap_or_default(),
+            default: field.default.clone().unwrap_or_default(),
+            check: field.check.clone().unwrap_or_default(),
+            primary: field.primary.unwrap_or(false),
+            unique: field.unique.unwrap_or(false),
+            not_null: field.not_null.unwrap_or(false),
+            increment: field.increment.unwrap_or(false),
+            comment: field.comment.clone().unwrap_or_default(),
+            size: field.size.map(Value::from).unwrap_or_else(|| Value::from("")),
+            values: field.values.clone(),
+        }
+    }
+
+    /// size兼容数字和字符串，空字符串或无法解析的长度视为未设置
+    pub fn convert_to_field_vo(&self, table_id: &str) -> FieldVo {
+        let size = match &self.size {
+            Value::Number(size) => size.as_i64().and_then(|size| i32::try_from(size).ok()),
+            Value::String(size) => size.trim().parse().ok(),
+            _ => None,
+        };
+        FieldVo {
+            id: json_id(&self.id),
+            table_id: Some(table_id.to_string()),
+            check: non_empty(&self.check),
+            comment: non_empty(&self.comment),
+            default: non_empty(&self.default),
+            increment: Some(self.increment),
+            not_null: Some(self.not_null),
+            primary: Some(self.primary),
+            size,
+            r#type: Some(self.r#type.clone()),
+            unique: Some(self.unique),
+            name: Some(self.name.clone()),
+            values: self.values.clone(),
+        }
+    }
+}
+
+impl DocumentReferenceVo {
+    pub fn convert_to_reference_vo(&self) -> ReferenceVo {
+        ReferenceVo {
+            id: json_id(&self.id),
+            name: non_empty(&self.name),
+            start_table_id: Some(json_id(&self.start_table_id)),
+            end_table_id: Some(json_id(&self.end_table_id)),
+            cardinality: non_empty(&self.cardinality),
+            delete_constraint: non_empty(&self.delete_constraint),
+            end_field_id: Some(json_id(&self.end_field_id)),
+            start_field_id: Some(json_id(&self.start_field_id)),
+            update_constraint: non_empty(&self.update_constraint),
+        }
+    }
+}
+
+impl DocumentAreaVo {
+    pub fn convert_to_area_vo(&self) -> AreaVo {
+        AreaVo {
+            id: json_id(&self.id),
+            color: non_empty(&self.color),
+            height: Some(self.height.to_string()),
+            name: Some(self.name.clone()),
+            width: Some(self.width.to_string()),
+            x: Some(self.x.to_string()),
+            y: Some(self.y.to_string()),
+        }
+    }
+}
+
+impl DocumentNoteVo {
+    pub fn convert_to_note_vo(&self) -> NoteVo {
+        NoteVo {
+            id: json_id(&self.id),
+            content: Some(self.content.clone()),
+            color: non_empty(&self.color),
+            title: Some(self.title.clone()),
+            height: Some(self.height.to_string()),
+            x: Some(self.x.to_string()),
+            y: Some(self.y.to_string()),
+        }
+    }
+}
+
+impl DocumentTaskVo {
+    pub fn convert_to_task_add_vo(&self, diagram_id: String) -> TaskAddVo {
+        TaskAddVo {
+            diagram_id,
+            complete: Some(self.complete),
+            order: Some(self.order),
+            details: Some(self.details.clone()),
+            title: Some(self.title.clone()),
+        }
+    }
+}
+
+/// 数字或字符串形式的id统一转换为字符串
+pub fn json_id(id: &Value) -> String {
+    match id {
+        Value::String(id) => id.clone(),
+        Value::Null => String::new(),
+        id => id.to_string(),
+    }
+}
+
+fn optional_id(id: Option<String>) -> Value {
+    id.map(Value::from).unwrap_or(Value::Null)
+}
+
+/// 画布上的坐标、尺寸以字符串保存，无法解析时为0
+fn number(value: &Option<String>) -> f64 {
+    value.as_deref().and_then(|value| value.parse().ok()).unwrap_or_default()
+}
+
+fn non_empty(value: &str) -> Option<String> {
+    (!value.is_empty()).then(|| value.to_string())
+}
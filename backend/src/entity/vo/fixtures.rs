@@ -0,0 +1,65 @@
+use crate::entity::vo::{DiagramVo, FieldVo, IndiceVo, ReferenceVo, TableVo};
+
+/// 构建测试用的字段，约束均为false，按需在测试中修改
+pub fn build_field(id: &str, name: &str, r#type: &str) -> FieldVo {
+    FieldVo {
+        id: id.to_string(),
+        table_id: None,
+        check: None,
+        comment: None,
+        default: None,
+        increment: Some(false),
+        not_null: Some(false),
+        primary: Some(false),
+        size: None,
+        r#type: Some(r#type.to_string()),
+        unique: Some(false),
+        name: Some(name.to_string()),
+        values: None,
+    }
+}
+
+/// 构建测试用的主键字段
+pub fn build_primary_field(id: &str, name: &str, r#type: &str) -> FieldVo {
+    FieldVo {
+        primary: Some(true),
+        not_null: Some(true),
+        ..build_field(id, name, r#type)
+    }
+}
+
+/// 构建测试用的表，归属图表d1
+pub fn build_table(id: &str, name: &str, fields: Vec<FieldVo>, indices: Vec<IndiceVo>) -> TableVo {
+    TableVo {
+        id: id.to_string(),
+        color: None,
+        comment: None,
+        locked: Some(false),
+        name: Some(name.to_string()),
+        x: None,
+        y: None,
+        fields: Some(fields),
+        indices: Some(indices),
+        diagram_id: "d1".to_string(),
+    }
+}
+
+/// 构建测试用的图表d1
+pub fn build_diagram(tables: Vec<TableVo>, references: Vec<ReferenceVo>) -> DiagramVo {
+    DiagramVo {
+        id: "d1".to_string(),
+        zoom: None,
+        database: None,
+        name: None,
+        tables: Some(tables),
+        areas: None,
+        references: Some(references),
+        indices: None,
+        notes: None,
+        tasks: None,
+        pan: None,
+        last_modified: None,
+        types: None,
+        enums: None,
+    }
+}
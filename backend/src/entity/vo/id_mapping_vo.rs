@@ -0,0 +1,54 @@
+use std::collections::HashMap;
+use serde::{Serialize, Deserialize};
+use crate::entity::vo::ReferenceVo;
+
+/// 前端生成的id与后端生成的id的映射
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IdMappingVo {
+    // 表的前端id -> 后端id
+    pub tables: HashMap<String, String>,
+    // 前端的字段id只在表内唯一，按表的前端id分组：表的前端id -> (字段的前端id -> 后端id)
+    pub fields: HashMap<String, HashMap<String, String>>,
+    // 关联关系的前端id -> 后端id
+    pub references: HashMap<String, String>,
+    // 主题区域的前端id -> 后端id
+    pub areas: HashMap<String, String>,
+    // 注释的前端id -> 后端id
+    pub notes: HashMap<String, String>,
+}
+
+impl IdMappingVo {
+    pub fn merge(&mut self, other: IdMappingVo) {
+        self.tables.extend(other.tables);
+        self.fields.extend(other.fields);
+        self.references.extend(other.references);
+        self.areas.extend(other.areas);
+        self.notes.extend(other.notes);
+    }
+
+    /// 为新增的关联关系生成后端id，并记录到映射中
+    pub fn assign_reference_id(&mut self, reference: &mut ReferenceVo, id: String) {
+        self.references.insert(reference.id.clone(), id.clone());
+        reference.id = id;
+    }
+
+    /// 将关联关系中引用的前端表id、字段id替换为后端id
+    /// 映射中不存在的id保持不变
+    pub fn apply_to_reference(&self, reference: &mut ReferenceVo) {
+        self.remap(&mut reference.start_table_id, &mut reference.start_field_id);
+        self.remap(&mut reference.end_table_id, &mut reference.end_field_id);
+    }
+
+    fn remap(&self, table_id: &mut Option<String>, field_id: &mut Option<String>) {
+        let Some(client_table_id) = table_id.clone() else {
+            return;
+        };
+        if let Some(server_field_id) = field_id.as_ref()
+            .and_then(|id| self.fields.get(&client_table_id)?.get(id)) {
+            *field_id = Some(server_field_id.clone());
+        }
+        if let Some(server_table_id) = self.tables.get(&client_table_id) {
+            *table_id = Some(server_table_id.clone());
+        }
+    }
+}
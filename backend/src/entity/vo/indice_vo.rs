@@ -1,10 +1,14 @@
 use crate::entity::indice::Model as IndiceModel;
+use crate::entity::indice_link::Model as IndiceLinkModel;
 use serde::{Serialize, Deserialize};
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct IndiceVo {
     pub id: String,
     pub name: Option<String>,
     pub unique: Option<bool>,
+    pub table_id: Option<String>,
+    // 索引包含的字段id，按索引列顺序排列
+    pub fields: Option<Vec<String>>,
 }
 
 impl IndiceVo {
@@ -12,7 +16,27 @@ impl IndiceVo {
         IndiceModel {
             id: self.id.clone(),
             name: self.name.clone(),
-            unique: self.unique.clone(),    
+            unique: self.unique,
+            table_id: self.table_id.clone(),
         }
     }
-}
\ No newline at end of file
+
+    pub fn build_from_indice(indice: &IndiceModel, fields: Option<Vec<String>>) -> Self {
+        Self {
+            id: indice.id.clone(),
+            name: indice.name.clone(),
+            unique: indice.unique,
+            table_id: indice.table_id.clone(),
+            fields,
+        }
+    }
+}
+
+pub fn build_indice_link(id: String, indice_id: String, field_id: String, order: i32) -> IndiceLinkModel {
+    IndiceLinkModel {
+        id,
+        field_id: Some(field_id),
+        indice_id: Some(indice_id),
+        order: Some(order),
+    }
+}
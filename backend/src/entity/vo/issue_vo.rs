@@ -0,0 +1,44 @@
+use serde::{Serialize, Deserialize};
+
+/// 问题的严重程度
+/// error会导致生成的DDL无法执行，warning只是提示
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IssueSeverity {
+    Error,
+    Warning,
+}
+
+/// 图表结构校验发现的问题
+/// code与前端issues.js的多语言key一致，便于前端翻译
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IssueVo {
+    pub severity: IssueSeverity,
+    pub code: String,
+    pub table_id: Option<String>,
+    pub field_id: Option<String>,
+    pub message: String,
+}
+
+impl IssueVo {
+    pub fn error(code: &str, message: String) -> Self {
+        Self { severity: IssueSeverity::Error, code: code.to_string(), table_id: None, field_id: None, message }
+    }
+
+    pub fn warning(code: &str, message: String) -> Self {
+        Self { severity: IssueSeverity::Warning, code: code.to_string(), table_id: None, field_id: None, message }
+    }
+
+    /// 问题所在的表
+    pub fn on_table(mut self, table_id: &str) -> Self {
+        self.table_id = Some(table_id.to_string());
+        self
+    }
+
+    /// 问题所在的字段
+    pub fn on_field(mut self, table_id: &str, field_id: &str) -> Self {
+        self.table_id = Some(table_id.to_string());
+        self.field_id = Some(field_id.to_string());
+        self
+    }
+}
@@ -5,10 +5,26 @@ mod area_vo;
 mod note_vo;
 mod reference_vo;
 mod indice_vo;
+mod id_mapping_vo;
+mod type_vo;
+mod document_vo;
+mod issue_vo;
+mod diagram_version_vo;
+mod diff_vo;
+mod deploy_vo;
+#[cfg(test)]
+pub mod fixtures;
 pub use task_vo::*;
 pub use diagram_vo::*;
 pub use table_vo::*;
 pub use area_vo::*;
 pub use note_vo::*;
 pub use reference_vo::*;
-pub use indice_vo::*;
\ No newline at end of file
+pub use indice_vo::*;
+pub use id_mapping_vo::*;
+pub use type_vo::*;
+pub use document_vo::*;
+pub use issue_vo::*;
+pub use diagram_version_vo::*;
+pub use diff_vo::*;
+pub use deploy_vo::*;
@@ -24,4 +24,41 @@ impl NoteVo {
             y: self.y.clone(),
         }
     }
-}
\ No newline at end of file
+
+    pub fn from(note: &NoteModel) -> Self {
+        Self {
+            id: note.id.clone(),
+            content: note.content.clone(),
+            color: note.color.clone(),
+            title: note.title.clone(),
+            height: note.height.clone(),
+            x: note.x.clone(),
+            y: note.y.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NoteAddVo {
+    pub diagram_id: String,
+    pub content: Option<String>,
+    pub color: Option<String>,
+    pub title: Option<String>,
+    pub height: Option<String>,
+    pub x: Option<String>,
+    pub y: Option<String>,
+}
+
+impl NoteAddVo {
+    pub fn convert_to_note_vo(&self, id: String) -> NoteVo {
+        NoteVo {
+            id,
+            content: self.content.clone(),
+            color: self.color.clone(),
+            title: self.title.clone(),
+            height: self.height.clone(),
+            x: self.x.clone(),
+            y: self.y.clone(),
+        }
+    }
+}
@@ -27,5 +27,19 @@ impl ReferenceVo {
             update_constraint: self.update_constraint.clone(),
         }
     }
+
+    pub fn from(reference: &ReferenceModel) -> Self {
+        Self {
+            id: reference.id.clone(),
+            name: reference.name.clone(),
+            start_table_id: reference.start_table_id.clone(),
+            end_table_id: reference.end_table_id.clone(),
+            cardinality: reference.cardinality.clone(),
+            delete_constraint: reference.delete_constraint.clone(),
+            end_field_id: reference.end_field_id.clone(),
+            start_field_id: reference.start_field_id.clone(),
+            update_constraint: reference.update_constraint.clone(),
+        }
+    }
 }
 
@@ -1,11 +1,9 @@
-use sea_orm::ActiveValue;
 use serde::{Deserialize, Serialize};    
-use crate::entity::diagram;
 use crate::entity::dto::FieldWithTable;
 use crate::entity::field::Model as FieldModel;
 use crate::entity::table::Model as TableModel;
-use crate::entity::table::ActiveModel as TableActiveModel;
 use crate::entity::table_link::Model as TableLinkModel;
+use crate::entity::vo::{IndiceVo, ReferenceVo};
 
 
 #[derive(Serialize, Deserialize,Clone,Debug,PartialEq,Eq)]
@@ -18,6 +16,7 @@ pub struct TableVo {
     pub x: Option<String>,
     pub y: Option<String>,
     pub fields: Option<Vec<FieldVo>>,
+    pub indices: Option<Vec<IndiceVo>>,
     pub diagram_id:String
 }
 
@@ -28,48 +27,35 @@ impl TableVo {
             name: self.name.clone(),
             color: self.color.clone(),
             comment: self.comment.clone(),
-            locked: self.locked.clone(),
+            locked: self.locked,
             x: self.x.clone(),
             y: self.y.clone(),
         }
     }
 
-    pub fn from(table: &TableModel,diagram_id:String,fields:Option<Vec<FieldVo>>) -> Self {
-        Self {
-            id: table.id.clone(),
-            name: table.name.clone(),
-            color: table.color.clone(),
-            comment: table.comment.clone(),
-            locked: table.locked.clone(),
-            x: table.x.clone(),
-            y: table.y.clone(),
-            fields: fields,
-            diagram_id: diagram_id,   
-        }
-    }
-
     pub fn build_from_table(table: TableModel,fields: Option<Vec<FieldVo>>,diagram_id:String) -> Self {
         Self {
             id: table.id.clone(),
             name: table.name.clone(),
-            fields: fields,
+            fields,
             color: table.color.clone(),
             comment: table.comment.clone(),
-            locked: table.locked.clone(),
+            locked: table.locked,
             x: table.x.clone(),
             y: table.y.clone(),
-            diagram_id: diagram_id
+            indices: None,
+            diagram_id
         }
     }
+}
 
-    pub fn convert_to_active_model(&self) -> TableActiveModel{
-        let id = ActiveValue::Set(self.id.clone());
-        let am:TableActiveModel = TableActiveModel{
-            id,
-            ..Default::default()
-        };
-        am
-    }
+/// 批量新增表结构的请求
+/// references中引用的前端表id、字段id会被替换为后端id
+#[derive(Serialize, Deserialize,Clone,Debug,PartialEq,Eq)]
+pub struct TableBatchAddVo {
+    pub diagram_id: String,
+    pub tables: Vec<TableVo>,
+    pub references: Option<Vec<ReferenceVo>>,
 }
 
 #[derive(Serialize, Deserialize,Clone,Debug,PartialEq,Eq)]
@@ -86,6 +72,8 @@ pub struct FieldVo {
     pub r#type: Option<String>,
     pub unique: Option<bool>,
     pub name: Option<String>,
+    // ENUM、SET类型的取值
+    pub values: Option<Vec<String>>,
 }
 
 
@@ -97,12 +85,13 @@ impl FieldVo {
             check: self.check.clone(),
             comment: self.comment.clone(),
             default: self.default.clone(),
-            increment: self.increment.clone(),
-            not_null: self.not_null.clone(),
-            primary: self.primary.clone(),  
-            size: self.size.clone(),
+            increment: self.increment,
+            not_null: self.not_null,
+            primary: self.primary,  
+            size: self.size,
+            values: self.values.as_ref().map(|values| serde_json::to_string(values).unwrap()),
             r#type: self.r#type.clone(),
-            unique: self.unique.clone(),
+            unique: self.unique,
             name: self.name.clone(),
         }
     }
@@ -115,13 +104,14 @@ impl FieldVo {
             check: field.check.clone(),
             comment: field.comment.clone(),
             default: field.default.clone(),
-            increment: field.increment.clone(),
-            not_null: field.not_null.clone(),
-            primary: field.primary.clone(),
-            size: field.size.clone(),
+            increment: field.increment,
+            not_null: field.not_null,
+            primary: field.primary,
+            size: field.size,
             r#type: field.r#type.clone(),
-            unique: field.unique.clone(),
+            unique: field.unique,
             name: field.name.clone(),
+            values: field.values.as_deref().and_then(|values| serde_json::from_str(values).ok()),
         }
     }
 }
@@ -1,4 +1,4 @@
-use crate::entity::{diagram, task::{ActiveModel as Task, Model as TaskModel}};
+use crate::entity::task::{ActiveModel as Task, Model as TaskModel};
 use sea_orm::ActiveValue;
 use serde::{Deserialize, Serialize};
 
@@ -17,11 +17,11 @@ impl TaskVo {
     pub fn from_option(task: &TaskModel,diagram_id: String) -> Self {
         Self {
             id: task.id.clone(),
-            complete: task.complete.clone(),
+            complete: task.complete,
             order: task.order,
             details: task.details.clone(),
             title: task.title.clone(),
-            diagram_id:diagram_id
+            diagram_id
         }
     }
 }
@@ -57,15 +57,6 @@ pub struct TaskUpdateVo {
 }
 
 impl TaskUpdateVo {
-    pub fn convert_to_task(&self) -> TaskModel {
-        TaskModel {
-            id: self.id.clone(),
-            complete: self.complete,
-            order: self.order,
-            details: self.details.clone(),
-            title: self.title.clone(),
-        }
-    }
     pub fn convert_to_active_model(&self) -> Task {
         let id = ActiveValue::Set(self.id.clone());
         // - 主键要用 Set 或 Unchanged，确保 SQL 里有 WHERE
@@ -79,10 +70,10 @@ impl TaskUpdateVo {
                 am.title = ActiveValue::Set(Some(t.clone()));
             }
             if let Some(c) = &self.complete {
-                am.complete = ActiveValue::Set(Some(c.clone()));
+                am.complete = ActiveValue::Set(Some(*c));
             }
             if let Some(o) = &self.order {
-                am.order = ActiveValue::Set(Some(o.clone()));
+                am.order = ActiveValue::Set(Some(*o));
             }
             if let Some(d) = &self.details {
                 am.details = ActiveValue::Set(Some(d.clone()));
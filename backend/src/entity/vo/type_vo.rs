@@ -0,0 +1,26 @@
+use serde::{Serialize, Deserialize};
+
+/// 自定义类型，PostgreSQL的复合类型
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TypeVo {
+    pub name: String,
+    pub fields: Vec<TypeFieldVo>,
+    #[serde(default)]
+    pub comment: String,
+}
+
+/// 自定义类型中的字段
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TypeFieldVo {
+    pub name: String,
+    pub r#type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub values: Option<Vec<String>>,
+}
+
+/// 枚举
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EnumVo {
+    pub name: String,
+    pub values: Vec<String>,
+}
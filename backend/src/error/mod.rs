@@ -3,6 +3,7 @@ use actix_web::{HttpResponse, ResponseError};
 /// 错误处理模块
 /// 定义自己的错误类型
 #[derive(Debug, thiserror::Error)]
+#[allow(clippy::enum_variant_names)]
 pub enum DrawDBError {
     /// 数据库错误
     #[error("数据库错误: {0}")]
@@ -12,11 +13,15 @@ pub enum DrawDBError {
     IoError(#[from] std::io::Error),
     /// 配置错误
     #[error("配置错误: {0}")]
-    ConfigError(#[from] toml::de::Error),
+    ConfigError(String),
 
     #[allow(dead_code)]
     #[error("解构错误：{0}")]
     DeconstructError(String),
+
+    /// 校验错误，请求的数据不满足约束
+    #[error("校验错误: {0}")]
+    ValidationError(String),
     
     /// 其他错误
     #[error("其他错误: {0}")]
@@ -40,6 +45,9 @@ impl ResponseError for DrawDBError {
             DrawDBError::DeconstructError(e) => {
                 HttpResponse::InternalServerError().json(format!("解构错误: {}", e))
             }
+            DrawDBError::ValidationError(e) => {
+                HttpResponse::BadRequest().json(format!("校验错误: {}", e))
+            }
         }
     }
 }
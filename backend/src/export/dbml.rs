@@ -0,0 +1,230 @@
+use std::collections::HashMap;
+use crate::entity::vo::{AreaVo, DiagramVo, FieldVo, TableVo};
+use crate::export::Dialect;
+
+/// 生成图表的DBML
+/// 依次输出Project、Table、Ref、TableGroup以及注释对应的Note
+pub fn to_dbml(diagram: &DiagramVo) -> String {
+    let tables = diagram.tables.clone().unwrap_or_default();
+    let tables_by_id = tables.iter()
+    .map(|table| (table.id.as_str(), table))
+    .collect::<HashMap<&str, &TableVo>>();
+    let mut blocks = Vec::new();
+
+    //1、项目，记录数据库类型
+    if let Ok(dialect) = Dialect::resolve(None, diagram.database.as_deref()) {
+        blocks.push(format!(
+            "Project {} {{\n\tdatabase_type: {}\n}}",
+            dbml_ident(diagram.name.as_deref().unwrap_or("project")),
+            dbml_string(dialect.dbml_database_type())
+        ));
+    }
+    //2、表
+    blocks.extend(tables.iter().map(table_block));
+    //3、关联关系
+    for reference in diagram.references.clone().unwrap_or_default() {
+        let endpoint = |table_id: &Option<String>, field_id: &Option<String>| {
+            let table = tables_by_id.get(table_id.as_deref()?)?;
+            let field = table.fields.as_ref()?.iter().find(|field| Some(&field.id) == field_id.as_ref())?;
+            Some(format!("{}.{}", dbml_ident(&table_name(table)), dbml_ident(&field_name(field))))
+        };
+        let (Some(start), Some(end)) = (
+            endpoint(&reference.start_table_id, &reference.start_field_id),
+            endpoint(&reference.end_table_id, &reference.end_field_id)
+        ) else {
+            continue;
+        };
+        let operator = match reference.cardinality.as_deref() {
+            Some("one_to_one") => "-",
+            Some("one_to_many") => "<",
+            _ => ">",
+        };
+        let settings = [("delete", &reference.delete_constraint), ("update", &reference.update_constraint)]
+        .into_iter()
+        .filter_map(|(key, value)| value.as_ref()
+            .filter(|value| !value.trim().is_empty())
+            .map(|value| format!("{}: {}", key, value.to_lowercase())))
+        .collect::<Vec<String>>();
+        let name = reference.name.as_deref()
+        .filter(|name| !name.trim().is_empty())
+        .map(|name| format!(" {}", dbml_ident(name)))
+        .unwrap_or_default();
+        blocks.push(format!("Ref{}: {} {} {}{}", name, start, operator, end, settings_suffix(&settings)));
+    }
+    //4、主题区域转换为TableGroup，包含左上角位于区域内的表
+    for area in diagram.areas.clone().unwrap_or_default() {
+        let members = tables.iter()
+        .filter(|table| area_contains(&area, table))
+        .map(|table| format!("\t{}", dbml_ident(&table_name(table))))
+        .collect::<Vec<String>>();
+        if members.is_empty() {
+            continue;
+        }
+        let name = area.name.clone().unwrap_or_else(|| area.id.clone());
+        blocks.push(format!("TableGroup {} {{\n{}\n}}", dbml_ident(&name), members.join("\n")));
+    }
+    //5、注释
+    for note in diagram.notes.clone().unwrap_or_default() {
+        let name = note.title.clone().filter(|title| !title.trim().is_empty()).unwrap_or_else(|| note.id.clone());
+        blocks.push(format!(
+            "Note {} {{\n\t{}\n}}",
+            dbml_ident(&name),
+            dbml_string(note.content.as_deref().unwrap_or_default())
+        ));
+    }
+    blocks.join("\n\n") + "\n"
+}
+
+/// Table name { 列; indexes; Note }
+fn table_block(table: &TableVo) -> String {
+    let fields = table.fields.clone().unwrap_or_default();
+    let primary_keys = fields.iter().filter(|field| field.primary.unwrap_or(false)).count();
+    let mut lines = fields.iter()
+    .map(|field| format!("\t{}", column_line(field, primary_keys == 1)))
+    .collect::<Vec<String>>();
+
+    // 复合主键写在indexes中
+    let mut indexes = Vec::new();
+    if primary_keys > 1 {
+        let columns = fields.iter()
+        .filter(|field| field.primary.unwrap_or(false))
+        .map(|field| dbml_ident(&field_name(field)))
+        .collect::<Vec<String>>();
+        indexes.push(format!("\t\t({}) [pk]", columns.join(", ")));
+    }
+    for indice in table.indices.clone().unwrap_or_default() {
+        let columns = indice.fields.clone().unwrap_or_default().iter()
+        .filter_map(|field_id| fields.iter().find(|field| &field.id == field_id))
+        .map(|field| dbml_ident(&field_name(field)))
+        .collect::<Vec<String>>();
+        let mut settings = Vec::new();
+        if let Some(name) = &indice.name {
+            settings.push(format!("name: {}", dbml_string(name)));
+        }
+        if indice.unique.unwrap_or(false) {
+            settings.push("unique".to_string());
+        }
+        match columns.as_slice() {
+            [] => continue,
+            [column] => indexes.push(format!("\t\t{}{}", column, settings_suffix(&settings))),
+            columns => indexes.push(format!("\t\t({}){}", columns.join(", "), settings_suffix(&settings))),
+        }
+    }
+    if !indexes.is_empty() {
+        lines.push(format!("\n\tindexes {{\n{}\n\t}}", indexes.join("\n")));
+    }
+    if let Some(comment) = table.comment.as_ref().filter(|comment| !comment.trim().is_empty()) {
+        lines.push(format!("\n\tNote: {}", dbml_string(comment)));
+    }
+    format!("Table {} {{\n{}\n}}", dbml_ident(&table_name(table)), lines.join("\n"))
+}
+
+/// 列：名称 类型 [设置]
+fn column_line(field: &FieldVo, inline_primary_key: bool) -> String {
+    let mut r#type = field.r#type.clone().unwrap_or_default().trim().to_lowercase();
+    if let Some(size) = field.size.filter(|size| *size > 0) {
+        r#type = format!("{}({})", r#type, size);
+    }
+    let mut settings = Vec::new();
+    if inline_primary_key && field.primary.unwrap_or(false) {
+        settings.push("pk".to_string());
+    }
+    if field.increment.unwrap_or(false) {
+        settings.push("increment".to_string());
+    }
+    if field.not_null.unwrap_or(false) {
+        settings.push("not null".to_string());
+    }
+    if field.unique.unwrap_or(false) {
+        settings.push("unique".to_string());
+    }
+    if let Some(default) = field.default.as_ref().filter(|default| !default.trim().is_empty()) {
+        settings.push(format!("default: {}", dbml_default(default)));
+    }
+    if let Some(check) = field.check.as_ref().filter(|check| !check.trim().is_empty()) {
+        settings.push(format!("check: `{}`", check));
+    }
+    if let Some(comment) = field.comment.as_ref().filter(|comment| !comment.trim().is_empty()) {
+        settings.push(format!("note: {}", dbml_string(comment)));
+    }
+    format!("{} {}{}", dbml_ident(&field_name(field)), dbml_type(&r#type), settings_suffix(&settings))
+}
+
+/// 默认值：数字和布尔值原样输出，函数调用等表达式使用反引号，其余作为字符串
+fn dbml_default(default: &str) -> String {
+    let default = default.trim();
+    let lower = default.to_lowercase();
+    if ["null", "true", "false"].contains(&lower.as_str()) || default.parse::<f64>().is_ok() {
+        return lower;
+    }
+    let quoted = default.len() >= 2
+        && ['\'', '"'].iter().any(|quote| default.starts_with(*quote) && default.ends_with(*quote));
+    if quoted {
+        return dbml_string(&default[1..default.len() - 1]);
+    }
+    if default.contains('(') || lower == "current_timestamp" {
+        return format!("`{}`", default);
+    }
+    dbml_string(default)
+}
+
+fn settings_suffix(settings: &[String]) -> String {
+    if settings.is_empty() {
+        String::new()
+    } else {
+        format!(" [{}]", settings.join(", "))
+    }
+}
+
+/// 不是简单标识符时加双引号
+fn dbml_ident(name: &str) -> String {
+    let simple = name.chars().next().is_some_and(|c| c.is_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_alphanumeric() || c == '_');
+    if simple {
+        name.to_string()
+    } else {
+        format!("\"{}\"", name.replace('\\', "\\\\").replace('"', "\\\""))
+    }
+}
+
+/// 类型中包含空格等字符时加双引号，长度参数保留在引号外
+fn dbml_type(r#type: &str) -> String {
+    let (name, args) = match r#type.find('(') {
+        Some(index) => r#type.split_at(index),
+        None => (r#type, ""),
+    };
+    let simple = name.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '[' || c == ']');
+    if simple && !name.is_empty() {
+        r#type.to_string()
+    } else {
+        format!("\"{}\"{}", name, args)
+    }
+}
+
+/// 字符串，多行内容使用三引号
+fn dbml_string(value: &str) -> String {
+    if value.contains('\n') {
+        format!("'''{}'''", value.replace('\\', "\\\\").replace("'''", "\\'''"))
+    } else {
+        format!("'{}'", value.replace('\\', "\\\\").replace('\'', "\\'"))
+    }
+}
+
+/// 表的左上角是否位于主题区域内
+pub(crate) fn area_contains(area: &AreaVo, table: &TableVo) -> bool {
+    let number = |value: &Option<String>| value.as_deref().and_then(|value| value.parse::<f64>().ok());
+    let (Some(x), Some(y), Some(width), Some(height), Some(table_x), Some(table_y)) = (
+        number(&area.x), number(&area.y), number(&area.width), number(&area.height), number(&table.x), number(&table.y)
+    ) else {
+        return false;
+    };
+    table_x >= x && table_x <= x + width && table_y >= y && table_y <= y + height
+}
+
+fn table_name(table: &TableVo) -> String {
+    table.name.clone().unwrap_or_else(|| table.id.clone())
+}
+
+fn field_name(field: &FieldVo) -> String {
+    field.name.clone().unwrap_or_else(|| field.id.clone())
+}
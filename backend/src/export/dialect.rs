@@ -0,0 +1,87 @@
+use std::fmt::Display;
+use std::str::FromStr;
+use crate::error::DrawDBError;
+
+/// 支持导出的数据库方言
+/// 同时兼容前端DB常量中的取值，如postgresql、transactsql
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Dialect {
+    MySql,
+    Postgres,
+    Sqlite,
+    MariaDb,
+    MsSql,
+    OracleSql,
+}
+
+impl Dialect {
+    /// 根据请求参数确定方言，未指定时使用图表保存的数据库类型
+    pub fn resolve(dialect: Option<&str>, database: Option<&str>) -> Result<Self, DrawDBError> {
+        match dialect.or(database) {
+            Some(value) => value.parse(),
+            None => Err(DrawDBError::ValidationError("未指定数据库方言".to_string())),
+        }
+    }
+
+    /// 前端DB常量中对应的取值，保存在diagram.database中
+    pub fn database(&self) -> &'static str {
+        match self {
+            Dialect::MySql => "mysql",
+            Dialect::Postgres => "postgresql",
+            Dialect::Sqlite => "sqlite",
+            Dialect::MariaDb => "mariadb",
+            Dialect::MsSql => "transactsql",
+            Dialect::OracleSql => "oraclesql",
+        }
+    }
+
+    /// DBML中Project的database_type
+    pub fn dbml_database_type(&self) -> &'static str {
+        match self {
+            Dialect::MySql => "MySQL",
+            Dialect::Postgres => "PostgreSQL",
+            Dialect::Sqlite => "SQLite",
+            Dialect::MariaDb => "MariaDB",
+            Dialect::MsSql => "SQL Server",
+            Dialect::OracleSql => "Oracle",
+        }
+    }
+
+    /// 引用标识符，标识符中的引号会被转义
+    pub fn quote(&self, ident: &str) -> String {
+        match self {
+            Dialect::MySql | Dialect::MariaDb => format!("`{}`", ident.replace('`', "``")),
+            Dialect::MsSql => format!("[{}]", ident.replace(']', "]]")),
+            _ => format!("\"{}\"", ident.replace('"', "\"\"")),
+        }
+    }
+}
+
+impl FromStr for Dialect {
+    type Err = DrawDBError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_lowercase().as_str() {
+            "mysql" => Ok(Dialect::MySql),
+            "postgres" | "postgresql" => Ok(Dialect::Postgres),
+            "sqlite" => Ok(Dialect::Sqlite),
+            "mariadb" => Ok(Dialect::MariaDb),
+            "mssql" | "transactsql" | "sql server" => Ok(Dialect::MsSql),
+            "oraclesql" | "oracle" => Ok(Dialect::OracleSql),
+            other => Err(DrawDBError::ValidationError(format!("不支持的数据库方言: {}", other))),
+        }
+    }
+}
+
+impl Display for Dialect {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Dialect::MySql => write!(f, "mysql"),
+            Dialect::Postgres => write!(f, "postgres"),
+            Dialect::Sqlite => write!(f, "sqlite"),
+            Dialect::MariaDb => write!(f, "mariadb"),
+            Dialect::MsSql => write!(f, "mssql"),
+            Dialect::OracleSql => write!(f, "oraclesql"),
+        }
+    }
+}
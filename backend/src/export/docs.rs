@@ -0,0 +1,292 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+use crate::entity::vo::{DiagramVo, FieldVo, ReferenceVo, TableVo};
+use crate::error::DrawDBError;
+use crate::export::Dialect;
+
+/// 数据字典文档的格式
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DocFormat {
+    Markdown,
+    Html,
+}
+
+impl FromStr for DocFormat {
+    type Err = DrawDBError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "md" | "markdown" => Ok(DocFormat::Markdown),
+            "html" | "htm" => Ok(DocFormat::Html),
+            other => Err(DrawDBError::ValidationError(format!("不支持的文档格式: {}", other))),
+        }
+    }
+}
+
+/// 文档内容块，与输出格式无关
+enum Block {
+    Heading(usize, String),
+    Paragraph(String),
+    List(Vec<String>),
+    Table(Vec<&'static str>, Vec<Vec<String>>),
+}
+
+/// 生成图表的数据字典
+/// 每张表一节，包含表注释、列、索引以及引出和引入的外键
+pub fn to_docs(diagram: &DiagramVo, format: DocFormat) -> String {
+    let blocks = document(diagram);
+    match format {
+        DocFormat::Markdown => to_markdown(&blocks),
+        DocFormat::Html => to_html(diagram_title(diagram), &blocks),
+    }
+}
+
+fn document(diagram: &DiagramVo) -> Vec<Block> {
+    let tables = diagram.tables.clone().unwrap_or_default();
+    let references = diagram.references.clone().unwrap_or_default();
+    let tables_by_id = tables.iter()
+    .map(|table| (table.id.as_str(), table))
+    .collect::<HashMap<&str, &TableVo>>();
+    let mut blocks = vec![Block::Heading(1, format!("{} documentation", diagram_title(diagram)))];
+
+    //1、概要：数据库类型和表清单
+    if let Ok(dialect) = Dialect::resolve(None, diagram.database.as_deref()) {
+        blocks.push(Block::Paragraph(format!("Database system: {}", dialect.dbml_database_type())));
+    }
+    blocks.push(Block::Heading(2, "Tables".to_string()));
+    blocks.push(Block::List(tables.iter().map(table_name).collect()));
+
+    //2、每张表的结构
+    for table in &tables {
+        let fields = table.fields.clone().unwrap_or_default();
+        blocks.push(Block::Heading(2, table_name(table)));
+        if let Some(comment) = table.comment.as_ref().filter(|comment| !comment.trim().is_empty()) {
+            blocks.push(Block::Paragraph(comment.clone()));
+        }
+
+        blocks.push(Block::Heading(3, "Columns".to_string()));
+        let columns = fields.iter().map(|field| vec![
+            field_name(field),
+            field.r#type.clone().unwrap_or_default(),
+            field.size.filter(|size| *size > 0).map(|size| size.to_string()).unwrap_or_default(),
+            yes_no(!field.not_null.unwrap_or(false) && !field.primary.unwrap_or(false)),
+            field.default.clone().unwrap_or_default(),
+            field.check.clone().unwrap_or_default(),
+            field.comment.clone().unwrap_or_default(),
+        ]).collect();
+        blocks.push(Block::Table(vec!["Name", "Type", "Size", "Nullable", "Default", "Check", "Comment"], columns));
+
+        // 主键作为PRIMARY索引列出
+        let mut indexes = Vec::new();
+        let primary_keys = fields.iter()
+        .filter(|field| field.primary.unwrap_or(false))
+        .map(field_name)
+        .collect::<Vec<String>>();
+        if !primary_keys.is_empty() {
+            indexes.push(vec!["PRIMARY".to_string(), yes_no(true), primary_keys.join(", ")]);
+        }
+        for indice in table.indices.clone().unwrap_or_default() {
+            let columns = indice.fields.clone().unwrap_or_default().iter()
+            .filter_map(|field_id| fields.iter().find(|field| &field.id == field_id))
+            .map(field_name)
+            .collect::<Vec<String>>();
+            indexes.push(vec![
+                indice.name.clone().unwrap_or_else(|| indice.id.clone()),
+                yes_no(indice.unique.unwrap_or(false)),
+                columns.join(", "),
+            ]);
+        }
+        if !indexes.is_empty() {
+            blocks.push(Block::Heading(3, "Indexes".to_string()));
+            blocks.push(Block::Table(vec!["Name", "Unique", "Fields"], indexes));
+        }
+
+        // 外键：起点表为引出，终点表为引入
+        let foreign_keys = |outbound: bool| references.iter().filter_map(|reference| {
+            let (own, other) = if outbound {
+                (reference.start_table_id.as_deref()?, reference.end_table_id.as_deref()?)
+            } else {
+                (reference.end_table_id.as_deref()?, reference.start_table_id.as_deref()?)
+            };
+            if own != table.id {
+                return None;
+            }
+            let other = tables_by_id.get(other)?;
+            let (own_field, other_field) = if outbound {
+                (reference.start_field_id.as_deref()?, reference.end_field_id.as_deref()?)
+            } else {
+                (reference.end_field_id.as_deref()?, reference.start_field_id.as_deref()?)
+            };
+            Some(foreign_key_row(reference, field_name_by_id(table, own_field)?, other, field_name_by_id(other, other_field)?))
+        }).collect::<Vec<Vec<String>>>();
+        let outbound = foreign_keys(true);
+        if !outbound.is_empty() {
+            blocks.push(Block::Heading(3, "Foreign keys".to_string()));
+            blocks.push(Block::Table(vec!["Name", "Column", "References", "Cardinality", "On update", "On delete"], outbound));
+        }
+        let inbound = foreign_keys(false);
+        if !inbound.is_empty() {
+            blocks.push(Block::Heading(3, "Referenced by".to_string()));
+            blocks.push(Block::Table(vec!["Name", "Column", "Referenced from", "Cardinality", "On update", "On delete"], inbound));
+        }
+    }
+    blocks
+}
+
+fn foreign_key_row(reference: &ReferenceVo, column: String, other: &TableVo, other_column: String) -> Vec<String> {
+    vec![
+        reference.name.clone().unwrap_or_default(),
+        column,
+        format!("{}.{}", table_name(other), other_column),
+        reference.cardinality.clone().unwrap_or_default().replace('_', " "),
+        reference.update_constraint.clone().unwrap_or_default(),
+        reference.delete_constraint.clone().unwrap_or_default(),
+    ]
+}
+
+fn to_markdown(blocks: &[Block]) -> String {
+    let escape = |value: &str| value.replace('|', "\\|").replace("\r\n", "<br>").replace('\n', "<br>");
+    let sections = blocks.iter().map(|block| match block {
+        Block::Heading(level, text) => format!("{} {}", "#".repeat(*level), escape(text)),
+        Block::Paragraph(text) => text.clone(),
+        Block::List(items) => items.iter().map(|item| format!("- {}", escape(item))).collect::<Vec<String>>().join("\n"),
+        Block::Table(headers, rows) => {
+            let mut lines = vec![
+                format!("| {} |", headers.join(" | ")),
+                format!("|{}|", vec!["---"; headers.len()].join("|")),
+            ];
+            lines.extend(rows.iter().map(|row| format!(
+                "| {} |",
+                row.iter().map(|cell| escape(cell)).collect::<Vec<String>>().join(" | ")
+            )));
+            lines.join("\n")
+        }
+    }).collect::<Vec<String>>();
+    sections.join("\n\n") + "\n"
+}
+
+fn to_html(title: String, blocks: &[Block]) -> String {
+    let body = blocks.iter().map(|block| match block {
+        Block::Heading(level, text) => format!("<h{0}>{1}</h{0}>", level, html_escape(text)),
+        Block::Paragraph(text) => format!("<p>{}</p>", html_escape(text).replace('\n', "<br>")),
+        Block::List(items) => format!(
+            "<ul>\n{}\n</ul>",
+            items.iter().map(|item| format!("<li>{}</li>", html_escape(item))).collect::<Vec<String>>().join("\n")
+        ),
+        Block::Table(headers, rows) => {
+            let header = headers.iter().map(|header| format!("<th>{}</th>", header)).collect::<String>();
+            let rows = rows.iter().map(|row| format!(
+                "<tr>{}</tr>",
+                row.iter().map(|cell| format!("<td>{}</td>", html_escape(cell).replace('\n', "<br>"))).collect::<String>()
+            )).collect::<Vec<String>>();
+            format!("<table>\n<thead><tr>{}</tr></thead>\n<tbody>\n{}\n</tbody>\n</table>", header, rows.join("\n"))
+        }
+    }).collect::<Vec<String>>();
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>\n{}\n</style>\n</head>\n<body>\n{}\n</body>\n</html>\n",
+        html_escape(&title),
+        "body { font-family: sans-serif; margin: 2em; }\n\
+        table { border-collapse: collapse; margin-bottom: 1em; }\n\
+        th, td { border: 1px solid #ccc; padding: 4px 8px; text-align: left; }\n\
+        th { background: #f4f4f4; }",
+        body.join("\n")
+    )
+}
+
+fn html_escape(value: &str) -> String {
+    value.replace('&', "&amp;")
+    .replace('<', "&lt;")
+    .replace('>', "&gt;")
+    .replace('"', "&quot;")
+}
+
+fn yes_no(value: bool) -> String {
+    if value { "yes" } else { "no" }.to_string()
+}
+
+fn diagram_title(diagram: &DiagramVo) -> String {
+    diagram.name.clone().filter(|name| !name.trim().is_empty()).unwrap_or_else(|| diagram.id.clone())
+}
+
+fn table_name(table: &TableVo) -> String {
+    table.name.clone().unwrap_or_else(|| table.id.clone())
+}
+
+fn field_name(field: &FieldVo) -> String {
+    field.name.clone().unwrap_or_else(|| field.id.clone())
+}
+
+fn field_name_by_id(table: &TableVo, field_id: &str) -> Option<String> {
+    table.fields.as_ref()?
+    .iter()
+    .find(|field| field.id == field_id)
+    .map(field_name)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::entity::vo::IndiceVo;
+    use crate::entity::vo::fixtures::{build_diagram, build_field, build_primary_field, build_table};
+
+    fn shop() -> DiagramVo {
+        let mut email = build_field("u2", "email", "VARCHAR");
+        email.size = Some(255);
+        email.default = Some("a|b".to_string());
+        email.comment = Some("<login>".to_string());
+        let mut users = build_table("t1", "users", vec![build_primary_field("u1", "id", "INTEGER"), email], vec![]);
+        users.comment = Some("registered users".to_string());
+        let mut user_id = build_field("o2", "user_id", "INTEGER");
+        user_id.check = Some("user_id > 0".to_string());
+        let indice = IndiceVo {
+            id: "i1".to_string(),
+            name: Some("idx_orders_user".to_string()),
+            unique: Some(true),
+            table_id: Some("t2".to_string()),
+            fields: Some(vec!["o2".to_string()]),
+        };
+        let orders = build_table("t2", "orders", vec![build_primary_field("o1", "id", "INTEGER"), user_id], vec![indice]);
+        let reference = ReferenceVo {
+            id: "r1".to_string(),
+            name: Some("fk_orders_users".to_string()),
+            start_table_id: Some("t2".to_string()),
+            end_table_id: Some("t1".to_string()),
+            cardinality: Some("many_to_one".to_string()),
+            delete_constraint: Some("Cascade".to_string()),
+            end_field_id: Some("u1".to_string()),
+            start_field_id: Some("o2".to_string()),
+            update_constraint: Some("No action".to_string()),
+        };
+        let mut diagram = build_diagram(vec![users, orders], vec![reference]);
+        diagram.name = Some("shop".to_string());
+        diagram.database = Some("postgresql".to_string());
+        diagram
+    }
+
+    #[test]
+    fn test_markdown_docs(){
+        let markdown = to_docs(&shop(), DocFormat::Markdown);
+        assert!(markdown.starts_with("# shop documentation\n\nDatabase system: PostgreSQL\n\n## Tables\n\n- users\n- orders"));
+        assert!(markdown.contains("## users\n\nregistered users\n\n### Columns"));
+        assert!(markdown.contains("| email | VARCHAR | 255 | yes | a\\|b |  | <login> |"));
+        assert!(markdown.contains("| user_id | INTEGER |  | yes |  | user_id > 0 |  |"));
+        assert!(markdown.contains("| PRIMARY | yes | id |\n| idx_orders_user | yes | user_id |"));
+        assert!(markdown.contains("### Foreign keys\n\n| Name | Column | References | Cardinality | On update | On delete |\n|---|---|---|---|---|---|\n\
+            | fk_orders_users | user_id | users.id | many to one | No action | Cascade |"));
+        assert!(markdown.contains("### Referenced by\n\n| Name | Column | Referenced from | Cardinality | On update | On delete |\n|---|---|---|---|---|---|\n\
+            | fk_orders_users | id | orders.user_id | many to one | No action | Cascade |"));
+    }
+
+    #[test]
+    fn test_html_docs(){
+        let html = to_docs(&shop(), DocFormat::Html);
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<title>shop</title>"));
+        assert!(html.contains("<h2>users</h2>\n<p>registered users</p>"));
+        assert!(html.contains("<td>&lt;login&gt;</td>"));
+        assert!(html.contains("<td>user_id &gt; 0</td>"));
+        assert!(html.ends_with("</html>\n"));
+        assert!("pdf".parse::<DocFormat>().is_err());
+        assert_eq!("MD".parse::<DocFormat>().unwrap(), DocFormat::Markdown);
+    }
+}
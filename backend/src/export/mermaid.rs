@@ -0,0 +1,168 @@
+use std::collections::{HashMap, HashSet};
+use crate::entity::vo::{DiagramVo, FieldVo, TableVo};
+
+/// 生成图表的Mermaid erDiagram
+/// 与前端exportAs/mermaid.js一致：先输出关联关系，再输出实体及其属性
+pub fn to_mermaid(diagram: &DiagramVo) -> String {
+    let tables = diagram.tables.clone().unwrap_or_default();
+    let references = diagram.references.clone().unwrap_or_default();
+    let tables_by_id = tables.iter()
+    .map(|table| (table.id.as_str(), table))
+    .collect::<HashMap<&str, &TableVo>>();
+
+    //1、关联关系，使用鱼尾纹表示法
+    let relationships = references.iter().filter_map(|reference| {
+        let start = tables_by_id.get(reference.start_table_id.as_deref()?)?;
+        let end = tables_by_id.get(reference.end_table_id.as_deref()?)?;
+        let label = reference.name.as_deref()
+        .filter(|name| !name.trim().is_empty())
+        .unwrap_or("references");
+        Some(format!(
+            "\t{} {} {} : {}",
+            mermaid_entity(&table_name(start)),
+            relationship(reference.cardinality.as_deref()),
+            mermaid_entity(&table_name(end)),
+            mermaid_string(label)
+        ))
+    }).collect::<Vec<String>>();
+
+    //2、外键字段：关联关系起点表中的字段
+    let foreign_keys = references.iter()
+    .filter_map(|reference| Some((reference.start_table_id.clone()?, reference.start_field_id.clone()?)))
+    .collect::<HashSet<(String, String)>>();
+
+    //3、实体
+    let entities = tables.iter().map(|table| {
+        let attributes = table.fields.clone().unwrap_or_default().iter()
+        .map(|field| format!("\t\t{}", attribute(field, foreign_keys.contains(&(table.id.clone(), field.id.clone())))))
+        .collect::<Vec<String>>();
+        format!("\t{} {{\n{}\n\t}}", mermaid_entity(&table_name(table)), attributes.join("\n"))
+    }).collect::<Vec<String>>();
+
+    let mut mermaid = String::from("erDiagram\n");
+    if !relationships.is_empty() {
+        mermaid.push_str(&relationships.join("\n"));
+        mermaid.push_str("\n\n");
+    }
+    mermaid.push_str(&entities.join("\n\n"));
+    mermaid.push('\n');
+    mermaid
+}
+
+/// 关联关系的鱼尾纹符号，未知的基数按多对一处理
+fn relationship(cardinality: Option<&str>) -> &'static str {
+    match cardinality {
+        Some("one_to_one") => "||--||",
+        Some("one_to_many") => "||--o{",
+        _ => "}o--||",
+    }
+}
+
+/// 属性：类型 名称 键标记 "注释"
+fn attribute(field: &FieldVo, foreign_key: bool) -> String {
+    let mut r#type = field.r#type.clone().unwrap_or_default().trim().to_lowercase();
+    if let Some(size) = field.size.filter(|size| *size > 0) {
+        r#type = format!("{}({})", r#type, size);
+    }
+    let mut keys = Vec::new();
+    if field.primary.unwrap_or(false) {
+        keys.push("PK");
+    }
+    if foreign_key {
+        keys.push("FK");
+    }
+    if field.unique.unwrap_or(false) && !field.primary.unwrap_or(false) {
+        keys.push("UK");
+    }
+    let mut line = format!("{} {}", mermaid_word(&r#type), mermaid_word(&field_name(field)));
+    if !keys.is_empty() {
+        line.push(' ');
+        line.push_str(&keys.join(","));
+    }
+    if let Some(comment) = field.comment.as_ref().filter(|comment| !comment.trim().is_empty()) {
+        line.push(' ');
+        line.push_str(&mermaid_string(comment));
+    }
+    line
+}
+
+/// 实体名称不是简单标识符时加双引号
+fn mermaid_entity(name: &str) -> String {
+    let simple = name.chars().next().is_some_and(|c| c.is_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '-');
+    if simple {
+        name.to_string()
+    } else {
+        mermaid_string(name)
+    }
+}
+
+/// 属性的类型和名称不支持引号，将不合法的字符替换为下划线
+fn mermaid_word(word: &str) -> String {
+    let word = word.chars()
+    .map(|c| if c.is_alphanumeric() || "_-()[]".contains(c) { c } else { '_' })
+    .collect::<String>();
+    match word.chars().next() {
+        Some(c) if c.is_alphabetic() || c == '_' => word,
+        _ => format!("_{}", word),
+    }
+}
+
+/// 双引号字符串，内容中的双引号替换为单引号，换行替换为空格
+fn mermaid_string(value: &str) -> String {
+    format!("\"{}\"", value.replace('"', "'").replace(['\r', '\n'], " "))
+}
+
+fn table_name(table: &TableVo) -> String {
+    table.name.clone().unwrap_or_else(|| table.id.clone())
+}
+
+fn field_name(field: &FieldVo) -> String {
+    field.name.clone().unwrap_or_else(|| field.id.clone())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::entity::vo::ReferenceVo;
+    use crate::entity::vo::fixtures::{build_diagram, build_field, build_table};
+
+    #[test]
+    fn test_mermaid(){
+        let mut user_id = build_field("u1", "id", "INTEGER");
+        user_id.primary = Some(true);
+        let mut email = build_field("u2", "email", "VARCHAR");
+        email.size = Some(255);
+        email.unique = Some(true);
+        email.comment = Some("login \"email\"".to_string());
+        let mut order_id = build_field("o1", "id", "INTEGER");
+        order_id.primary = Some(true);
+        let order_user = build_field("o2", "user_id", "DOUBLE PRECISION");
+        let reference = ReferenceVo {
+            id: "r1".to_string(),
+            name: None,
+            start_table_id: Some("t2".to_string()),
+            end_table_id: Some("t1".to_string()),
+            cardinality: Some("many_to_one".to_string()),
+            delete_constraint: None,
+            end_field_id: Some("u1".to_string()),
+            start_field_id: Some("o2".to_string()),
+            update_constraint: None,
+        };
+        let diagram = build_diagram(vec![
+            build_table("t1", "users", vec![user_id, email], vec![]),
+            build_table("t2", "order items", vec![order_id, order_user], vec![]),
+        ], vec![reference]);
+        let mermaid = to_mermaid(&diagram);
+        assert_eq!(mermaid, "erDiagram\n\
+            \t\"order items\" }o--|| users : \"references\"\n\n\
+            \tusers {\n\
+            \t\tinteger id PK\n\
+            \t\tvarchar(255) email UK \"login 'email'\"\n\
+            \t}\n\n\
+            \t\"order items\" {\n\
+            \t\tinteger id PK\n\
+            \t\tdouble_precision user_id FK\n\
+            \t}\n");
+    }
+}
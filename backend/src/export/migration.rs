@@ -0,0 +1,818 @@
+use std::collections::{HashMap, HashSet};
+use std::io::{Cursor, Write};
+use std::str::FromStr;
+use zip::write::FileOptions;
+use zip::ZipWriter;
+use crate::datatypes::{is_function, unquote};
+use crate::diff::{match_items, Matched};
+use crate::entity::vo::{DiagramVo, FieldVo, IndiceVo, TableVo};
+use crate::error::DrawDBError;
+use crate::export::Dialect;
+use super::sql::{
+    column_definition, field_name, field_name_by_id, field_type, index_name, index_statement, parse_default,
+    quote_literal, table_name, table_statement, with_batch_separator, ForeignKey,
+};
+
+/// 迁移脚本的输出格式
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MigrationFormat {
+    /// 单个脚本，升级和回滚以dbmate的-- migrate:up、-- migrate:down分隔
+    Sql,
+    /// golang-migrate、sqlx的编号文件：000001_name.up.sql、000001_name.down.sql
+    Migrate,
+    /// Flyway的编号文件：V1__name.sql、U1__name.sql
+    Flyway,
+}
+
+impl FromStr for MigrationFormat {
+    type Err = DrawDBError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "sql" | "dbmate" => Ok(MigrationFormat::Sql),
+            "migrate" | "golang-migrate" | "sqlx" => Ok(MigrationFormat::Migrate),
+            "flyway" => Ok(MigrationFormat::Flyway),
+            other => Err(DrawDBError::ValidationError(format!("不支持的迁移格式: {}", other))),
+        }
+    }
+}
+
+/// 迁移脚本，up为升级语句，down为回滚语句
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Migration {
+    pub up: String,
+    pub down: String,
+}
+
+impl Migration {
+    /// 升级和回滚合并为单个脚本
+    pub fn to_script(&self) -> String {
+        format!("-- migrate:up\n{}\n-- migrate:down\n{}", self.up, self.down)
+    }
+
+    /// 按迁移工具的目录结构生成编号文件，返回文件名和内容
+    pub fn to_files(&self, format: MigrationFormat, number: u32, name: &str) -> Vec<(String, String)> {
+        let name = name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '_' })
+        .collect::<String>();
+        match format {
+            MigrationFormat::Sql => vec![(format!("{:06}_{}.sql", number, name), self.to_script())],
+            MigrationFormat::Migrate => vec![
+                (format!("{:06}_{}.up.sql", number, name), self.up.clone()),
+                (format!("{:06}_{}.down.sql", number, name), self.down.clone()),
+            ],
+            MigrationFormat::Flyway => vec![
+                (format!("V{}__{}.sql", number, name), self.up.clone()),
+                (format!("U{}__{}.sql", number, name), self.down.clone()),
+            ],
+        }
+    }
+
+    /// 编号文件打包为zip
+    pub fn to_zip(&self, format: MigrationFormat, number: u32, name: &str) -> Result<Vec<u8>, DrawDBError> {
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        for (file_name, content) in self.to_files(format, number, name) {
+            zip.start_file(file_name, FileOptions::default())
+            .map_err(|e| DrawDBError::OtherError(e.to_string()))?;
+            zip.write_all(content.as_bytes())?;
+        }
+        let cursor = zip.finish().map_err(|e| DrawDBError::OtherError(e.to_string()))?;
+        Ok(cursor.into_inner())
+    }
+}
+
+/// 生成从from迁移到to的脚本，回滚脚本为从to迁移回from
+/// 表、字段、索引、外键先按id匹配，再按名称匹配
+pub fn to_migration(from: &DiagramVo, to: &DiagramVo, dialect: Dialect) -> Migration {
+    Migration {
+        up: migrate(from, to, dialect),
+        down: migrate(to, from, dialect),
+    }
+}
+
+fn migrate(from: &DiagramVo, to: &DiagramVo, dialect: Dialect) -> String {
+    let (mut statements, rebuilt) = plan(from, to, dialect);
+    // 重建表时关闭外键检查，避免删除旧表时触发级联
+    if rebuilt {
+        statements.insert(0, "PRAGMA foreign_keys = OFF;\n".to_string());
+        statements.push("PRAGMA foreign_keys = ON;\n".to_string());
+    }
+    statements.join("\n")
+}
+
+/// 从from迁移到to的语句，每项为一条语句，建表语句包含表的索引
+/// SQLite重建表时需要关闭外键检查，PRAGMA foreign_keys在事务中无效，由调用方在事务外设置
+pub fn migration_statements(from: &DiagramVo, to: &DiagramVo, dialect: Dialect) -> Vec<String> {
+    plan(from, to, dialect).0
+}
+
+/// 迁移语句以及SQLite是否需要重建表
+fn plan(from: &DiagramVo, to: &DiagramVo, dialect: Dialect) -> (Vec<String>, bool) {
+    let from_tables = from.tables.clone().unwrap_or_default();
+    let to_tables = to.tables.clone().unwrap_or_default();
+    let tables = match_items(&from_tables, &to_tables, |table| &table.id, |table| table.name.clone());
+    let renames = Renames::new(&tables.pairs);
+    let removed_tables = tables.removed.iter().map(|table| table_name(table)).collect::<HashSet<String>>();
+
+    //1、外键按起止字段匹配，换算重命名后定义不同的视为删除后重新添加
+    let from_keys = foreign_keys(from, &from_tables);
+    let to_keys = foreign_keys(to, &to_tables);
+    let from_items = from_keys.iter().map(|key| (key, renames.foreign_key(key))).collect::<Vec<_>>();
+    let to_items = to_keys.iter().map(|key| (key, key.clone())).collect::<Vec<_>>();
+    let keys = match_items(&from_items, &to_items, |(key, _)| &key.id, |(_, key)| Some(foreign_key_signature(key)));
+    let changed_keys = keys.pairs.iter()
+    .filter(|((_, from_key), (_, to_key))| {
+        from_key.start_table != to_key.start_table || foreign_key_clause(from_key, dialect) != foreign_key_clause(to_key, dialect)
+    })
+    .map(|((from_key, _), (to_key, _))| (*from_key, *to_key))
+    .collect::<Vec<(&ForeignKey, &ForeignKey)>>();
+    let dropped_keys = keys.removed.iter().map(|(key, _)| *key)
+    .chain(changed_keys.iter().map(|(from_key, _)| *from_key))
+    .collect::<Vec<&ForeignKey>>();
+    let added_keys = keys.added.iter().map(|(key, _)| *key)
+    .chain(changed_keys.iter().map(|(_, to_key)| *to_key))
+    .collect::<Vec<&ForeignKey>>();
+
+    //2、SQLite不支持修改列、删除列和外键，这些表需要重建
+    let rebuilt = match dialect {
+        Dialect::Sqlite => {
+            let key_tables = dropped_keys.iter()
+            .map(|key| renames.table(&key.start_table))
+            .chain(added_keys.iter().map(|key| key.start_table.clone()))
+            .collect::<HashSet<String>>();
+            tables.pairs.iter()
+            .filter(|(from_table, to_table)| key_tables.contains(&table_name(to_table)) || needs_rebuild(from_table, to_table))
+            .map(|(_, to_table)| to_table.id.clone())
+            .collect::<HashSet<String>>()
+        }
+        _ => HashSet::new(),
+    };
+
+    let mut statements = Vec::new();
+    //3、删除外键，所在表被删除的随表删除
+    if dialect != Dialect::Sqlite {
+        statements.extend(dropped_keys.iter()
+        .filter(|key| !removed_tables.contains(&key.start_table))
+        .map(|key| drop_foreign_key(key, dialect)));
+    }
+    //4、删除索引，重建的表随表删除
+    for (from_table, to_table) in tables.pairs.iter().filter(|(_, to_table)| !rebuilt.contains(&to_table.id)) {
+        let indices = match_indices(from_table, to_table, &renames);
+        statements.extend(indices.removed.iter()
+        .chain(indices.changed.iter().map(|(from_indice, _)| from_indice))
+        .map(|indice| drop_index(&table_name(from_table), indice, dialect)));
+    }
+    //5、删除表
+    statements.extend(tables.removed.iter()
+    .map(|table| statement(format!("DROP TABLE {}", dialect.quote(&table_name(table))), dialect)));
+    //6、重命名表
+    for (from_table, to_table) in tables.pairs.iter() {
+        if table_name(from_table) != table_name(to_table) {
+            statements.push(rename_table(&table_name(from_table), &table_name(to_table), dialect));
+        }
+    }
+    //7、修改表的列，SQLite重建表
+    for (from_table, to_table) in tables.pairs.iter() {
+        if rebuilt.contains(&to_table.id) {
+            statements.extend(rebuild_table(from_table, to_table, &to_keys));
+        } else {
+            statements.extend(alter_table(from_table, to_table, &renames, dialect));
+        }
+    }
+    //8、新增表，SQLite的外键写在建表语句中
+    let inline_keys = match dialect {
+        Dialect::Sqlite => to_keys.as_slice(),
+        _ => &[],
+    };
+    statements.extend(tables.added.iter().map(|table| table_statement(table, inline_keys, dialect)));
+    //9、新增索引
+    for (from_table, to_table) in tables.pairs.iter().filter(|(_, to_table)| !rebuilt.contains(&to_table.id)) {
+        let indices = match_indices(from_table, to_table, &renames);
+        statements.extend(indices.added.iter()
+        .chain(indices.changed.iter().map(|(_, to_indice)| to_indice))
+        .filter_map(|indice| index_statement(to_table, indice, dialect)));
+    }
+    //10、新增外键
+    if dialect != Dialect::Sqlite {
+        statements.extend(added_keys.iter().map(|key| key.alter_statement(dialect)));
+    }
+    (statements, !rebuilt.is_empty())
+}
+
+/// from中的表名、字段名到to中名称的映射
+struct Renames {
+    tables: HashMap<String, String>,
+    fields: HashMap<(String, String), String>,
+}
+
+impl Renames {
+    fn new(pairs: &[(&TableVo, &TableVo)]) -> Self {
+        let mut tables = HashMap::new();
+        let mut fields = HashMap::new();
+        for (from_table, to_table) in pairs {
+            tables.insert(table_name(from_table), table_name(to_table));
+            for (from_field, to_field) in match_fields(from_table, to_table).pairs {
+                fields.insert((table_name(from_table), field_name(from_field)), field_name(to_field));
+            }
+        }
+        Self { tables, fields }
+    }
+
+    fn table(&self, table: &str) -> String {
+        self.tables.get(table).cloned().unwrap_or_else(|| table.to_string())
+    }
+
+    fn field(&self, table: &str, field: &str) -> String {
+        self.fields.get(&(table.to_string(), field.to_string())).cloned().unwrap_or_else(|| field.to_string())
+    }
+
+    /// 外键引用的表和字段换算为to中的名称
+    fn foreign_key(&self, key: &ForeignKey) -> ForeignKey {
+        let mut key = key.clone();
+        key.start_field = self.field(&key.start_table, &key.start_field);
+        key.end_field = self.field(&key.end_table, &key.end_field);
+        key.start_table = self.table(&key.start_table);
+        key.end_table = self.table(&key.end_table);
+        key
+    }
+}
+
+/// 同一张表的索引匹配结果，changed为名称、唯一性或列不同的索引
+struct MatchedIndices<'a> {
+    removed: Vec<&'a IndiceVo>,
+    added: Vec<&'a IndiceVo>,
+    changed: Vec<(&'a IndiceVo, &'a IndiceVo)>,
+}
+
+fn match_indices<'a>(from: &'a TableVo, to: &'a TableVo, renames: &Renames) -> MatchedIndices<'a> {
+    let from_indices = from.indices.as_deref().unwrap_or_default();
+    let to_indices = to.indices.as_deref().unwrap_or_default();
+    let indices = match_items(from_indices, to_indices, |indice| &indice.id, |indice| indice.name.clone());
+    let columns = |table: &TableVo, indice: &IndiceVo| indice.fields.clone().unwrap_or_default().iter()
+    .filter_map(|field_id| field_name_by_id(table, field_id))
+    .collect::<Vec<String>>();
+    let changed = indices.pairs.into_iter()
+    .filter(|(from_indice, to_indice)| {
+        let from_columns = columns(from, from_indice).iter()
+        .map(|column| renames.field(&table_name(from), column))
+        .collect::<Vec<String>>();
+        index_name(from_indice) != index_name(to_indice)
+            || from_indice.unique.unwrap_or(false) != to_indice.unique.unwrap_or(false)
+            || from_columns != columns(to, to_indice)
+    })
+    .collect();
+    MatchedIndices { removed: indices.removed, added: indices.added, changed }
+}
+
+fn match_fields<'a>(from: &'a TableVo, to: &'a TableVo) -> Matched<'a, FieldVo> {
+    let from_fields = from.fields.as_deref().unwrap_or_default();
+    let to_fields = to.fields.as_deref().unwrap_or_default();
+    match_items(from_fields, to_fields, |field| &field.id, |field| field.name.clone())
+}
+
+/// 关联关系解析为外键，表或字段不存在的忽略
+fn foreign_keys(diagram: &DiagramVo, tables: &[TableVo]) -> Vec<ForeignKey> {
+    let tables_by_id = tables.iter()
+    .map(|table| (table.id.as_str(), table))
+    .collect::<HashMap<&str, &TableVo>>();
+    diagram.references.iter()
+    .flatten()
+    .filter_map(|reference| ForeignKey::resolve(reference, &tables_by_id))
+    .collect()
+}
+
+fn foreign_key_signature(key: &ForeignKey) -> String {
+    format!("{}.{} -> {}.{}", key.start_table, key.start_field, key.end_table, key.end_field)
+}
+
+/// SQLite无法读取外键的名称，比较时忽略
+fn foreign_key_clause(key: &ForeignKey, dialect: Dialect) -> String {
+    match dialect {
+        Dialect::Sqlite => {
+            let mut key = key.clone();
+            key.name = None;
+            key.clause(dialect)
+        }
+        _ => key.clause(dialect),
+    }
+}
+
+/// 列定义中参与比较的属性
+/// 类型和检查约束忽略空白，默认值忽略引号，与数据库中读取的结构比较时不会因书写格式不同而变化
+#[derive(PartialEq, Eq)]
+struct Column {
+    r#type: String,
+    not_null: bool,
+    default: Option<String>,
+    increment: bool,
+    unique: bool,
+    check: Option<String>,
+    comment: Option<String>,
+}
+
+impl Column {
+    fn from(field: &FieldVo) -> Self {
+        let text = |value: &Option<String>| value.as_ref()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty());
+        let compact = |value: String| value.split_whitespace().collect::<String>();
+        Self {
+            r#type: compact(column_type(field).to_uppercase()),
+            not_null: field.not_null.unwrap_or(false),
+            default: text(&field.default).map(|default| unquote(&default).to_string()),
+            increment: field.increment.unwrap_or(false),
+            unique: field.unique.unwrap_or(false),
+            check: text(&field.check).map(compact),
+            comment: text(&field.comment),
+        }
+    }
+}
+
+/// SQLite的ALTER TABLE只能重命名表和列、添加列，其余修改需要重建表
+/// 添加的列不能是主键或唯一列，非空列必须有默认值，默认值必须是常量
+fn needs_rebuild(from: &TableVo, to: &TableVo) -> bool {
+    let fields = match_fields(from, to);
+    let modified = fields.pairs.iter().any(|(from_field, to_field)| {
+        let (mut from_column, mut to_column) = (Column::from(from_field), Column::from(to_field));
+        // 注释只存在于建表语句中，不需要重建
+        from_column.comment = None;
+        to_column.comment = None;
+        from_field.primary.unwrap_or(false) != to_field.primary.unwrap_or(false) || from_column != to_column
+    });
+    let unsupported_add = fields.added.iter().any(|field| {
+        let column = Column::from(field);
+        let constant_default = column.default.as_deref().is_none_or(|default| {
+            !is_function(default) && !default.to_uppercase().starts_with("CURRENT_")
+        });
+        field.primary.unwrap_or(false) || column.unique || !constant_default || (column.not_null && column.default.is_none())
+    });
+    !fields.removed.is_empty() || modified || unsupported_add
+}
+
+/// SQLite重建表：新建临时表、复制数据、删除旧表、重命名临时表，再重建索引
+/// 表已重命名为to中的名称，列仍是from中的名称
+fn rebuild_table(from: &TableVo, to: &TableVo, keys: &[ForeignKey]) -> Vec<String> {
+    let dialect = Dialect::Sqlite;
+    let name = table_name(to);
+    let temp_name = format!("_new_{}", name);
+    let mut temp_table = to.clone();
+    temp_table.name = Some(temp_name.clone());
+    temp_table.indices = None;
+    let fields = match_fields(from, to);
+    let to_columns = fields.pairs.iter()
+    .map(|(_, to_field)| dialect.quote(&field_name(to_field)))
+    .collect::<Vec<String>>();
+    let from_columns = fields.pairs.iter()
+    .map(|(from_field, _)| dialect.quote(&field_name(from_field)))
+    .collect::<Vec<String>>();
+
+    let mut statements = vec![table_statement(&temp_table, keys, dialect)];
+    if !to_columns.is_empty() {
+        statements.push(statement(format!(
+            "INSERT INTO {} ({})\nSELECT {} FROM {}",
+            dialect.quote(&temp_name),
+            to_columns.join(", "),
+            from_columns.join(", "),
+            dialect.quote(&name)
+        ), dialect));
+    }
+    statements.push(statement(format!("DROP TABLE {}", dialect.quote(&name)), dialect));
+    statements.push(rename_table(&temp_name, &name, dialect));
+    statements.extend(to.indices.iter()
+    .flatten()
+    .filter_map(|indice| index_statement(to, indice, dialect)));
+    statements
+}
+
+/// 修改表：主键、列的重命名、删除、新增和修改，以及表注释
+/// 表已重命名为to中的名称
+fn alter_table(from: &TableVo, to: &TableVo, renames: &Renames, dialect: Dialect) -> Vec<String> {
+    let name = table_name(to);
+    let table = dialect.quote(&name);
+    let fields = match_fields(from, to);
+    let from_primary_keys = primary_keys(from).iter()
+    .map(|column| renames.field(&table_name(from), column))
+    .collect::<Vec<String>>();
+    let to_primary_keys = primary_keys(to);
+    let primary_key_changed = from_primary_keys != to_primary_keys;
+    let mut statements = Vec::new();
+
+    //1、删除原主键
+    if primary_key_changed && !from_primary_keys.is_empty() {
+        statements.push(match dialect {
+            Dialect::MySql | Dialect::MariaDb | Dialect::OracleSql => statement(format!("ALTER TABLE {} DROP PRIMARY KEY", table), dialect),
+            Dialect::Postgres => statement(format!(
+                "ALTER TABLE {} DROP CONSTRAINT {}",
+                table,
+                dialect.quote(&format!("{}_pkey", table_name(from)))
+            ), dialect),
+            _ => manual(format!("删除表{}上未命名的主键", table)),
+        });
+    }
+    //2、重命名列
+    for (from_field, to_field) in fields.pairs.iter() {
+        if field_name(from_field) != field_name(to_field) {
+            statements.push(match dialect {
+                Dialect::MsSql => statement(format!(
+                    "EXEC sp_rename {}, {}, 'COLUMN'",
+                    quote_literal(&format!("{}.{}", name, field_name(from_field))),
+                    quote_literal(&field_name(to_field))
+                ), dialect),
+                _ => statement(format!(
+                    "ALTER TABLE {} RENAME COLUMN {} TO {}",
+                    table,
+                    dialect.quote(&field_name(from_field)),
+                    dialect.quote(&field_name(to_field))
+                ), dialect),
+            });
+        }
+    }
+    //3、删除列
+    statements.extend(fields.removed.iter().map(|field| statement(format!(
+        "ALTER TABLE {} DROP COLUMN {}",
+        table,
+        dialect.quote(&field_name(field))
+    ), dialect)));
+    //4、新增列
+    for field in fields.added.iter() {
+        let definition = column_definition(field, false, dialect);
+        statements.push(statement(match dialect {
+            Dialect::MsSql => format!("ALTER TABLE {} ADD {}", table, definition),
+            Dialect::OracleSql => format!("ALTER TABLE {} ADD ({})", table, definition),
+            _ => format!("ALTER TABLE {} ADD COLUMN {}", table, definition),
+        }, dialect));
+        if let (Dialect::Postgres, Some(comment)) = (dialect, Column::from(field).comment) {
+            statements.push(statement(format!(
+                "COMMENT ON COLUMN {}.{} IS {}",
+                table,
+                dialect.quote(&field_name(field)),
+                quote_literal(&comment)
+            ), dialect));
+        }
+    }
+    //5、修改列
+    for (from_field, to_field) in fields.pairs.iter() {
+        statements.extend(alter_column(&name, from_field, to_field, dialect));
+    }
+    //6、新增主键
+    if primary_key_changed && !to_primary_keys.is_empty() {
+        let columns = to_primary_keys.iter()
+        .map(|column| dialect.quote(column))
+        .collect::<Vec<String>>();
+        statements.push(statement(format!("ALTER TABLE {} ADD PRIMARY KEY ({})", table, columns.join(", ")), dialect));
+    }
+    //7、表注释
+    let text = |comment: &Option<String>| comment.clone().filter(|comment| !comment.trim().is_empty());
+    let comment = text(&to.comment);
+    if text(&from.comment) != comment {
+        match dialect {
+            Dialect::MySql | Dialect::MariaDb => statements.push(statement(format!(
+                "ALTER TABLE {} COMMENT = {}",
+                table,
+                quote_literal(comment.as_deref().unwrap_or_default())
+            ), dialect)),
+            Dialect::Postgres => statements.push(statement(format!(
+                "COMMENT ON TABLE {} IS {}",
+                table,
+                comment.as_deref().map(quote_literal).unwrap_or_else(|| "NULL".to_string())
+            ), dialect)),
+            _ => {}
+        }
+    }
+    statements
+}
+
+/// 修改列的类型、非空、默认值、自增、唯一、检查约束和注释，列已重命名
+/// 依赖数据库自动命名的约束无法删除时输出注释，由用户手动处理
+fn alter_column(name: &str, from: &FieldVo, to: &FieldVo, dialect: Dialect) -> Vec<String> {
+    let (old, new) = (Column::from(from), Column::from(to));
+    let table = dialect.quote(name);
+    let column_name = field_name(to);
+    let column = dialect.quote(&column_name);
+    let default = to.default.as_deref()
+    .filter(|default| !default.trim().is_empty())
+    .map(|default| parse_default(default, &field_type(to), dialect));
+    let mut statements = Vec::new();
+
+    //1、类型、非空、默认值、自增、注释
+    match dialect {
+        Dialect::MySql | Dialect::MariaDb => {
+            let changed = old.r#type != new.r#type
+                || old.not_null != new.not_null
+                || old.default != new.default
+                || old.increment != new.increment
+                || old.comment != new.comment;
+            if changed {
+                // 唯一和检查约束单独处理，避免重复创建
+                let mut field = to.clone();
+                field.unique = Some(false);
+                field.check = None;
+                statements.push(statement(format!(
+                    "ALTER TABLE {} MODIFY COLUMN {}",
+                    table,
+                    column_definition(&field, false, dialect)
+                ), dialect));
+            }
+        }
+        Dialect::Postgres => {
+            let alter = |action: String| statement(format!("ALTER TABLE {} ALTER COLUMN {} {}", table, column, action), dialect);
+            if old.r#type != new.r#type {
+                statements.push(alter(format!("TYPE {}", column_type(to))));
+            }
+            if old.not_null != new.not_null {
+                statements.push(alter(if new.not_null { "SET NOT NULL" } else { "DROP NOT NULL" }.to_string()));
+            }
+            if old.default != new.default {
+                statements.push(alter(match &default {
+                    Some(default) => format!("SET DEFAULT {}", default),
+                    None => "DROP DEFAULT".to_string(),
+                }));
+            }
+            if old.increment != new.increment {
+                statements.push(alter(if new.increment {
+                    "ADD GENERATED BY DEFAULT AS IDENTITY"
+                } else {
+                    "DROP IDENTITY IF EXISTS"
+                }.to_string()));
+            }
+            if old.comment != new.comment {
+                statements.push(statement(format!(
+                    "COMMENT ON COLUMN {}.{} IS {}",
+                    table,
+                    column,
+                    new.comment.as_deref().map(quote_literal).unwrap_or_else(|| "NULL".to_string())
+                ), dialect));
+            }
+        }
+        Dialect::MsSql => {
+            if old.r#type != new.r#type || old.not_null != new.not_null {
+                statements.push(statement(format!(
+                    "ALTER TABLE {} ALTER COLUMN {} {} {}",
+                    table,
+                    column,
+                    column_type(to),
+                    if new.not_null { "NOT NULL" } else { "NULL" }
+                ), dialect));
+            }
+            if old.default != new.default {
+                if old.default.is_some() {
+                    statements.push(manual(format!("删除列{}.{}上未命名的默认值约束", name, column_name)));
+                }
+                if let Some(default) = &default {
+                    statements.push(statement(format!("ALTER TABLE {} ADD DEFAULT {} FOR {}", table, default, column), dialect));
+                }
+            }
+            if old.increment != new.increment {
+                statements.push(manual(format!("SQL Server不支持修改列{}.{}的IDENTITY属性", name, column_name)));
+            }
+        }
+        Dialect::OracleSql => {
+            let modify = |definition: String| statement(format!("ALTER TABLE {} MODIFY ({} {})", table, column, definition), dialect);
+            if old.r#type != new.r#type {
+                statements.push(modify(column_type(to)));
+            }
+            if old.not_null != new.not_null {
+                statements.push(modify(if new.not_null { "NOT NULL" } else { "NULL" }.to_string()));
+            }
+            if old.default != new.default {
+                statements.push(modify(format!("DEFAULT {}", default.as_deref().unwrap_or("NULL"))));
+            }
+            if old.increment != new.increment {
+                statements.push(manual(format!("Oracle不支持修改列{}.{}的IDENTITY属性", name, column_name)));
+            }
+        }
+        // SQLite修改列时重建表
+        Dialect::Sqlite => return statements,
+    }
+
+    //2、唯一约束
+    if old.unique != new.unique {
+        statements.push(match (new.unique, dialect) {
+            (true, _) => statement(format!("ALTER TABLE {} ADD UNIQUE ({})", table, column), dialect),
+            (false, Dialect::MySql | Dialect::MariaDb) => statement(format!("ALTER TABLE {} DROP INDEX {}", table, column), dialect),
+            (false, Dialect::Postgres) => statement(format!(
+                "ALTER TABLE {} DROP CONSTRAINT {}",
+                table,
+                dialect.quote(&format!("{}_{}_key", name, column_name))
+            ), dialect),
+            (false, _) => manual(format!("删除列{}.{}上未命名的唯一约束", name, column_name)),
+        });
+    }
+    //3、检查约束
+    if old.check != new.check {
+        if old.check.is_some() {
+            statements.push(match dialect {
+                Dialect::Postgres => statement(format!(
+                    "ALTER TABLE {} DROP CONSTRAINT {}",
+                    table,
+                    dialect.quote(&format!("{}_{}_check", name, column_name))
+                ), dialect),
+                _ => manual(format!("删除列{}.{}上未命名的检查约束", name, column_name)),
+            });
+        }
+        if let Some(check) = to.check.as_deref().filter(|check| !check.trim().is_empty()) {
+            statements.push(statement(format!("ALTER TABLE {} ADD CHECK({})", table, check), dialect));
+        }
+    }
+    statements
+}
+
+/// 未命名的外键只有PostgreSQL能推断出默认名称
+fn drop_foreign_key(key: &ForeignKey, dialect: Dialect) -> String {
+    let name = key.name.clone().or_else(|| {
+        (dialect == Dialect::Postgres).then(|| format!("{}_{}_fkey", key.start_table, key.start_field))
+    });
+    let Some(name) = name else {
+        return manual(format!("删除{}.{}上未命名的外键", key.start_table, key.start_field));
+    };
+    let keyword = match dialect {
+        Dialect::MySql | Dialect::MariaDb => "FOREIGN KEY",
+        _ => "CONSTRAINT",
+    };
+    statement(format!("ALTER TABLE {} DROP {} {}", dialect.quote(&key.start_table), keyword, dialect.quote(&name)), dialect)
+}
+
+fn drop_index(table: &str, indice: &IndiceVo, dialect: Dialect) -> String {
+    let index = dialect.quote(&index_name(indice));
+    statement(match dialect {
+        Dialect::MySql | Dialect::MariaDb | Dialect::MsSql => format!("DROP INDEX {} ON {}", index, dialect.quote(table)),
+        Dialect::Sqlite => format!("DROP INDEX IF EXISTS {}", index),
+        _ => format!("DROP INDEX {}", index),
+    }, dialect)
+}
+
+fn rename_table(from: &str, to: &str, dialect: Dialect) -> String {
+    statement(match dialect {
+        Dialect::MsSql => format!("EXEC sp_rename {}, {}", quote_literal(from), quote_literal(to)),
+        _ => format!("ALTER TABLE {} RENAME TO {}", dialect.quote(from), dialect.quote(to)),
+    }, dialect)
+}
+
+fn primary_keys(table: &TableVo) -> Vec<String> {
+    table.fields.iter()
+    .flatten()
+    .filter(|field| field.primary.unwrap_or(false))
+    .map(field_name)
+    .collect()
+}
+
+/// 类型以及长度
+fn column_type(field: &FieldVo) -> String {
+    match field.size.filter(|size| *size > 0) {
+        Some(size) => format!("{}({})", field_type(field), size),
+        None => field_type(field),
+    }
+}
+
+fn statement(sql: String, dialect: Dialect) -> String {
+    with_batch_separator(format!("{};\n", sql), dialect)
+}
+
+/// 无法自动生成的语句
+fn manual(message: String) -> String {
+    format!("-- 需要手动处理: {}\n", message)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::entity::vo::ReferenceVo;
+    use crate::entity::vo::fixtures::{build_diagram, build_field, build_table};
+
+    /// 以1结尾的字段id为主键
+    fn field(id: &str, name: &str, r#type: &str) -> FieldVo {
+        FieldVo {
+            primary: Some(id.ends_with('1')),
+            ..build_field(id, name, r#type)
+        }
+    }
+
+    fn build_reference(delete_constraint: &str) -> ReferenceVo {
+        ReferenceVo {
+            id: "r1".to_string(),
+            name: Some("fk_orders_users".to_string()),
+            start_table_id: Some("t2".to_string()),
+            end_table_id: Some("t1".to_string()),
+            cardinality: Some("many_to_one".to_string()),
+            delete_constraint: Some(delete_constraint.to_string()),
+            end_field_id: Some("u1".to_string()),
+            start_field_id: Some("o2".to_string()),
+            update_constraint: None,
+        }
+    }
+
+    fn orders(unique: bool) -> TableVo {
+        let indice = IndiceVo {
+            id: "i1".to_string(),
+            name: Some("idx_orders_user".to_string()),
+            unique: Some(unique),
+            table_id: None,
+            fields: Some(vec!["o2".to_string()]),
+        };
+        build_table("t2", "orders", vec![field("o1", "id", "INTEGER"), field("o2", "user_id", "INTEGER")], vec![indice])
+    }
+
+    /// users重命名为accounts，email重命名为mail并修改长度和非空，删除age，新增created_at
+    /// 唯一索引、外键删除动作变化，删除logs，新增audit
+    fn build_diagrams() -> (DiagramVo, DiagramVo) {
+        let mut email = field("u2", "email", "VARCHAR");
+        email.size = Some(255);
+        let from = build_diagram(vec![
+            build_table("t1", "users", vec![field("u1", "id", "INTEGER"), email, field("u3", "age", "INTEGER")], vec![]),
+            orders(false),
+            build_table("t3", "logs", vec![field("l1", "id", "INTEGER")], vec![]),
+        ], vec![build_reference("Cascade")]);
+
+        let mut mail = field("u2", "mail", "VARCHAR");
+        mail.size = Some(320);
+        mail.not_null = Some(true);
+        let created_at = field("u4", "created_at", "DATE");
+        let to = build_diagram(vec![
+            build_table("t1", "accounts", vec![field("u1", "id", "INTEGER"), mail, created_at], vec![]),
+            orders(true),
+            build_table("t9", "audit", vec![field("a1", "id", "INTEGER")], vec![]),
+        ], vec![build_reference("Restrict")]);
+        (from, to)
+    }
+
+    #[test]
+    fn test_postgres_migration(){
+        let (from, to) = build_diagrams();
+        let migration = to_migration(&from, &to, Dialect::Postgres);
+        let up = migration.up.as_str();
+        let position = |sql: &str| up.find(sql).unwrap_or_else(|| panic!("missing: {}", sql));
+        assert!(position("ALTER TABLE \"orders\" DROP CONSTRAINT \"fk_orders_users\";") < position("DROP INDEX \"idx_orders_user\";"));
+        assert!(position("DROP TABLE \"logs\";") < position("ALTER TABLE \"users\" RENAME TO \"accounts\";"));
+        assert!(up.contains("ALTER TABLE \"accounts\" RENAME COLUMN \"email\" TO \"mail\";"));
+        assert!(up.contains("ALTER TABLE \"accounts\" DROP COLUMN \"age\";"));
+        assert!(up.contains("ALTER TABLE \"accounts\" ADD COLUMN \"created_at\" DATE;"));
+        assert!(up.contains("ALTER TABLE \"accounts\" ALTER COLUMN \"mail\" TYPE VARCHAR(320);"));
+        assert!(up.contains("ALTER TABLE \"accounts\" ALTER COLUMN \"mail\" SET NOT NULL;"));
+        assert!(up.contains("CREATE TABLE \"audit\""));
+        assert!(up.contains("CREATE UNIQUE INDEX \"idx_orders_user\"\nON \"orders\" (\"user_id\");"));
+        assert!(position("REFERENCES \"accounts\" (\"id\")\n\tON UPDATE NO ACTION ON DELETE RESTRICT;") > position("CREATE TABLE \"audit\""));
+
+        // 回滚脚本为反向迁移
+        let down = migration.down.as_str();
+        assert!(down.contains("DROP TABLE \"audit\";"));
+        assert!(down.contains("ALTER TABLE \"accounts\" RENAME TO \"users\";"));
+        assert!(down.contains("ALTER TABLE \"users\" RENAME COLUMN \"mail\" TO \"email\";"));
+        assert!(down.contains("ALTER TABLE \"users\" ADD COLUMN \"age\" INTEGER;"));
+        assert!(down.contains("ALTER TABLE \"users\" ALTER COLUMN \"email\" DROP NOT NULL;"));
+        assert!(down.contains("CREATE TABLE \"logs\""));
+        assert!(down.contains("ON UPDATE NO ACTION ON DELETE CASCADE;"));
+        assert!(to_migration(&to, &to, Dialect::Postgres).up.is_empty());
+    }
+
+    #[test]
+    fn test_mysql_migration(){
+        let (from, mut to) = build_diagrams();
+        // 未命名的外键无法删除，主键改为联合主键
+        to.references.as_mut().unwrap()[0].name = None;
+        to.tables.as_mut().unwrap()[1].fields.as_mut().unwrap()[1].primary = Some(true);
+        let migration = to_migration(&from, &to, Dialect::MySql);
+        assert!(migration.up.contains("ALTER TABLE `orders` DROP FOREIGN KEY `fk_orders_users`;"));
+        assert!(migration.up.contains("ALTER TABLE `accounts` MODIFY COLUMN `mail` VARCHAR(320) NOT NULL;"));
+        assert!(migration.up.contains("ALTER TABLE `orders` DROP PRIMARY KEY;\n\nALTER TABLE `orders` ADD PRIMARY KEY (`id`, `user_id`);"));
+        assert!(migration.up.contains("DROP INDEX `idx_orders_user` ON `orders`;"));
+        assert!(migration.down.contains("-- 需要手动处理: 删除orders.user_id上未命名的外键"));
+        assert!(migration.down.contains("ALTER TABLE `orders` ADD PRIMARY KEY (`id`);"));
+    }
+
+    #[test]
+    fn test_sqlite_migration(){
+        let (from, to) = build_diagrams();
+        let migration = to_migration(&from, &to, Dialect::Sqlite);
+        let up = migration.up.as_str();
+        assert!(up.starts_with("PRAGMA foreign_keys = OFF;\n"));
+        assert!(up.ends_with("PRAGMA foreign_keys = ON;\n"));
+        assert!(up.contains("ALTER TABLE \"users\" RENAME TO \"accounts\";"));
+        assert!(up.contains("CREATE TABLE IF NOT EXISTS \"_new_accounts\" (\n\t\"id\" INTEGER,\n\t\"mail\" VARCHAR(320) NOT NULL,\n\t\"created_at\" DATE,"));
+        assert!(up.contains("INSERT INTO \"_new_accounts\" (\"id\", \"mail\")\nSELECT \"id\", \"email\" FROM \"accounts\";\n\nDROP TABLE \"accounts\";\n\nALTER TABLE \"_new_accounts\" RENAME TO \"accounts\";"));
+        // 外键变化的表也需要重建，索引随表重建
+        assert!(up.contains("CONSTRAINT \"fk_orders_users\" FOREIGN KEY (\"user_id\") REFERENCES \"accounts\" (\"id\")\n\tON UPDATE NO ACTION ON DELETE RESTRICT"));
+        assert!(up.contains("ALTER TABLE \"_new_orders\" RENAME TO \"orders\";\n\nCREATE UNIQUE INDEX IF NOT EXISTS \"idx_orders_user\""));
+        assert!(!up.contains("DROP INDEX"));
+
+        // 只新增可空列时不需要重建
+        let mut added = from.clone();
+        added.tables.as_mut().unwrap()[2].fields.as_mut().unwrap().push(field("l2", "message", "TEXT"));
+        let migration = to_migration(&from, &added, Dialect::Sqlite);
+        assert_eq!(migration.up, "ALTER TABLE \"logs\" ADD COLUMN \"message\" TEXT;\n");
+        assert!(migration.down.contains("CREATE TABLE IF NOT EXISTS \"_new_logs\""));
+    }
+
+    #[test]
+    fn test_migration_files(){
+        let migration = Migration { up: "up;\n".to_string(), down: "down;\n".to_string() };
+        assert_eq!(migration.to_script(), "-- migrate:up\nup;\n\n-- migrate:down\ndown;\n");
+        let files = migration.to_files(MigrationFormat::Migrate, 3, "Add users");
+        assert_eq!(files, vec![
+            ("000003_add_users.up.sql".to_string(), "up;\n".to_string()),
+            ("000003_add_users.down.sql".to_string(), "down;\n".to_string()),
+        ]);
+        let files = migration.to_files(MigrationFormat::Flyway, 3, "add_users");
+        assert_eq!(files[0].0, "V3__add_users.sql");
+        assert_eq!(files[1].0, "U3__add_users.sql");
+    }
+}
@@ -0,0 +1,287 @@
+mod dbml;
+mod dialect;
+mod docs;
+mod mermaid;
+mod migration;
+mod plantuml;
+mod sql;
+pub use dbml::*;
+pub use dialect::*;
+pub use docs::*;
+pub use mermaid::*;
+pub use migration::*;
+pub use plantuml::*;
+pub use sql::*;
+use actix_web::{get, web, HttpResponse};
+use sea_orm::DatabaseConnection;
+use serde::Deserialize;
+use crate::common::{CommonResponse, ResponseCode, ResponseMessage};
+use crate::diagrams::query_diagram_detail;
+use crate::entity::vo::DiagramDocumentVo;
+use crate::error::DrawDBError;
+
+/// 导出模块，挂载在/diagrams下
+pub fn export_routes(config: &mut web::ServiceConfig) {
+    config.service(export_sql);
+    config.service(export_dbml);
+    config.service(export_mermaid);
+    config.service(export_plantuml);
+    config.service(export_docs);
+    config.service(export_json);
+}
+
+/// 导出参数
+#[derive(Debug, Deserialize)]
+pub struct ExportQuery {
+    // 数据库方言，未指定时使用图表保存的数据库类型
+    pub dialect: Option<String>,
+}
+
+/// 文档导出参数
+#[derive(Debug, Deserialize)]
+pub struct DocsQuery {
+    // 文档格式，md或html，默认为md
+    pub format: Option<String>,
+}
+
+/// 图表不存在时的响应
+fn not_found() -> HttpResponse {
+    HttpResponse::Ok().json(CommonResponse::new(
+        ResponseCode::NotFound,
+        ResponseMessage::NotFound,
+        None,
+    ))
+}
+
+/// 导出内容以纯文本返回
+fn text(body: String) -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type("text/plain; charset=utf-8")
+        .body(body)
+}
+
+/// 导出图表的DDL
+/// 返回纯文本的建表、索引和外键语句
+#[get("/{id}/export/sql")]
+async fn export_sql(
+    db: web::Data<DatabaseConnection>,
+    id: web::Path<String>,
+    query: web::Query<ExportQuery>
+) -> Result<HttpResponse, DrawDBError> {
+    let Some(diagram) = query_diagram_detail(db.get_ref(), id.into_inner()).await? else {
+        return Ok(not_found());
+    };
+    let dialect = Dialect::resolve(query.dialect.as_deref(), diagram.database.as_deref())?;
+    Ok(text(to_sql(&diagram, dialect)))
+}
+
+/// 导出图表的DBML
+#[get("/{id}/export/dbml")]
+async fn export_dbml(
+    db: web::Data<DatabaseConnection>,
+    id: web::Path<String>
+) -> Result<HttpResponse, DrawDBError> {
+    let Some(diagram) = query_diagram_detail(db.get_ref(), id.into_inner()).await? else {
+        return Ok(not_found());
+    };
+    Ok(text(to_dbml(&diagram)))
+}
+
+/// 导出图表的Mermaid erDiagram
+#[get("/{id}/export/mermaid")]
+async fn export_mermaid(
+    db: web::Data<DatabaseConnection>,
+    id: web::Path<String>
+) -> Result<HttpResponse, DrawDBError> {
+    let Some(diagram) = query_diagram_detail(db.get_ref(), id.into_inner()).await? else {
+        return Ok(not_found());
+    };
+    Ok(text(to_mermaid(&diagram)))
+}
+
+/// 导出图表的PlantUML实体关系图
+#[get("/{id}/export/plantuml")]
+async fn export_plantuml(
+    db: web::Data<DatabaseConnection>,
+    id: web::Path<String>
+) -> Result<HttpResponse, DrawDBError> {
+    let Some(diagram) = query_diagram_detail(db.get_ref(), id.into_inner()).await? else {
+        return Ok(not_found());
+    };
+    Ok(text(to_plantuml(&diagram)))
+}
+
+/// 导出图表的数据字典文档
+#[get("/{id}/export/docs")]
+async fn export_docs(
+    db: web::Data<DatabaseConnection>,
+    id: web::Path<String>,
+    query: web::Query<DocsQuery>
+) -> Result<HttpResponse, DrawDBError> {
+    let format = match query.format.as_deref() {
+        Some(format) => format.parse()?,
+        None => DocFormat::Markdown,
+    };
+    let Some(diagram) = query_diagram_detail(db.get_ref(), id.into_inner()).await? else {
+        return Ok(not_found());
+    };
+    let docs = to_docs(&diagram, format);
+    match format {
+        DocFormat::Markdown => Ok(text(docs)),
+        DocFormat::Html => Ok(HttpResponse::Ok().content_type("text/html; charset=utf-8").body(docs)),
+    }
+}
+
+/// 导出前端编辑器格式的JSON文档
+#[get("/{id}/export/json")]
+async fn export_json(
+    db: web::Data<DatabaseConnection>,
+    id: web::Path<String>
+) -> Result<HttpResponse, DrawDBError> {
+    let Some(diagram) = query_diagram_detail(db.get_ref(), id.into_inner()).await? else {
+        return Ok(not_found());
+    };
+    Ok(HttpResponse::Ok().json(DiagramDocumentVo::from(&diagram)))
+}
+
+#[cfg(test)]
+mod test {
+    use actix_web::{test, App};
+    use sea_orm::{ActiveModelTrait, ConnectionTrait, TransactionTrait};
+
+    use super::*;
+    use crate::diagrams::add_diagram_links;
+    use crate::entity::{diagram, diagram_link};
+    use crate::entity::vo::{FieldVo, ReferenceVo};
+    use crate::entity::vo::fixtures::{build_field, build_primary_field, build_table};
+    use crate::init::init_test_db;
+    use crate::references::add_references;
+    use crate::tables::batch_add_table;
+
+    fn id_field(id: &str) -> FieldVo {
+        FieldVo {
+            increment: Some(true),
+            ..build_primary_field(id, "id", "INTEGER")
+        }
+    }
+
+    /// 创建SQLite图表d1：users(id) <- orders(id, user_id)
+    async fn seed_diagram(db: &DatabaseConnection) {
+        let tx = db.begin().await.unwrap();
+        diagram::ActiveModel::from(diagram::Model {
+            id: "d1".to_string(),
+            database: Some("sqlite".to_string()),
+            zoom: None,
+            name: Some("shop".to_string()),
+            pan: None,
+            last_modified: None,
+            types: None,
+            enums: None,
+        }).insert(&tx).await.unwrap();
+        let tables = vec![
+            build_table("0", "users", vec![id_field("0")], vec![]),
+            build_table("1", "orders", vec![id_field("0"), build_field("1", "user_id", "INTEGER")], vec![]),
+        ];
+        let id_mapping = batch_add_table(&tx, tables).await.unwrap();
+        let mut reference = ReferenceVo {
+            id: "r1".to_string(),
+            name: Some("fk_orders_users".to_string()),
+            start_table_id: Some("1".to_string()),
+            end_table_id: Some("0".to_string()),
+            cardinality: Some("many_to_one".to_string()),
+            delete_constraint: Some("Cascade".to_string()),
+            end_field_id: Some("0".to_string()),
+            start_field_id: Some("1".to_string()),
+            update_constraint: Some("No action".to_string()),
+        };
+        id_mapping.apply_to_reference(&mut reference);
+        add_references(&tx, vec![reference]).await.unwrap();
+        add_diagram_links(&tx, "d1", diagram_link::Column::ReferenceId, vec!["r1".to_string()]).await.unwrap();
+        tx.commit().await.unwrap();
+    }
+
+    /// 导出的SQLite DDL可以直接在SQLite中执行
+    #[actix_web::test]
+    async fn test_export_sql() {
+        let db = init_test_db().await;
+        seed_diagram(&db).await;
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(db))
+                .configure(export_routes)
+        ).await;
+        // 未指定方言时使用图表的数据库类型
+        let req = test::TestRequest::get().uri("/d1/export/sql").to_request();
+        let body = test::call_and_read_body(&app, req).await;
+        let sql = String::from_utf8(body.to_vec()).unwrap();
+        assert!(sql.contains("CREATE TABLE IF NOT EXISTS \"orders\""));
+        assert!(sql.contains("ON UPDATE NO ACTION ON DELETE CASCADE"));
+        let target = init_test_db().await;
+        target.execute_unprepared(&sql).await.unwrap();
+
+        let req = test::TestRequest::get().uri("/d1/export/sql?dialect=postgres").to_request();
+        let body = test::call_and_read_body(&app, req).await;
+        let sql = String::from_utf8(body.to_vec()).unwrap();
+        assert!(sql.contains("ALTER TABLE \"orders\"\nADD CONSTRAINT \"fk_orders_users\""));
+
+        let req = test::TestRequest::get().uri("/d1/export/sql?dialect=db2").to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 400);
+
+        let req = test::TestRequest::get().uri("/missing/export/sql").to_request();
+        let resp: CommonResponse = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(resp.code, 404);
+    }
+
+    #[actix_web::test]
+    async fn test_export_mermaid() {
+        let db = init_test_db().await;
+        seed_diagram(&db).await;
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(db))
+                .configure(export_routes)
+        ).await;
+        let req = test::TestRequest::get().uri("/d1/export/mermaid").to_request();
+        let body = test::call_and_read_body(&app, req).await;
+        let mermaid = String::from_utf8(body.to_vec()).unwrap();
+        assert!(mermaid.starts_with("erDiagram\n\torders }o--|| users : \"fk_orders_users\""));
+        assert!(mermaid.contains("integer user_id FK"));
+    }
+
+    #[actix_web::test]
+    async fn test_export_plantuml() {
+        let db = init_test_db().await;
+        seed_diagram(&db).await;
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(db))
+                .configure(export_routes)
+        ).await;
+        let req = test::TestRequest::get().uri("/d1/export/plantuml").to_request();
+        let body = test::call_and_read_body(&app, req).await;
+        let plantuml = String::from_utf8(body.to_vec()).unwrap();
+        assert!(plantuml.starts_with("@startuml\n"));
+        assert!(plantuml.contains("t1 }o--|| t0 : fk_orders_users"));
+    }
+
+    #[actix_web::test]
+    async fn test_export_docs() {
+        let db = init_test_db().await;
+        seed_diagram(&db).await;
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(db))
+                .configure(export_routes)
+        ).await;
+        let req = test::TestRequest::get().uri("/d1/export/docs?format=html").to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.headers().get("content-type").unwrap(), "text/html; charset=utf-8");
+        let html = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+        assert!(html.contains("<h2>orders</h2>"));
+
+        let req = test::TestRequest::get().uri("/d1/export/docs?format=pdf").to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 400);
+    }
+}
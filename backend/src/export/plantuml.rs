@@ -0,0 +1,220 @@
+use std::collections::HashMap;
+use crate::entity::vo::{DiagramVo, FieldVo, TableVo};
+use crate::export::area_contains;
+
+/// 生成图表的PlantUML实体关系图
+/// 主题区域输出为package，其中包含左上角位于区域内的表，注释输出为独立的note
+pub fn to_plantuml(diagram: &DiagramVo) -> String {
+    let tables = diagram.tables.clone().unwrap_or_default();
+    let areas = diagram.areas.clone().unwrap_or_default();
+    // 实体使用别名，避免表名中的特殊字符
+    let aliases = tables.iter()
+    .enumerate()
+    .map(|(index, table)| (table.id.as_str(), format!("t{}", index)))
+    .collect::<HashMap<&str, String>>();
+    let mut lines = vec![
+        "@startuml".to_string(),
+        "hide circle".to_string(),
+        "skinparam linetype ortho".to_string(),
+    ];
+
+    //1、实体，每张表只归属于第一个包含它的主题区域
+    let mut packages = vec![Vec::new(); areas.len()];
+    let mut entities = Vec::new();
+    for table in &tables {
+        let block = entity(table, &aliases[table.id.as_str()]);
+        match areas.iter().position(|area| area_contains(area, table)) {
+            Some(index) => packages[index].push(block),
+            None => entities.push(block),
+        }
+    }
+    for (area, blocks) in areas.iter().zip(packages) {
+        if blocks.is_empty() {
+            continue;
+        }
+        let name = area.name.clone().unwrap_or_else(|| area.id.clone());
+        lines.push(String::new());
+        lines.push(format!("package {} {{", plantuml_string(&name)));
+        lines.push(blocks.join("\n\n"));
+        lines.push("}".to_string());
+    }
+    for block in entities {
+        lines.push(String::new());
+        lines.push(block);
+    }
+
+    //2、关联关系
+    let relationships = diagram.references.clone().unwrap_or_default().iter().filter_map(|reference| {
+        let start = aliases.get(reference.start_table_id.as_deref()?)?;
+        let end = aliases.get(reference.end_table_id.as_deref()?)?;
+        let arrow = match reference.cardinality.as_deref() {
+            Some("one_to_one") => "||--||",
+            Some("one_to_many") => "||--o{",
+            _ => "}o--||",
+        };
+        let label = reference.name.as_deref()
+        .filter(|name| !name.trim().is_empty())
+        .map(|name| format!(" : {}", single_line(name)))
+        .unwrap_or_default();
+        Some(format!("{} {} {}{}", start, arrow, end, label))
+    }).collect::<Vec<String>>();
+    if !relationships.is_empty() {
+        lines.push(String::new());
+        lines.extend(relationships);
+    }
+
+    //3、注释
+    for (index, note) in diagram.notes.clone().unwrap_or_default().iter().enumerate() {
+        lines.push(String::new());
+        lines.push(format!("note as N{}", index));
+        if let Some(title) = note.title.as_ref().filter(|title| !title.trim().is_empty()) {
+            lines.push(format!("**{}**", single_line(title)));
+        }
+        lines.extend(note.content.clone().unwrap_or_default().lines().map(str::to_string));
+        lines.push("end note".to_string());
+    }
+
+    lines.push(String::new());
+    lines.push("@enduml".to_string());
+    lines.join("\n") + "\n"
+}
+
+/// entity "name" as alias { 主键列; --; 其余列 }
+/// 主键和非空列使用*标记
+fn entity(table: &TableVo, alias: &str) -> String {
+    let fields = table.fields.clone().unwrap_or_default();
+    let (primary, others): (Vec<&FieldVo>, Vec<&FieldVo>) = fields.iter().partition(|field| field.primary.unwrap_or(false));
+    let mut lines = vec![format!("entity {} as {} {{", plantuml_string(&table_name(table)), alias)];
+    lines.extend(primary.iter().map(|field| column(field)));
+    if !primary.is_empty() && !others.is_empty() {
+        lines.push("\t--".to_string());
+    }
+    lines.extend(others.iter().map(|field| column(field)));
+    lines.push("}".to_string());
+    lines.join("\n")
+}
+
+/// [*]名称 : 类型 <<标记>>
+fn column(field: &FieldVo) -> String {
+    let primary = field.primary.unwrap_or(false);
+    let mandatory = if primary || field.not_null.unwrap_or(false) { "* " } else { "" };
+    let mut r#type = field.r#type.clone().unwrap_or_default().trim().to_lowercase();
+    if let Some(size) = field.size.filter(|size| *size > 0) {
+        r#type = format!("{}({})", r#type, size);
+    }
+    let mut line = format!("\t{}{} : {}", mandatory, single_line(&field_name(field)), r#type);
+    if primary {
+        line.push_str(" <<PK>>");
+    }
+    if field.unique.unwrap_or(false) && !primary {
+        line.push_str(" <<UK>>");
+    }
+    if field.increment.unwrap_or(false) {
+        line.push_str(" <<generated>>");
+    }
+    line
+}
+
+fn plantuml_string(value: &str) -> String {
+    format!("\"{}\"", single_line(value).replace('"', "'"))
+}
+
+fn single_line(value: &str) -> String {
+    value.replace(['\r', '\n'], " ")
+}
+
+fn table_name(table: &TableVo) -> String {
+    table.name.clone().unwrap_or_else(|| table.id.clone())
+}
+
+fn field_name(field: &FieldVo) -> String {
+    field.name.clone().unwrap_or_else(|| field.id.clone())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::entity::vo::{AreaVo, NoteVo, ReferenceVo};
+    use crate::entity::vo::fixtures::{build_diagram, build_field, build_table};
+
+    fn field(id: &str, name: &str, primary: bool, not_null: bool) -> FieldVo {
+        FieldVo {
+            primary: Some(primary),
+            not_null: Some(not_null),
+            ..build_field(id, name, "INTEGER")
+        }
+    }
+
+    fn table(id: &str, name: &str, x: &str, fields: Vec<FieldVo>) -> TableVo {
+        TableVo {
+            x: Some(x.to_string()),
+            y: Some("50".to_string()),
+            ..build_table(id, name, fields, vec![])
+        }
+    }
+
+    #[test]
+    fn test_plantuml(){
+        let reference = ReferenceVo {
+            id: "r1".to_string(),
+            name: Some("fk_orders_users".to_string()),
+            start_table_id: Some("t2".to_string()),
+            end_table_id: Some("t1".to_string()),
+            cardinality: Some("many_to_one".to_string()),
+            delete_constraint: None,
+            end_field_id: Some("u1".to_string()),
+            start_field_id: Some("o2".to_string()),
+            update_constraint: None,
+        };
+        let area = AreaVo {
+            id: "a1".to_string(),
+            color: None,
+            height: Some("300".to_string()),
+            name: Some("accounts".to_string()),
+            width: Some("300".to_string()),
+            x: Some("0".to_string()),
+            y: Some("0".to_string()),
+        };
+        let note = NoteVo {
+            id: "n1".to_string(),
+            color: None,
+            content: Some("line one\nline two".to_string()),
+            height: None,
+            title: Some("todo".to_string()),
+            x: None,
+            y: None,
+        };
+        let mut diagram = build_diagram(vec![
+            table("t1", "users", "50", vec![field("u1", "id", true, false)]),
+            table("t2", "order items", "500", vec![
+                field("o1", "id", true, true),
+                field("o2", "user_id", false, true),
+                field("o3", "memo", false, false),
+            ]),
+        ], vec![reference]);
+        diagram.areas = Some(vec![area]);
+        diagram.notes = Some(vec![note]);
+        let plantuml = to_plantuml(&diagram);
+        assert_eq!(plantuml, "@startuml\n\
+            hide circle\n\
+            skinparam linetype ortho\n\n\
+            package \"accounts\" {\n\
+            entity \"users\" as t0 {\n\
+            \t* id : integer <<PK>>\n\
+            }\n\
+            }\n\n\
+            entity \"order items\" as t1 {\n\
+            \t* id : integer <<PK>>\n\
+            \t--\n\
+            \t* user_id : integer\n\
+            \tmemo : integer\n\
+            }\n\n\
+            t1 }o--|| t0 : fk_orders_users\n\n\
+            note as N0\n\
+            **todo**\n\
+            line one\n\
+            line two\n\
+            end note\n\n\
+            @enduml\n");
+    }
+}
@@ -0,0 +1,476 @@
+use std::collections::HashMap;
+use crate::entity::vo::{DiagramVo, EnumVo, FieldVo, IndiceVo, ReferenceVo, TableVo, TypeVo};
+use crate::datatypes::{is_function, DataTypes};
+use crate::export::Dialect;
+
+// 默认值中不需要加引号的关键字
+const DEFAULT_KEYWORDS: [&str; 4] = ["CURRENT_TIMESTAMP", "NULL", "TRUE", "FALSE"];
+
+/// 生成图表的DDL
+/// 依次输出建表语句、索引和外键，SQLite不支持ALTER TABLE添加外键，外键写在建表语句中
+/// PostgreSQL的枚举和自定义类型在建表之前创建
+pub fn to_sql(diagram: &DiagramVo, dialect: Dialect) -> String {
+    let tables = diagram.tables.clone().unwrap_or_default();
+    let references = diagram.references.clone().unwrap_or_default();
+    let tables_by_id = tables.iter()
+    .map(|table| (table.id.as_str(), table))
+    .collect::<HashMap<&str, &TableVo>>();
+    let foreign_keys = references.iter()
+    .filter_map(|reference| ForeignKey::resolve(reference, &tables_by_id))
+    .collect::<Vec<ForeignKey>>();
+
+    let mut statements = Vec::new();
+    if dialect == Dialect::Postgres {
+        statements.extend(diagram.enums.iter().flatten().map(|r#enum| enum_statement(r#enum, dialect)));
+        statements.extend(diagram.types.iter().flatten().map(|r#type| type_statement(r#type, dialect)));
+    }
+    statements.extend(tables.iter().map(|table| table_statement(table, &foreign_keys, dialect)));
+    if dialect != Dialect::Sqlite {
+        statements.extend(foreign_keys.iter().map(|foreign_key| foreign_key.alter_statement(dialect)));
+    }
+    statements.join("\n")
+}
+
+/// CREATE TYPE ... AS ENUM
+fn enum_statement(r#enum: &EnumVo, dialect: Dialect) -> String {
+    format!("CREATE TYPE {} AS ENUM ({});\n", dialect.quote(&r#enum.name), quote_values(&r#enum.values))
+}
+
+/// 复合类型 CREATE TYPE ... AS (...)
+fn type_statement(r#type: &TypeVo, dialect: Dialect) -> String {
+    let fields = r#type.fields.iter()
+    .map(|field| format!("\t{} {}", dialect.quote(&field.name), field.r#type.trim()))
+    .collect::<Vec<String>>();
+    let mut statement = format!("CREATE TYPE {} AS (\n{}\n);\n", dialect.quote(&r#type.name), fields.join(",\n"));
+    if !r#type.comment.trim().is_empty() {
+        statement.push_str(&format!("COMMENT ON TYPE {} IS {};\n", dialect.quote(&r#type.name), quote_literal(&r#type.comment)));
+    }
+    statement
+}
+
+/// 关联关系解析出的外键，表id、字段id已替换为名称
+#[derive(Clone)]
+pub(super) struct ForeignKey {
+    pub(super) id: String,
+    pub(super) name: Option<String>,
+    pub(super) start_table_id: String,
+    pub(super) start_table: String,
+    pub(super) start_field: String,
+    pub(super) end_table: String,
+    pub(super) end_field: String,
+    update_constraint: Option<String>,
+    delete_constraint: Option<String>,
+}
+
+impl ForeignKey {
+    /// 表或字段不存在时忽略该关联关系
+    pub(super) fn resolve(reference: &ReferenceVo, tables_by_id: &HashMap<&str, &TableVo>) -> Option<Self> {
+        let start_table = tables_by_id.get(reference.start_table_id.as_deref()?)?;
+        let end_table = tables_by_id.get(reference.end_table_id.as_deref()?)?;
+        Some(Self {
+            id: reference.id.clone(),
+            name: reference.name.clone().filter(|name| !name.trim().is_empty()),
+            start_table_id: start_table.id.clone(),
+            start_table: table_name(start_table),
+            start_field: field_name_by_id(start_table, reference.start_field_id.as_deref()?)?,
+            end_table: table_name(end_table),
+            end_field: field_name_by_id(end_table, reference.end_field_id.as_deref()?)?,
+            update_constraint: reference.update_constraint.clone(),
+            delete_constraint: reference.delete_constraint.clone(),
+        })
+    }
+
+    /// FOREIGN KEY ... REFERENCES ... ON UPDATE ... ON DELETE ...
+    pub(super) fn clause(&self, dialect: Dialect) -> String {
+        let constraint = self.name.as_ref()
+        .map(|name| format!("CONSTRAINT {} ", dialect.quote(name)))
+        .unwrap_or_default();
+        let mut clause = format!(
+            "{}FOREIGN KEY ({}) REFERENCES {} ({})",
+            constraint,
+            dialect.quote(&self.start_field),
+            dialect.quote(&self.end_table),
+            dialect.quote(&self.end_field)
+        );
+        let actions = [
+            ("ON UPDATE", update_action(self.update_constraint.as_deref(), dialect)),
+            ("ON DELETE", delete_action(self.delete_constraint.as_deref(), dialect)),
+        ]
+        .into_iter()
+        .filter_map(|(keyword, action)| action.map(|action| format!("{} {}", keyword, action)))
+        .collect::<Vec<String>>();
+        if !actions.is_empty() {
+            clause.push_str(&format!("\n\t{}", actions.join(" ")));
+        }
+        clause
+    }
+
+    pub(super) fn alter_statement(&self, dialect: Dialect) -> String {
+        let statement = format!(
+            "ALTER TABLE {}\nADD {};\n",
+            dialect.quote(&self.start_table),
+            self.clause(dialect)
+        );
+        with_batch_separator(statement, dialect)
+    }
+}
+
+/// 单张表的建表语句以及索引
+pub(super) fn table_statement(table: &TableVo, foreign_keys: &[ForeignKey], dialect: Dialect) -> String {
+    let name = table_name(table);
+    let fields = table.fields.clone().unwrap_or_default();
+    let primary_keys = fields.iter()
+    .filter(|field| field.primary.unwrap_or(false))
+    .collect::<Vec<&FieldVo>>();
+    // SQLite的自增只能写在INTEGER PRIMARY KEY列上
+    let inline_primary_key = match primary_keys.as_slice() {
+        [field] if dialect == Dialect::Sqlite
+            && field.increment.unwrap_or(false)
+            && field_type(field).eq_ignore_ascii_case("INTEGER") => Some(field.id.clone()),
+        _ => None,
+    };
+
+    //1、列定义
+    let mut lines = fields.iter()
+    .map(|field| {
+        let inline = inline_primary_key.as_deref() == Some(field.id.as_str());
+        format!("{}\t{}", field_comment(field, dialect), column_definition(field, inline, dialect))
+    })
+    .collect::<Vec<String>>();
+    //2、主键
+    if !primary_keys.is_empty() && inline_primary_key.is_none() {
+        let columns = primary_keys.iter()
+        .map(|field| dialect.quote(&field_name(field)))
+        .collect::<Vec<String>>()
+        .join(", ");
+        lines.push(format!("\tPRIMARY KEY ({})", columns));
+    }
+    //3、SQLite的外键写在建表语句中
+    if dialect == Dialect::Sqlite {
+        lines.extend(foreign_keys.iter()
+        .filter(|foreign_key| foreign_key.start_table_id == table.id)
+        .map(|foreign_key| format!("\t{}", foreign_key.clause(dialect))));
+    }
+
+    let comment = table.comment.clone().filter(|comment| !comment.trim().is_empty());
+    let create = match dialect {
+        Dialect::Sqlite => "CREATE TABLE IF NOT EXISTS",
+        _ => "CREATE TABLE",
+    };
+    let mut statement = match (&comment, dialect) {
+        (Some(_), Dialect::MySql | Dialect::MariaDb | Dialect::Postgres) | (None, _) => String::new(),
+        (Some(comment), _) => format!("/* {} */\n", comment.replace("*/", "* /")),
+    };
+    statement.push_str(&format!("{} {} (\n{}\n)", create, dialect.quote(&name), lines.join(",\n")));
+    if let (Some(comment), Dialect::MySql | Dialect::MariaDb) = (&comment, dialect) {
+        statement.push_str(&format!(" COMMENT={}", quote_literal(comment)));
+    }
+    statement.push_str(";\n");
+    let mut statement = with_batch_separator(statement, dialect);
+
+    //4、PostgreSQL的注释使用COMMENT ON
+    if dialect == Dialect::Postgres {
+        if let Some(comment) = &comment {
+            statement.push_str(&format!("COMMENT ON TABLE {} IS {};\n", dialect.quote(&name), quote_literal(comment)));
+        }
+        for field in fields.iter() {
+            if let Some(comment) = field.comment.as_ref().filter(|comment| !comment.trim().is_empty()) {
+                statement.push_str(&format!(
+                    "COMMENT ON COLUMN {}.{} IS {};\n",
+                    dialect.quote(&name),
+                    dialect.quote(&field_name(field)),
+                    quote_literal(comment)
+                ));
+            }
+        }
+    }
+    //5、索引
+    for indice in table.indices.clone().unwrap_or_default() {
+        if let Some(index) = index_statement(table, &indice, dialect) {
+            statement.push_str(&index);
+        }
+    }
+    statement
+}
+
+/// 单个索引的创建语句，索引的字段都不存在时返回None
+pub(super) fn index_statement(table: &TableVo, indice: &IndiceVo, dialect: Dialect) -> Option<String> {
+    let columns = indice.fields.clone().unwrap_or_default().iter()
+    .filter_map(|field_id| field_name_by_id(table, field_id))
+    .map(|field_name| dialect.quote(&field_name))
+    .collect::<Vec<String>>();
+    if columns.is_empty() {
+        return None;
+    }
+    let if_not_exists = if dialect == Dialect::Sqlite { "IF NOT EXISTS " } else { "" };
+    let index = format!(
+        "CREATE {}INDEX {}{}\nON {} ({});\n",
+        if indice.unique.unwrap_or(false) { "UNIQUE " } else { "" },
+        if_not_exists,
+        dialect.quote(&index_name(indice)),
+        dialect.quote(&table_name(table)),
+        columns.join(", ")
+    );
+    Some(with_batch_separator(index, dialect))
+}
+
+/// 列定义：名称、类型、约束
+pub(super) fn column_definition(field: &FieldVo, inline_primary_key: bool, dialect: Dialect) -> String {
+    let mut column = format!("{} {}", dialect.quote(&field_name(field)), field_type(field));
+    // MySQL的ENUM和SET在类型中列出可选值
+    let values = field.values.as_ref()
+    .filter(|values| !values.is_empty())
+    .filter(|_| matches!(dialect, Dialect::MySql | Dialect::MariaDb))
+    .filter(|_| ["ENUM", "SET"].iter().any(|r#type| field_type(field).eq_ignore_ascii_case(r#type)));
+    if let Some(values) = values {
+        column.push_str(&format!("({})", quote_values(values)));
+    } else if let Some(size) = field.size.filter(|size| *size > 0) {
+        column.push_str(&format!("({})", size));
+    }
+    if inline_primary_key {
+        column.push_str(" PRIMARY KEY AUTOINCREMENT");
+    }
+    // 自增属性紧跟类型，Oracle要求IDENTITY位于NOT NULL、DEFAULT等约束之前
+    if field.increment.unwrap_or(false) {
+        let identity = match dialect {
+            Dialect::MySql | Dialect::MariaDb => Some(" AUTO_INCREMENT"),
+            Dialect::Postgres => Some(" GENERATED BY DEFAULT AS IDENTITY"),
+            Dialect::MsSql => Some(" IDENTITY"),
+            Dialect::OracleSql => Some(" GENERATED ALWAYS AS IDENTITY"),
+            Dialect::Sqlite => None,
+        };
+        column.push_str(identity.unwrap_or_default());
+    }
+    if field.not_null.unwrap_or(false) {
+        column.push_str(" NOT NULL");
+    }
+    if field.unique.unwrap_or(false) {
+        column.push_str(" UNIQUE");
+    }
+    if let Some(default) = field.default.as_ref().filter(|default| !default.trim().is_empty()) {
+        column.push_str(&format!(" DEFAULT {}", parse_default(default, &field_type(field), dialect)));
+    }
+    if let Some(check) = field.check.as_ref().filter(|check| !check.trim().is_empty()) {
+        column.push_str(&format!(" CHECK({})", check));
+    }
+    if let (Some(comment), Dialect::MySql | Dialect::MariaDb) = (field.comment.as_ref().filter(|comment| !comment.trim().is_empty()), dialect) {
+        column.push_str(&format!(" COMMENT {}", quote_literal(comment)));
+    }
+    column
+}
+
+/// 不支持行内注释的方言，字段注释写在列定义的上一行
+fn field_comment(field: &FieldVo, dialect: Dialect) -> String {
+    match (field.comment.as_ref().filter(|comment| !comment.trim().is_empty()), dialect) {
+        (Some(comment), Dialect::Sqlite | Dialect::MsSql | Dialect::OracleSql) => comment
+        .lines()
+        .map(|line| format!("\t-- {}\n", line))
+        .collect(),
+        _ => String::new(),
+    }
+}
+
+/// 默认值已带引号、是函数调用、关键字或者类型不需要引号时原样输出，否则加引号
+/// 类型表中不存在的类型（如自定义类型、枚举）只有数字不加引号
+pub(super) fn parse_default(default: &str, r#type: &str, dialect: Dialect) -> String {
+    let default = default.trim();
+    let has_quotes = default.len() >= 2
+        && ['\'', '"', '`'].iter().any(|quote| default.starts_with(*quote) && default.ends_with(*quote));
+    let is_keyword = DEFAULT_KEYWORDS.iter().any(|keyword| keyword.eq_ignore_ascii_case(default));
+    let quoted_type = match DataTypes::new(Some(dialect)).find(r#type) {
+        Some(data_type) => data_type.quoted,
+        None => default.parse::<f64>().is_err(),
+    };
+    if has_quotes || is_function(default) || is_keyword || !quoted_type {
+        default.to_string()
+    } else {
+        quote_literal(default)
+    }
+}
+
+/// 外键更新时的动作，Oracle不支持ON UPDATE，SQL Server不支持RESTRICT
+fn update_action(constraint: Option<&str>, dialect: Dialect) -> Option<String> {
+    match dialect {
+        Dialect::OracleSql => None,
+        _ => Some(normalize_action(constraint, dialect)),
+    }
+}
+
+/// 外键删除时的动作，Oracle只支持CASCADE和SET NULL
+fn delete_action(constraint: Option<&str>, dialect: Dialect) -> Option<String> {
+    let action = normalize_action(constraint, dialect);
+    match dialect {
+        Dialect::OracleSql if action != "CASCADE" && action != "SET NULL" => None,
+        _ => Some(action),
+    }
+}
+
+fn normalize_action(constraint: Option<&str>, dialect: Dialect) -> String {
+    let action = constraint
+    .map(|constraint| constraint.trim().to_uppercase())
+    .filter(|constraint| !constraint.is_empty())
+    .unwrap_or_else(|| "NO ACTION".to_string());
+    match (action.as_str(), dialect) {
+        ("RESTRICT", Dialect::MsSql) => "NO ACTION".to_string(),
+        _ => action,
+    }
+}
+
+/// SQL Server的每个批次以GO结束
+pub(super) fn with_batch_separator(statement: String, dialect: Dialect) -> String {
+    match dialect {
+        Dialect::MsSql => format!("{}GO\n", statement),
+        _ => statement,
+    }
+}
+
+pub(super) fn quote_literal(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+fn quote_values(values: &[String]) -> String {
+    values.iter()
+    .map(|value| quote_literal(value))
+    .collect::<Vec<String>>()
+    .join(", ")
+}
+
+pub(super) fn table_name(table: &TableVo) -> String {
+    table.name.clone().unwrap_or_else(|| table.id.clone())
+}
+
+pub(super) fn index_name(indice: &IndiceVo) -> String {
+    indice.name.clone().unwrap_or_else(|| indice.id.clone())
+}
+
+pub(super) fn field_name(field: &FieldVo) -> String {
+    field.name.clone().unwrap_or_else(|| field.id.clone())
+}
+
+pub(super) fn field_type(field: &FieldVo) -> String {
+    field.r#type.clone().unwrap_or_default().trim().to_string()
+}
+
+pub(super) fn field_name_by_id(table: &TableVo, field_id: &str) -> Option<String> {
+    table.fields.as_ref()?
+    .iter()
+    .find(|field| field.id == field_id)
+    .map(field_name)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::entity::vo::TypeFieldVo;
+    use crate::entity::vo::fixtures::{build_diagram, build_field, build_primary_field, build_table};
+
+    /// users(id, email) <- orders(id, user_id)
+    fn shop() -> DiagramVo {
+        let mut user_id = build_primary_field("u1", "id", "INTEGER");
+        user_id.increment = Some(true);
+        let mut email = build_field("u2", "email", "VARCHAR");
+        email.size = Some(255);
+        email.unique = Some(true);
+        email.default = Some("nobody".to_string());
+        email.comment = Some("login's email".to_string());
+        let users = build_table("t1", "users", vec![user_id, email], vec![]);
+
+        let mut order_id = build_field("o1", "id", "INTEGER");
+        order_id.primary = Some(true);
+        let mut order_user = build_field("o2", "user_id", "INTEGER");
+        order_user.check = Some("user_id > 0".to_string());
+        let indice = IndiceVo {
+            id: "i1".to_string(),
+            name: Some("idx_orders_user".to_string()),
+            unique: Some(false),
+            table_id: Some("t2".to_string()),
+            fields: Some(vec!["o2".to_string(), "o1".to_string()]),
+        };
+        let orders = build_table("t2", "orders", vec![order_id, order_user], vec![indice]);
+        let reference = ReferenceVo {
+            id: "r1".to_string(),
+            name: Some("fk_orders_users".to_string()),
+            start_table_id: Some("t2".to_string()),
+            end_table_id: Some("t1".to_string()),
+            cardinality: Some("many_to_one".to_string()),
+            delete_constraint: Some("Cascade".to_string()),
+            end_field_id: Some("u1".to_string()),
+            start_field_id: Some("o2".to_string()),
+            update_constraint: Some("Restrict".to_string()),
+        };
+        let mut diagram = build_diagram(vec![users, orders], vec![reference]);
+        diagram.name = Some("shop".to_string());
+        diagram
+    }
+
+    #[test]
+    fn test_postgres_sql(){
+        let sql = to_sql(&shop(), Dialect::Postgres);
+        assert!(sql.contains("CREATE TABLE \"users\" (\n\t\"id\" INTEGER GENERATED BY DEFAULT AS IDENTITY NOT NULL"));
+        assert!(sql.contains("\"email\" VARCHAR(255) UNIQUE DEFAULT 'nobody'"));
+        assert!(sql.contains("COMMENT ON COLUMN \"users\".\"email\" IS 'login''s email';"));
+        assert!(sql.contains("\"user_id\" INTEGER CHECK(user_id > 0)"));
+        assert!(sql.contains("CREATE INDEX \"idx_orders_user\"\nON \"orders\" (\"user_id\", \"id\");"));
+        assert!(sql.contains("ALTER TABLE \"orders\"\nADD CONSTRAINT \"fk_orders_users\" FOREIGN KEY (\"user_id\") REFERENCES \"users\" (\"id\")\n\tON UPDATE RESTRICT ON DELETE CASCADE;"));
+    }
+
+    #[test]
+    fn test_mysql_sql(){
+        let sql = to_sql(&shop(), Dialect::MySql);
+        assert!(sql.contains("`id` INTEGER AUTO_INCREMENT NOT NULL"));
+        assert!(sql.contains("`email` VARCHAR(255) UNIQUE DEFAULT 'nobody' COMMENT 'login''s email'"));
+        assert!(sql.contains("PRIMARY KEY (`id`)"));
+        assert!(sql.contains("ALTER TABLE `orders`\nADD CONSTRAINT `fk_orders_users` FOREIGN KEY (`user_id`)"));
+    }
+
+    #[test]
+    fn test_enum_sql(){
+        let mut status = build_field("s1", "status", "ENUM");
+        status.values = Some(vec!["new".to_string(), "paid".to_string()]);
+        let mut tags = build_field("s2", "tags", "SET");
+        tags.values = Some(vec!["gift".to_string(), "rush's".to_string()]);
+        let mut diagram = build_diagram(vec![build_table("t1", "orders", vec![status, tags], vec![])], vec![]);
+        let sql = to_sql(&diagram, Dialect::MySql);
+        assert!(sql.contains("`status` ENUM('new', 'paid')"));
+        assert!(sql.contains("`tags` SET('gift', 'rush''s')"));
+
+        diagram.enums = Some(vec![EnumVo { name: "order_status".to_string(), values: vec!["new".to_string(), "paid".to_string()] }]);
+        diagram.types = Some(vec![TypeVo {
+            name: "address".to_string(),
+            fields: vec![
+                TypeFieldVo { name: "street".to_string(), r#type: "VARCHAR".to_string(), values: None },
+                TypeFieldVo { name: "zip".to_string(), r#type: "INTEGER".to_string(), values: None },
+            ],
+            comment: "收货地址".to_string(),
+        }]);
+        diagram.tables = Some(vec![build_table("t1", "orders", vec![build_field("s1", "status", "order_status")], vec![])]);
+        let sql = to_sql(&diagram, Dialect::Postgres);
+        assert!(sql.starts_with("CREATE TYPE \"order_status\" AS ENUM ('new', 'paid');\n"));
+        assert!(sql.contains("CREATE TYPE \"address\" AS (\n\t\"street\" VARCHAR,\n\t\"zip\" INTEGER\n);\nCOMMENT ON TYPE \"address\" IS '收货地址';"));
+        assert!(sql.find("CREATE TYPE").unwrap() < sql.find("CREATE TABLE").unwrap());
+        assert!(sql.contains("\"status\" order_status"));
+        assert!(!to_sql(&diagram, Dialect::MySql).contains("CREATE TYPE"));
+    }
+
+    #[test]
+    fn test_sqlite_sql(){
+        let sql = to_sql(&shop(), Dialect::Sqlite);
+        assert!(sql.contains("CREATE TABLE IF NOT EXISTS \"users\" (\n\t\"id\" INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL"));
+        assert!(sql.contains("\t-- login's email\n\t\"email\""));
+        assert!(sql.contains("\tCONSTRAINT \"fk_orders_users\" FOREIGN KEY (\"user_id\") REFERENCES \"users\" (\"id\")"));
+        assert!(sql.contains("CREATE INDEX IF NOT EXISTS \"idx_orders_user\""));
+        assert!(!sql.contains("ALTER TABLE"));
+    }
+
+    #[test]
+    fn test_mssql_and_oracle_sql(){
+        let sql = to_sql(&shop(), Dialect::MsSql);
+        assert!(sql.contains("[id] INTEGER IDENTITY NOT NULL"));
+        assert!(sql.contains("ON UPDATE NO ACTION ON DELETE CASCADE;\nGO\n"));
+
+        let sql = to_sql(&shop(), Dialect::OracleSql);
+        assert!(sql.contains("\"id\" INTEGER GENERATED ALWAYS AS IDENTITY NOT NULL"));
+        assert!(sql.contains("REFERENCES \"users\" (\"id\")\n\tON DELETE CASCADE;"));
+        assert!(!sql.contains("ON UPDATE"));
+    }
+}
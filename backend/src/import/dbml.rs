@@ -0,0 +1,828 @@
+use std::collections::HashMap;
+use crate::entity::vo::{FieldVo, IndiceVo, NoteVo, ReferenceVo, TableVo};
+use crate::error::DrawDBError;
+use crate::import::{constraint_name, field_id_by_name};
+
+/// DBML词法单元，换行在DBML中有意义，单独保留
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    // 标识符、关键字或数字
+    Word(String),
+    // 双引号标识符
+    Quoted(String),
+    // 单引号或三引号字符串
+    Str(String),
+    // 反引号表达式
+    Expr(String),
+    Symbol(char),
+    Newline,
+}
+
+impl Token {
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(self, Token::Word(word) if word.eq_ignore_ascii_case(keyword))
+    }
+
+    fn is_symbol(&self, symbol: char) -> bool {
+        matches!(self, Token::Symbol(c) if *c == symbol)
+    }
+
+    fn text(&self) -> String {
+        match self {
+            Token::Word(text) | Token::Quoted(text) | Token::Str(text) | Token::Expr(text) => text.clone(),
+            Token::Symbol(c) => c.to_string(),
+            Token::Newline => "\n".to_string(),
+        }
+    }
+}
+
+/// DBML解析结果，表、字段、关联关系使用前端风格的临时id
+#[derive(Debug, Default)]
+pub struct DbmlSchema {
+    // Project中的database_type
+    pub database: Option<String>,
+    pub tables: Vec<TableVo>,
+    pub references: Vec<ReferenceVo>,
+    // TableGroup的名称以及包含的表的临时id
+    pub groups: Vec<(String, Vec<String>)>,
+    // 独立的Note，位置由调用方排列
+    pub notes: Vec<NoteVo>,
+}
+
+/// 解析DBML
+/// 支持Project、Table（列设置、indexes、Note）、Ref、TableGroup和独立的Note，Enum等其余块忽略
+pub fn parse_dbml(dbml: &str) -> Result<DbmlSchema, DrawDBError> {
+    let mut parser = Parser { tokens: tokenize(dbml)?, pos: 0 };
+    let mut state = ParseState::default();
+    loop {
+        parser.skip_newlines();
+        let Some(token) = parser.next() else {
+            break;
+        };
+        if token.is_keyword("Table") {
+            let mut table = parser.table()?;
+            state.refs.append(&mut table.refs);
+            state.tables.push(table);
+        } else if token.is_keyword("Ref") {
+            state.refs.extend(parser.reference()?);
+        } else if token.is_keyword("TableGroup") {
+            state.groups.push(parser.table_group()?);
+        } else if token.is_keyword("Note") {
+            state.notes.push(parser.sticky_note()?);
+        } else if token.is_keyword("Project") {
+            state.database = parser.project()?;
+        } else {
+            parser.skip_block()?;
+        }
+    }
+    if state.tables.is_empty() {
+        return Err(DrawDBError::ValidationError("未解析到Table".to_string()));
+    }
+    Ok(state.build())
+}
+
+/// 词法分析，支持//和/* */注释
+fn tokenize(dbml: &str) -> Result<Vec<Token>, DrawDBError> {
+    let chars = dbml.chars().collect::<Vec<char>>();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        if c == '\n' {
+            tokens.push(Token::Newline);
+            i += 1;
+        } else if c.is_whitespace() {
+            i += 1;
+        } else if c == '/' && next == Some('/') {
+            i = (i..chars.len()).find(|j| chars[*j] == '\n').unwrap_or(chars.len());
+        } else if c == '/' && next == Some('*') {
+            let end = (i + 2..chars.len().saturating_sub(1))
+            .find(|j| chars[*j] == '*' && chars[*j + 1] == '/')
+            .ok_or_else(|| DrawDBError::ValidationError("注释未闭合".to_string()))?;
+            i = end + 2;
+        } else if c == '\'' && chars.get(i + 1..i + 3) == Some(&['\'', '\''][..]) {
+            let (value, end) = read_quoted(&chars, i + 3, "'''")?;
+            tokens.push(Token::Str(trim_multiline(&value)));
+            i = end;
+        } else if c == '\'' || c == '"' || c == '`' {
+            let (value, end) = read_quoted(&chars, i + 1, &c.to_string())?;
+            tokens.push(match c {
+                '\'' => Token::Str(value),
+                '"' => Token::Quoted(value),
+                _ => Token::Expr(value),
+            });
+            i = end;
+        } else if c.is_alphanumeric() || c == '_' {
+            let end = (i + 1..chars.len())
+            .find(|j| !(chars[*j].is_alphanumeric() || chars[*j] == '_'))
+            .unwrap_or(chars.len());
+            tokens.push(Token::Word(chars[i..end].iter().collect()));
+            i = end;
+        } else {
+            tokens.push(Token::Symbol(c));
+            i += 1;
+        }
+    }
+    Ok(tokens)
+}
+
+/// 读取到结束引号为止，反斜杠转义下一个字符，返回内容和结束引号之后的位置
+fn read_quoted(chars: &[char], start: usize, close: &str) -> Result<(String, usize), DrawDBError> {
+    let close = close.chars().collect::<Vec<char>>();
+    let mut value = String::new();
+    let mut i = start;
+    while i < chars.len() {
+        if chars[i] == '\\' && i + 1 < chars.len() {
+            value.push(chars[i + 1]);
+            i += 2;
+        } else if chars[i..].starts_with(&close) {
+            return Ok((value, i + close.len()));
+        } else {
+            value.push(chars[i]);
+            i += 1;
+        }
+    }
+    Err(DrawDBError::ValidationError(format!("引号未闭合: {}", close.iter().collect::<String>())))
+}
+
+/// 三引号字符串去掉首尾空行和公共缩进
+fn trim_multiline(value: &str) -> String {
+    let lines = value.lines().collect::<Vec<&str>>();
+    let indent = lines.iter()
+    .filter(|line| !line.trim().is_empty())
+    .map(|line| line.len() - line.trim_start().len())
+    .min()
+    .unwrap_or_default();
+    lines.iter()
+    .map(|line| line.get(indent..).unwrap_or(line.trim_start()))
+    .collect::<Vec<&str>>()
+    .join("\n")
+    .trim_matches('\n')
+    .to_string()
+}
+
+/// 关系端点：表名以及字段名
+type Endpoint = (String, Vec<String>);
+
+/// 设置项，如pk、not null、default: 1
+struct Setting {
+    key: String,
+    value: Vec<Token>,
+}
+
+impl Setting {
+    /// 只在相邻的两个单词之间加空格，如no action、-1、1.5
+    fn value_text(&self) -> Option<String> {
+        let mut text = String::new();
+        for (index, token) in self.value.iter().enumerate() {
+            if index > 0 && matches!((&self.value[index - 1], token), (Token::Word(_), Token::Word(_))) {
+                text.push(' ');
+            }
+            text.push_str(&token.text());
+        }
+        (!self.value.is_empty()).then_some(text)
+    }
+}
+
+struct ParsedTable {
+    name: String,
+    alias: Option<String>,
+    comment: Option<String>,
+    fields: Vec<FieldVo>,
+    indices: Vec<(Option<String>, bool, Vec<String>)>,
+    // 列上的内联ref：ref: > table.column
+    refs: Vec<ParsedRef>,
+}
+
+struct ParsedRef {
+    name: Option<String>,
+    start: Endpoint,
+    end: Endpoint,
+    cardinality: &'static str,
+    delete: Option<String>,
+    update: Option<String>,
+}
+
+#[derive(Default)]
+struct ParseState {
+    database: Option<String>,
+    tables: Vec<ParsedTable>,
+    refs: Vec<ParsedRef>,
+    groups: Vec<(String, Vec<String>)>,
+    notes: Vec<NoteVo>,
+}
+
+impl ParseState {
+    fn build(self) -> DbmlSchema {
+        // 表名和别名都可以引用表
+        let mut table_index = HashMap::new();
+        for (index, table) in self.tables.iter().enumerate() {
+            table_index.insert(table.name.to_lowercase(), index);
+            if let Some(alias) = &table.alias {
+                table_index.insert(alias.to_lowercase(), index);
+            }
+        }
+        let tables = self.tables.iter().enumerate().map(|(index, table)| {
+            let fields = table.fields.iter().enumerate()
+            .map(|(field_index, field)| FieldVo { id: field_index.to_string(), ..field.clone() })
+            .collect::<Vec<FieldVo>>();
+            let indices = table.indices.iter().enumerate().filter_map(|(indice_index, (name, unique, columns))| {
+                let field_ids = columns.iter()
+                .filter_map(|column| field_id_by_name(&fields, column))
+                .collect::<Vec<String>>();
+                (!field_ids.is_empty()).then(|| IndiceVo {
+                    id: indice_index.to_string(),
+                    name: Some(name.clone().unwrap_or_else(|| format!("{}_index_{}", table.name, indice_index))),
+                    unique: Some(*unique),
+                    table_id: None,
+                    fields: Some(field_ids),
+                })
+            }).collect();
+            TableVo {
+                id: index.to_string(),
+                color: None,
+                comment: table.comment.clone(),
+                locked: Some(false),
+                name: Some(table.name.clone()),
+                x: None,
+                y: None,
+                fields: Some(fields),
+                indices: Some(indices),
+                diagram_id: String::new(),
+            }
+        }).collect::<Vec<TableVo>>();
+
+        let mut references = Vec::new();
+        for reference in self.refs.iter() {
+            let (Some(&start), Some(&end)) = (
+                table_index.get(&reference.start.0.to_lowercase()),
+                table_index.get(&reference.end.0.to_lowercase())
+            ) else {
+                continue;
+            };
+            let start_fields = tables[start].fields.clone().unwrap_or_default();
+            let end_fields = tables[end].fields.clone().unwrap_or_default();
+            for (start_column, end_column) in reference.start.1.iter().zip(reference.end.1.iter()) {
+                let (Some(start_field), Some(end_field)) = (
+                    field_id_by_name(&start_fields, start_column),
+                    field_id_by_name(&end_fields, end_column)
+                ) else {
+                    continue;
+                };
+                references.push(ReferenceVo {
+                    id: references.len().to_string(),
+                    name: Some(reference.name.clone().unwrap_or_else(|| {
+                        format!("fk_{}_{}_{}", self.tables[start].name, start_column, self.tables[end].name)
+                    })),
+                    start_table_id: Some(start.to_string()),
+                    end_table_id: Some(end.to_string()),
+                    cardinality: Some(reference.cardinality.to_string()),
+                    delete_constraint: Some(constraint_name(reference.delete.as_deref())),
+                    end_field_id: Some(end_field),
+                    start_field_id: Some(start_field),
+                    update_constraint: Some(constraint_name(reference.update.as_deref())),
+                });
+            }
+        }
+        let groups = self.groups.into_iter().map(|(name, members)| {
+            let ids = members.iter()
+            .filter_map(|member| table_index.get(&member.to_lowercase()))
+            .map(|index| index.to_string())
+            .collect();
+            (name, ids)
+        }).collect();
+        DbmlSchema { database: self.database, tables, references, groups, notes: self.notes }
+    }
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn is_symbol(&self, symbol: char) -> bool {
+        self.peek().is_some_and(|token| token.is_symbol(symbol))
+    }
+
+    fn eat_symbol(&mut self, symbol: char) -> bool {
+        let matched = self.is_symbol(symbol);
+        if matched {
+            self.pos += 1;
+        }
+        matched
+    }
+
+    fn expect_symbol(&mut self, symbol: char) -> Result<(), DrawDBError> {
+        if self.eat_symbol(symbol) {
+            Ok(())
+        } else {
+            Err(DrawDBError::ValidationError(format!("缺少{}，实际为{:?}", symbol, self.peek())))
+        }
+    }
+
+    fn skip_newlines(&mut self) {
+        while self.peek() == Some(&Token::Newline) {
+            self.pos += 1;
+        }
+    }
+
+    /// 标识符，带模式名的标识符只保留最后一段
+    fn ident(&mut self) -> Result<String, DrawDBError> {
+        let mut name = match self.next() {
+            Some(Token::Word(name) | Token::Quoted(name)) => name,
+            token => return Err(DrawDBError::ValidationError(format!("缺少标识符: {:?}", token))),
+        };
+        while self.is_symbol('.') && matches!(self.tokens.get(self.pos + 1), Some(Token::Word(_) | Token::Quoted(_))) {
+            self.pos += 1;
+            name = self.ident()?;
+        }
+        Ok(name)
+    }
+
+    /// 跳过未支持的块：到匹配的右花括号或者行尾
+    fn skip_block(&mut self) -> Result<(), DrawDBError> {
+        while let Some(token) = self.peek() {
+            match token {
+                Token::Newline => return Ok(()),
+                Token::Symbol('{') => {
+                    self.block_body()?;
+                    return Ok(());
+                }
+                _ => self.pos += 1,
+            }
+        }
+        Ok(())
+    }
+
+    /// 花括号内的词法单元，不包含外层花括号
+    fn block_body(&mut self) -> Result<Vec<Token>, DrawDBError> {
+        self.expect_symbol('{')?;
+        let start = self.pos;
+        let mut depth = 1;
+        while let Some(token) = self.next() {
+            match token {
+                Token::Symbol('{') => depth += 1,
+                Token::Symbol('}') => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Ok(self.tokens[start..self.pos - 1].to_vec());
+                    }
+                }
+                _ => {}
+            }
+        }
+        Err(DrawDBError::ValidationError("花括号未闭合".to_string()))
+    }
+
+    /// [设置1, 设置2: 值]
+    fn settings(&mut self) -> Result<Vec<Setting>, DrawDBError> {
+        if !self.eat_symbol('[') {
+            return Ok(Vec::new());
+        }
+        let mut settings: Vec<Vec<Token>> = Vec::new();
+        let mut current = Vec::new();
+        let mut depth = 0;
+        loop {
+            let token = self.next().ok_or_else(|| DrawDBError::ValidationError("方括号未闭合".to_string()))?;
+            match token {
+                Token::Symbol(']') if depth == 0 => break,
+                Token::Symbol(',') if depth == 0 => settings.push(std::mem::take(&mut current)),
+                Token::Newline => {}
+                token => {
+                    if token.is_symbol('(') {
+                        depth += 1;
+                    } else if token.is_symbol(')') {
+                        depth -= 1;
+                    }
+                    current.push(token);
+                }
+            }
+        }
+        settings.push(current);
+        Ok(settings.into_iter().filter(|tokens| !tokens.is_empty()).map(|tokens| {
+            match tokens.iter().position(|token| token.is_symbol(':')) {
+                Some(colon) => Setting {
+                    key: tokens[..colon].iter().map(Token::text).collect::<Vec<String>>().join(" ").to_lowercase(),
+                    value: tokens[colon + 1..].to_vec(),
+                },
+                None => Setting {
+                    key: tokens.iter().map(Token::text).collect::<Vec<String>>().join(" ").to_lowercase(),
+                    value: Vec::new(),
+                },
+            }
+        }).collect())
+    }
+
+    /// Table name [as alias] [settings] { ... }
+    fn table(&mut self) -> Result<ParsedTable, DrawDBError> {
+        let name = self.ident()?;
+        let alias = if self.peek().is_some_and(|token| token.is_keyword("as")) {
+            self.pos += 1;
+            Some(self.ident()?)
+        } else {
+            None
+        };
+        let mut table = ParsedTable { name, alias, comment: None, fields: Vec::new(), indices: Vec::new(), refs: Vec::new() };
+        for setting in self.settings()? {
+            if setting.key == "note" {
+                table.comment = setting.value_text();
+            }
+        }
+        self.skip_newlines();
+        self.expect_symbol('{')?;
+        loop {
+            self.skip_newlines();
+            match self.peek() {
+                None => return Err(DrawDBError::ValidationError(format!("表{}缺少}}", table.name))),
+                Some(Token::Symbol('}')) => {
+                    self.pos += 1;
+                    break;
+                }
+                Some(token) if token.is_keyword("indexes") && self.tokens.get(self.pos + 1).is_some_and(|t| t.is_symbol('{')) => {
+                    self.pos += 1;
+                    self.indexes(&mut table)?;
+                }
+                Some(token) if token.is_keyword("Note") && self.tokens.get(self.pos + 1).is_some_and(|t| t.is_symbol(':') || t.is_symbol('{')) => {
+                    self.pos += 1;
+                    table.comment = Some(self.note_value()?);
+                }
+                Some(_) => {
+                    let (field, inline_ref) = self.column()?;
+                    if let Some((operator, end)) = inline_ref {
+                        table.refs.push(ParsedRef {
+                            name: None,
+                            start: (table.name.clone(), vec![field.name.clone().unwrap_or_default()]),
+                            end,
+                            cardinality: cardinality(operator),
+                            delete: None,
+                            update: None,
+                        });
+                    }
+                    table.fields.push(field);
+                }
+            }
+        }
+        Ok(table)
+    }
+
+    /// Note: '...' 或 Note { '...' }
+    fn note_value(&mut self) -> Result<String, DrawDBError> {
+        if self.eat_symbol(':') {
+            return Ok(self.next().map(|token| token.text()).unwrap_or_default());
+        }
+        let body = self.block_body()?;
+        Ok(body.iter().find_map(|token| match token {
+            Token::Str(value) => Some(value.clone()),
+            _ => None,
+        }).unwrap_or_default())
+    }
+
+    /// 列：name type [settings]，返回字段和内联的ref
+    fn column(&mut self) -> Result<(FieldVo, Option<(char, Endpoint)>), DrawDBError> {
+        let name = self.ident()?;
+        let mut r#type = self.ident()?.to_uppercase();
+        let mut size = None;
+        if self.eat_symbol('(') {
+            let mut args = Vec::new();
+            while let Some(token) = self.next() {
+                if token.is_symbol(')') {
+                    break;
+                }
+                args.push(token);
+            }
+            size = args.first().and_then(|token| token.text().parse().ok());
+        }
+        if self.is_symbol('[') && self.tokens.get(self.pos + 1).is_some_and(|token| token.is_symbol(']')) {
+            self.pos += 2;
+            r#type.push_str("[]");
+        }
+        let mut field = FieldVo {
+            id: String::new(),
+            table_id: None,
+            check: None,
+            comment: None,
+            default: None,
+            increment: Some(false),
+            not_null: Some(false),
+            primary: Some(false),
+            size,
+            r#type: Some(r#type),
+            unique: Some(false),
+            name: Some(name),
+            values: None,
+        };
+        let mut inline_ref = None;
+        for setting in self.settings()? {
+            match setting.key.as_str() {
+                "pk" | "primary key" => field.primary = Some(true),
+                "increment" => field.increment = Some(true),
+                "not null" => field.not_null = Some(true),
+                "unique" => field.unique = Some(true),
+                "default" => field.default = setting.value_text(),
+                "note" => field.comment = setting.value_text(),
+                "check" => field.check = setting.value_text(),
+                "ref" => {
+                    let mut parser = Parser { tokens: setting.value.clone(), pos: 0 };
+                    let operator = parser.operator()?;
+                    inline_ref = operator.map(|operator| parser.endpoint().map(|end| (operator, end))).transpose()?;
+                }
+                _ => {}
+            }
+        }
+        Ok((field, inline_ref))
+    }
+
+    /// indexes { (a, b) [settings] }
+    fn indexes(&mut self, table: &mut ParsedTable) -> Result<(), DrawDBError> {
+        let body = self.block_body()?;
+        let mut parser = Parser { tokens: body, pos: 0 };
+        loop {
+            parser.skip_newlines();
+            let Some(token) = parser.peek().cloned() else {
+                break;
+            };
+            let columns = match token {
+                Token::Symbol('(') => {
+                    parser.pos += 1;
+                    let mut columns = Vec::new();
+                    while let Some(token) = parser.next() {
+                        match token {
+                            Token::Symbol(')') => break,
+                            Token::Word(column) | Token::Quoted(column) => columns.push(column),
+                            _ => {}
+                        }
+                    }
+                    columns
+                }
+                Token::Word(column) | Token::Quoted(column) => {
+                    parser.pos += 1;
+                    vec![column]
+                }
+                // 表达式索引无法对应到字段
+                _ => {
+                    parser.pos += 1;
+                    Vec::new()
+                }
+            };
+            let settings = parser.settings()?;
+            if settings.iter().any(|setting| setting.key == "pk") {
+                for column in columns.iter() {
+                    if let Some(field) = table.fields.iter_mut().find(|field| field.name.as_ref() == Some(column)) {
+                        field.primary = Some(true);
+                    }
+                }
+                continue;
+            }
+            if columns.is_empty() {
+                continue;
+            }
+            let name = settings.iter().find(|setting| setting.key == "name").and_then(Setting::value_text);
+            let unique = settings.iter().any(|setting| setting.key == "unique");
+            table.indices.push((name, unique, columns));
+        }
+        Ok(())
+    }
+
+    /// 关系运算符：> 多对一，< 一对多，- 一对一，<> 多对多不支持
+    fn operator(&mut self) -> Result<Option<char>, DrawDBError> {
+        match self.next() {
+            Some(Token::Symbol('<')) if self.eat_symbol('>') => Ok(None),
+            Some(Token::Symbol(operator @ ('<' | '>' | '-'))) => Ok(Some(operator)),
+            token => Err(DrawDBError::ValidationError(format!("无效的关系运算符: {:?}", token))),
+        }
+    }
+
+    /// table.column 或 schema.table.(a, b)
+    fn endpoint(&mut self) -> Result<Endpoint, DrawDBError> {
+        let mut parts = Vec::new();
+        loop {
+            if self.eat_symbol('(') {
+                let mut columns = Vec::new();
+                while let Some(token) = self.next() {
+                    match token {
+                        Token::Symbol(')') => break,
+                        Token::Word(column) | Token::Quoted(column) => columns.push(column),
+                        _ => {}
+                    }
+                }
+                parts.push(columns);
+            } else {
+                match self.next() {
+                    Some(Token::Word(part) | Token::Quoted(part)) => parts.push(vec![part]),
+                    token => return Err(DrawDBError::ValidationError(format!("无效的关系端点: {:?}", token))),
+                }
+            }
+            if !self.eat_symbol('.') {
+                break;
+            }
+        }
+        match parts.as_slice() {
+            [.., table, columns] if table.len() == 1 => Ok((table[0].clone(), columns.clone())),
+            _ => Err(DrawDBError::ValidationError("关系端点缺少表名".to_string())),
+        }
+    }
+
+    /// Ref [name]: a.b > c.d [settings] 或 Ref [name] { a.b > c.d [settings] }
+    fn reference(&mut self) -> Result<Option<ParsedRef>, DrawDBError> {
+        let name = if self.is_symbol(':') || self.is_symbol('{') { None } else { Some(self.ident()?) };
+        let tokens = if self.eat_symbol(':') {
+            let start = self.pos;
+            while self.peek().is_some_and(|token| *token != Token::Newline) {
+                self.pos += 1;
+            }
+            self.tokens[start..self.pos].to_vec()
+        } else {
+            self.skip_newlines();
+            self.block_body()?
+        };
+        let mut parser = Parser { tokens, pos: 0 };
+        parser.skip_newlines();
+        let start = parser.endpoint()?;
+        let Some(operator) = parser.operator()? else {
+            return Ok(None);
+        };
+        let end = parser.endpoint()?;
+        let settings = parser.settings()?;
+        let action = |key: &str| settings.iter().find(|setting| setting.key == key).and_then(Setting::value_text);
+        Ok(Some(ParsedRef {
+            name,
+            start,
+            end,
+            cardinality: cardinality(operator),
+            delete: action("delete"),
+            update: action("update"),
+        }))
+    }
+
+    /// TableGroup name { table1 table2 }
+    fn table_group(&mut self) -> Result<(String, Vec<String>), DrawDBError> {
+        let name = self.ident()?;
+        self.settings()?;
+        self.skip_newlines();
+        let body = self.block_body()?;
+        let mut parser = Parser { tokens: body, pos: 0 };
+        let mut members = Vec::new();
+        loop {
+            parser.skip_newlines();
+            match parser.peek() {
+                None => break,
+                Some(token) if token.is_keyword("Note") => {
+                    parser.pos += 1;
+                    parser.note_value()?;
+                }
+                Some(Token::Word(_) | Token::Quoted(_)) => members.push(parser.ident()?),
+                Some(_) => parser.pos += 1,
+            }
+        }
+        Ok((name, members))
+    }
+
+    /// Note name { '...' }
+    fn sticky_note(&mut self) -> Result<NoteVo, DrawDBError> {
+        let title = self.ident()?;
+        self.settings()?;
+        self.skip_newlines();
+        let content = self.note_value()?;
+        Ok(NoteVo {
+            id: String::new(),
+            content: Some(content),
+            color: None,
+            title: Some(title),
+            height: None,
+            x: None,
+            y: None,
+        })
+    }
+
+    /// Project name { database_type: '...' }，返回数据库类型
+    fn project(&mut self) -> Result<Option<String>, DrawDBError> {
+        if !self.is_symbol('{') {
+            self.ident()?;
+        }
+        self.skip_newlines();
+        let body = self.block_body()?;
+        Ok(body.windows(3).find_map(|window| match window {
+            [key, colon, Token::Str(value)] if key.is_keyword("database_type") && colon.is_symbol(':') => Some(value.clone()),
+            _ => None,
+        }))
+    }
+}
+
+fn cardinality(operator: char) -> &'static str {
+    match operator {
+        '-' => "one_to_one",
+        '<' => "one_to_many",
+        _ => "many_to_one",
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const DBML: &str = r#"
+        Project shop {
+          database_type: 'PostgreSQL'
+        }
+
+        // 用户
+        Table users as U {
+          id integer [pk, increment]
+          email "character varying"(255) [not null, unique, note: 'login email']
+          score decimal(10,2) [default: -1.5]
+          created_at timestamp [default: `now()`]
+          status varchar [default: 'active']
+
+          Note: '''
+            registered users
+          '''
+        }
+
+        Table orders {
+          id integer
+          user_id integer [ref: > U.id]
+          code varchar
+
+          indexes {
+            id [pk]
+            (user_id, code) [name: 'idx_orders_user', unique]
+            `lower(code)`
+          }
+        }
+
+        Ref fk_orders_code: orders.code - users.email [delete: cascade, update: set null]
+        Ref: users.id <> orders.id
+
+        Enum status {
+          active
+          disabled
+        }
+
+        TableGroup sales {
+          users
+          orders
+        }
+
+        Note release {
+          'first release'
+        }
+    "#;
+
+    fn field<'a>(table: &'a TableVo, name: &str) -> &'a FieldVo {
+        table.fields.as_ref().unwrap().iter().find(|field| field.name.as_deref() == Some(name)).unwrap()
+    }
+
+    #[test]
+    fn test_parse_dbml(){
+        let schema = parse_dbml(DBML).unwrap();
+        assert_eq!(schema.database, Some("PostgreSQL".to_string()));
+        assert_eq!(schema.tables.len(), 2);
+        let users = &schema.tables[0];
+        assert_eq!(users.comment, Some("registered users".to_string()));
+        let id = field(users, "id");
+        assert_eq!((id.primary, id.increment), (Some(true), Some(true)));
+        let email = field(users, "email");
+        assert_eq!((email.r#type.as_deref(), email.size), (Some("CHARACTER VARYING"), Some(255)));
+        assert_eq!((email.not_null, email.unique), (Some(true), Some(true)));
+        assert_eq!(email.comment, Some("login email".to_string()));
+        assert_eq!(field(users, "score").default, Some("-1.5".to_string()));
+        assert_eq!(field(users, "created_at").default, Some("now()".to_string()));
+        assert_eq!(field(users, "status").default, Some("active".to_string()));
+
+        let orders = &schema.tables[1];
+        assert_eq!(field(orders, "id").primary, Some(true));
+        let indices = orders.indices.as_ref().unwrap();
+        assert_eq!(indices.len(), 1);
+        assert_eq!(indices[0].name, Some("idx_orders_user".to_string()));
+        assert_eq!(indices[0].unique, Some(true));
+        assert_eq!(indices[0].fields, Some(vec!["1".to_string(), "2".to_string()]));
+
+        // 多对多的关系不支持，被忽略
+        assert_eq!(schema.references.len(), 2);
+        let inline = &schema.references[0];
+        assert_eq!((inline.start_table_id.as_deref(), inline.end_table_id.as_deref()), (Some("1"), Some("0")));
+        assert_eq!(inline.cardinality, Some("many_to_one".to_string()));
+        let named = &schema.references[1];
+        assert_eq!(named.name, Some("fk_orders_code".to_string()));
+        assert_eq!(named.cardinality, Some("one_to_one".to_string()));
+        assert_eq!(named.delete_constraint, Some("Cascade".to_string()));
+        assert_eq!(named.update_constraint, Some("Set null".to_string()));
+
+        assert_eq!(schema.groups, vec![("sales".to_string(), vec!["0".to_string(), "1".to_string()])]);
+        assert_eq!(schema.notes[0].title, Some("release".to_string()));
+        assert_eq!(schema.notes[0].content, Some("first release".to_string()));
+        assert!(parse_dbml("Enum e { a }").is_err());
+    }
+}
@@ -0,0 +1,75 @@
+use crate::entity::vo::{AreaVo, NoteVo, TableVo};
+
+// 与前端画布上表的尺寸保持一致
+const TABLE_WIDTH: usize = 220;
+const TABLE_HEADER_HEIGHT: usize = 50;
+const TABLE_FIELD_HEIGHT: usize = 36;
+const TABLE_COLOR_STRIP_HEIGHT: usize = 7;
+const GAP_X: usize = 54;
+const GAP_Y: usize = 40;
+// 与前端新建主题区域、注释时的默认值保持一致
+const AREA_PADDING: usize = 20;
+const AREA_COLOR: &str = "#175e7a";
+const NOTE_HEIGHT: usize = 88;
+const NOTE_COLOR: &str = "#fcf7ac";
+
+/// 将导入的表按网格排列
+/// 每行的表数量为表总数的平方根向上取整，行高取该行最高的表
+pub fn arrange_tables(tables: &mut [TableVo]) {
+    if tables.is_empty() {
+        return;
+    }
+    let columns = (tables.len() as f64).sqrt().ceil() as usize;
+    let mut y = GAP_Y;
+    for row in tables.chunks_mut(columns) {
+        let mut row_height = 0;
+        for (index, table) in row.iter_mut().enumerate() {
+            let x = GAP_X + index * (TABLE_WIDTH + GAP_X);
+            table.x = Some(x.to_string());
+            table.y = Some(y.to_string());
+            let (_, top, _, bottom) = table_bounds(table);
+            row_height = row_height.max(bottom - top);
+        }
+        y += row_height + GAP_Y;
+    }
+}
+
+/// 按表分组生成主题区域，区域包住组内所有已排列的表
+/// tables中的表需要先经过arrange_tables，组内没有表时不生成区域
+pub fn arrange_areas(groups: &[(String, Vec<String>)], tables: &[TableVo]) -> Vec<AreaVo> {
+    groups.iter().enumerate().filter_map(|(index, (name, table_ids))| {
+        let bounds = tables.iter()
+        .filter(|table| table_ids.contains(&table.id))
+        .map(table_bounds)
+        .reduce(|(x1, y1, x2, y2), (a1, b1, a2, b2)| (x1.min(a1), y1.min(b1), x2.max(a2), y2.max(b2)))?;
+        let (x, y) = (bounds.0.saturating_sub(AREA_PADDING), bounds.1.saturating_sub(AREA_PADDING));
+        Some(AreaVo {
+            id: index.to_string(),
+            color: Some(AREA_COLOR.to_string()),
+            height: Some((bounds.3 + AREA_PADDING - y).to_string()),
+            name: Some(name.clone()),
+            width: Some((bounds.2 + AREA_PADDING - x).to_string()),
+            x: Some(x.to_string()),
+            y: Some(y.to_string()),
+        })
+    }).collect()
+}
+
+/// 注释排列在所有表的右侧，自上而下依次放置
+pub fn arrange_notes(notes: &mut [NoteVo], tables: &[TableVo]) {
+    let x = tables.iter().map(|table| table_bounds(table).2).max().unwrap_or_default() + GAP_X;
+    for (index, note) in notes.iter_mut().enumerate() {
+        note.x = Some(x.to_string());
+        note.y = Some((GAP_Y + index * (NOTE_HEIGHT + GAP_Y)).to_string());
+        note.height = note.height.clone().or_else(|| Some(NOTE_HEIGHT.to_string()));
+        note.color = note.color.clone().or_else(|| Some(NOTE_COLOR.to_string()));
+    }
+}
+
+/// 表在画布上的范围：左、上、右、下
+fn table_bounds(table: &TableVo) -> (usize, usize, usize, usize) {
+    let position = |value: &Option<String>| value.as_deref().and_then(|value| value.parse::<f64>().ok()).unwrap_or_default() as usize;
+    let (x, y) = (position(&table.x), position(&table.y));
+    let fields = table.fields.as_ref().map(Vec::len).unwrap_or_default();
+    (x, y, x + TABLE_WIDTH, y + fields * TABLE_FIELD_HEIGHT + TABLE_HEADER_HEIGHT + TABLE_COLOR_STRIP_HEIGHT)
+}
@@ -0,0 +1,504 @@
+mod dbml;
+mod layout;
+mod sql;
+mod sqlite;
+pub use dbml::*;
+pub use layout::*;
+pub use sql::*;
+pub use sqlite::*;
+use actix_web::{post, web};
+use sea_orm::{ActiveModelTrait, ActiveValue, DatabaseConnection, DatabaseTransaction, TransactionTrait};
+use serde::Deserialize;
+use crate::common::{CommonResponse, ResponseCode, ResponseMessage};
+use crate::deploy::resolve_target;
+use crate::diagrams::query_diagram_detail;
+use crate::entity::diagram;
+use crate::areas::add_area;
+use crate::entity::vo::{AreaVo, DiagramDocumentVo, DiagramSaveVo, DocumentTableVo, NoteVo, ReferenceVo, TableVo};
+use crate::notes::add_note;
+use crate::error::DrawDBError;
+use crate::export::Dialect;
+use crate::init::DeployConfig;
+use crate::next_id;
+use crate::tables::batch_add_table_with_references;
+use crate::todos::add_task;
+
+/// 导入模块，挂载在/diagrams下
+pub fn import_routes(config: &mut web::ServiceConfig) {
+    config.service(import_sql);
+    config.service(import_dbml);
+    config.service(import_json);
+    config.service(import_sqlite);
+}
+
+/// 导入参数
+#[derive(Debug, Deserialize)]
+pub struct ImportQuery {
+    // 数据库方言，保存为新图表的数据库类型
+    pub dialect: Option<String>,
+    // 新图表的名称
+    pub name: Option<String>,
+}
+
+/// SQLite数据库文件导入参数
+#[derive(Debug, Deserialize)]
+pub struct SqliteImportQuery {
+    // 服务器上数据库文件的路径，必须在config.toml的部署白名单中
+    pub path: String,
+    // 新图表的名称，默认为文件名
+    pub name: Option<String>,
+}
+
+/// 新建导入用的图表，返回图表id
+pub async fn insert_diagram(
+    tx: &DatabaseTransaction,
+    name: Option<String>,
+    database: Option<String>
+) -> Result<String, DrawDBError> {
+    let id = next_id();
+    diagram::ActiveModel::from(diagram::Model {
+        id: id.clone(),
+        database,
+        zoom: None,
+        name: Some(name.unwrap_or_else(|| "Untitled Diagram".to_string())),
+        pan: None,
+        last_modified: None,
+        types: None,
+        enums: None,
+    }).insert(tx).await?;
+    Ok(id)
+}
+
+/// 保存导入的表、关联关系、主题区域和注释，返回保存后的图表以及id映射
+pub async fn save_import(
+    tx: &DatabaseTransaction,
+    diagram_id: String,
+    tables: Vec<TableVo>,
+    references: Vec<ReferenceVo>,
+    areas: Vec<AreaVo>,
+    notes: Vec<NoteVo>
+) -> Result<DiagramSaveVo, DrawDBError> {
+    let id_mapping = batch_add_table_with_references(tx, &diagram_id, tables, references).await?;
+    for area in areas {
+        add_area(tx, diagram_id.clone(), AreaVo { id: next_id(), ..area }).await?;
+    }
+    for note in notes {
+        add_note(tx, diagram_id.clone(), NoteVo { id: next_id(), ..note }).await?;
+    }
+    let diagram = query_diagram_detail(tx, diagram_id).await?
+    .ok_or_else(|| DrawDBError::OtherError("导入的图表不存在".to_string()))?;
+    Ok(DiagramSaveVo { diagram, id_mapping })
+}
+
+/// 导入SQL DDL
+/// 请求体为SQL脚本，解析出的表按网格排列后保存为新图表，返回新图表以及id映射
+#[post("/import/sql")]
+async fn import_sql(
+    db: web::Data<DatabaseConnection>,
+    query: web::Query<ImportQuery>,
+    body: String
+) -> Result<CommonResponse, DrawDBError> {
+    let query = query.into_inner();
+    let dialect = query.dialect.as_deref().map(str::parse::<Dialect>).transpose()?;
+    //1、解析SQL并排列表的位置
+    let (mut tables, references) = parse_sql(&body)?;
+    arrange_tables(&mut tables);
+    //2、在同一个事务中新建图表并保存表和关联关系
+    let tx = db.begin().await?;
+    let diagram_id = insert_diagram(&tx, query.name, dialect.map(|dialect| dialect.database().to_string())).await?;
+    let result = save_import(&tx, diagram_id, tables, references, Vec::new(), Vec::new()).await?;
+    tx.commit().await?;
+    Ok(CommonResponse::new(
+        ResponseCode::Success,
+        ResponseMessage::Success,
+        Some(serde_json::to_value(result).unwrap()),
+    ))
+}
+
+/// 导入DBML
+/// TableGroup转换为包住组内表的主题区域，独立的Note转换为注释
+/// 未指定方言时使用Project中的database_type
+#[post("/import/dbml")]
+async fn import_dbml(
+    db: web::Data<DatabaseConnection>,
+    query: web::Query<ImportQuery>,
+    body: String
+) -> Result<CommonResponse, DrawDBError> {
+    let query = query.into_inner();
+    let DbmlSchema { database, mut tables, references, groups, mut notes } = parse_dbml(&body)?;
+    let dialect = match query.dialect.as_deref() {
+        Some(dialect) => Some(dialect.parse::<Dialect>()?),
+        None => database.and_then(|database| database.parse::<Dialect>().ok()),
+    };
+    //1、排列表、主题区域和注释的位置
+    arrange_tables(&mut tables);
+    let areas = arrange_areas(&groups, &tables);
+    arrange_notes(&mut notes, &tables);
+    //2、在同一个事务中新建图表并保存
+    let tx = db.begin().await?;
+    let diagram_id = insert_diagram(&tx, query.name, dialect.map(|dialect| dialect.database().to_string())).await?;
+    let result = save_import(&tx, diagram_id, tables, references, areas, notes).await?;
+    tx.commit().await?;
+    Ok(CommonResponse::new(
+        ResponseCode::Success,
+        ResponseMessage::Success,
+        Some(serde_json::to_value(result).unwrap()),
+    ))
+}
+
+/// 导入前端编辑器的JSON文档
+/// 保留文档中表、主题区域和注释的位置，同时保存代办事项、自定义类型和枚举
+/// 未指定方言时使用文档中的database，未指定名称时使用文档的title
+#[post("/import/json")]
+async fn import_json(
+    db: web::Data<DatabaseConnection>,
+    query: web::Query<ImportQuery>,
+    document: web::Json<DiagramDocumentVo>
+) -> Result<CommonResponse, DrawDBError> {
+    let query = query.into_inner();
+    let document = document.into_inner();
+    let database = match query.dialect.as_deref() {
+        Some(dialect) => Some(dialect.parse::<Dialect>()?.database().to_string()),
+        None => document.database.clone(),
+    };
+    //1、转换为使用前端id的结构
+    let tables = document.tables.iter().map(DocumentTableVo::convert_to_table_vo).collect();
+    let references = document.relationships.iter().map(|reference| reference.convert_to_reference_vo()).collect();
+    let areas = document.subject_areas.iter().map(|area| area.convert_to_area_vo()).collect();
+    let notes = document.notes.iter().map(|note| note.convert_to_note_vo()).collect();
+    //2、在同一个事务中新建图表，保存自定义类型、枚举和代办事项
+    let tx = db.begin().await?;
+    let diagram_id = insert_diagram(&tx, query.name.or(document.title), database).await?;
+    diagram::ActiveModel {
+        id: ActiveValue::Unchanged(diagram_id.clone()),
+        types: ActiveValue::Set(Some(serde_json::to_string(&document.types).unwrap())),
+        enums: ActiveValue::Set(Some(serde_json::to_string(&document.enums).unwrap())),
+        ..Default::default()
+    }.update(&tx).await?;
+    for task in &document.todo_items {
+        add_task(&tx, &task.convert_to_task_add_vo(diagram_id.clone())).await?;
+    }
+    //3、保存表、关联关系、主题区域和注释
+    let result = save_import(&tx, diagram_id, tables, references, areas, notes).await?;
+    tx.commit().await?;
+    Ok(CommonResponse::new(
+        ResponseCode::Success,
+        ResponseMessage::Success,
+        Some(serde_json::to_value(result).unwrap()),
+    ))
+}
+
+/// 从服务器上的SQLite数据库文件逆向生成图表
+/// 读取表、字段、索引和外键后保存为新图表，返回新图表以及id映射
+/// 与部署共用白名单，白名单之外的路径返回400
+#[post("/import/sqlite")]
+async fn import_sqlite(
+    db: web::Data<DatabaseConnection>,
+    deploy_config: web::Data<DeployConfig>,
+    query: web::Query<SqliteImportQuery>
+) -> Result<CommonResponse, DrawDBError> {
+    let query = query.into_inner();
+    let path = resolve_target(&query.path, &deploy_config.allowed_paths)?;
+    let result = import_sqlite_file(db.get_ref(), &path, query.name).await?;
+    Ok(CommonResponse::new(
+        ResponseCode::Success,
+        ResponseMessage::Success,
+        Some(serde_json::to_value(result).unwrap()),
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use actix_web::{test, App};
+    use sea_orm::{ConnectionTrait, Database};
+
+    use super::*;
+    use crate::entity::vo::{DiagramVo, TableVo};
+    use crate::export::{export_routes, to_dbml, to_sql};
+    use crate::init::init_test_db;
+
+    /// 表结构中用于比较的部分：表名、注释以及字段的名称、类型和约束
+    fn structure(tables: &[TableVo]) -> Vec<(Option<String>, Option<String>, Vec<String>)> {
+        tables.iter().map(|table| {
+            let fields = table.fields.clone().unwrap_or_default().iter().map(|field| format!(
+                "{:?} {:?} {:?} {:?} {:?} {:?}",
+                field.name, field.r#type, field.primary, field.not_null, field.unique, field.comment
+            )).collect();
+            (table.name.clone(), table.comment.clone(), fields)
+        }).collect()
+    }
+
+    /// 后端自身的init.sql导入后再导出，结构保持一致
+    #[actix_web::test]
+    async fn test_import_init_sql() {
+        let db = init_test_db().await;
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(db))
+                .configure(import_routes)
+        ).await;
+        let req = test::TestRequest::post()
+            .uri("/import/sql?dialect=sqlite&name=drawdb")
+            .set_payload(include_str!("../../init.sql"))
+            .to_request();
+        let resp: CommonResponse = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(resp.code, 200);
+        let saved: DiagramSaveVo = serde_json::from_value(resp.data.unwrap()).unwrap();
+        let diagram: DiagramVo = saved.diagram;
+        assert_eq!(diagram.database, Some("sqlite".to_string()));
+        assert_eq!(diagram.name, Some("drawdb".to_string()));
+        let tables = diagram.tables.clone().unwrap();
+        assert_eq!(tables.len(), 13);
+        assert_eq!(saved.id_mapping.tables.len(), 13);
+        let task = tables.iter().find(|table| table.name.as_deref() == Some("task")).unwrap();
+        assert_eq!(task.comment, Some("代办事项表".to_string()));
+        let id = task.fields.as_ref().unwrap().iter().find(|field| field.name.as_deref() == Some("id")).unwrap();
+        assert_eq!((id.primary, id.not_null, id.unique), (Some(true), Some(true), Some(true)));
+        assert_eq!(id.comment, Some("主键".to_string()));
+        let diagram_link = tables.iter().find(|table| table.name.as_deref() == Some("diagram_link")).unwrap();
+        let indices = diagram_link.indices.clone().unwrap();
+        assert_eq!(indices[0].name, Some("diagram_link_index_0".to_string()));
+        assert_eq!(indices[0].fields.as_ref().unwrap().len(), 3);
+        // 表按网格排列
+        assert!(tables.iter().all(|table| table.x.is_some() && table.y.is_some()));
+
+        // 导出的DDL可以执行，并且再次解析后结构不变
+        let sql = to_sql(&diagram, Dialect::Sqlite);
+        let target = Database::connect("sqlite::memory:").await.unwrap();
+        target.execute_unprepared(&sql).await.unwrap();
+        let (reparsed, _) = parse_sql(&sql).unwrap();
+        let mut expected = structure(&tables);
+        let mut actual = structure(&reparsed);
+        expected.sort();
+        actual.sort();
+        assert_eq!(actual, expected);
+        assert_eq!(reparsed.iter().map(|table| table.indices.as_ref().unwrap().len()).sum::<usize>(), 2);
+
+        let req = test::TestRequest::post()
+            .uri("/import/sql?dialect=sqlite")
+            .set_payload("DROP TABLE task;")
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 400);
+    }
+
+    /// DBML导入后再导出，表、关联关系、分组和注释保持一致
+    #[actix_web::test]
+    async fn test_import_dbml() {
+        let db = init_test_db().await;
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(db))
+                .configure(import_routes)
+        ).await;
+        let dbml = r#"
+            Project shop {
+              database_type: 'PostgreSQL'
+            }
+            Table users {
+              id integer [pk, increment]
+              email varchar(255) [not null, unique, note: 'login email']
+              Note: 'registered users'
+            }
+            Table orders {
+              id integer [pk]
+              user_id integer [not null]
+              indexes {
+                (user_id, id) [name: 'idx_orders_user']
+              }
+            }
+            Table audit_log {
+              id integer [pk]
+            }
+            Ref fk_orders_users: orders.user_id > users.id [delete: cascade]
+            TableGroup sales {
+              users
+              orders
+            }
+            Note release {
+              'first release'
+            }
+        "#;
+        let req = test::TestRequest::post().uri("/import/dbml").set_payload(dbml).to_request();
+        let resp: CommonResponse = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(resp.code, 200);
+        let saved: DiagramSaveVo = serde_json::from_value(resp.data.unwrap()).unwrap();
+        let diagram = saved.diagram;
+        assert_eq!(diagram.database, Some("postgresql".to_string()));
+        assert_eq!(diagram.tables.as_ref().unwrap().len(), 3);
+        assert_eq!(diagram.references.as_ref().unwrap().len(), 1);
+        let areas = diagram.areas.clone().unwrap();
+        assert_eq!(areas.len(), 1);
+        assert_eq!(areas[0].name, Some("sales".to_string()));
+        assert_eq!(diagram.notes.as_ref().unwrap()[0].content, Some("first release".to_string()));
+
+        let exported = to_dbml(&diagram);
+        assert!(exported.contains("database_type: 'PostgreSQL'"));
+        assert!(exported.contains("Ref fk_orders_users: orders.user_id > users.id [delete: cascade, update: no action]"));
+        let reparsed = parse_dbml(&exported).unwrap();
+        let mut expected = structure(diagram.tables.as_ref().unwrap());
+        let mut actual = structure(&reparsed.tables);
+        expected.sort();
+        actual.sort();
+        assert_eq!(actual, expected);
+        assert_eq!(reparsed.references.len(), 1);
+        // 只有users和orders位于主题区域内
+        assert_eq!(reparsed.groups[0].1.len(), 2);
+        assert_eq!(reparsed.notes[0].title, Some("release".to_string()));
+    }
+
+    /// database_design.json导入后再导出，文档结构保持一致
+    #[actix_web::test]
+    async fn test_import_json() {
+        let db = init_test_db().await;
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(db))
+                .configure(import_routes)
+                .configure(export_routes)
+        ).await;
+        let mut document: serde_json::Value = serde_json::from_str(include_str!("../../database_design.json")).unwrap();
+        // SQLite没有ENUM类型，使用MySQL验证枚举值
+        document["database"] = "mysql".into();
+        document["tables"][0]["fields"][1]["type"] = "ENUM".into();
+        document["tables"][0]["fields"][1]["values"] = serde_json::json!(["yes", "no"]);
+        document["types"] = serde_json::json!([{"name": "point", "fields": [{"name": "x", "type": "INTEGER"}], "comment": ""}]);
+        document["enums"] = serde_json::json!([{"name": "status", "values": ["active", "disabled"]}]);
+        document["todoItems"] = serde_json::json!([{"complete": true, "details": "", "title": "review", "priority": 0, "order": 0}]);
+        let req = test::TestRequest::post().uri("/import/json").set_json(&document).to_request();
+        let resp: CommonResponse = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(resp.code, 200);
+        let saved: DiagramSaveVo = serde_json::from_value(resp.data.unwrap()).unwrap();
+        assert_eq!(saved.diagram.name, Some("col_drawdb_database_design".to_string()));
+        assert_eq!(saved.diagram.database, Some("mysql".to_string()));
+        assert_eq!(saved.id_mapping.tables.len(), 11);
+
+        let req = test::TestRequest::get().uri(&format!("/{}/export/json", saved.diagram.id)).to_request();
+        let exported: DiagramDocumentVo = test::call_and_read_body_json(&app, req).await;
+        let original: DiagramDocumentVo = serde_json::from_value(document).unwrap();
+        // 表、字段的id被替换为后端id，其余内容保持不变
+        let table_key = |table: &DocumentTableVo| {
+            let fields = table.fields.iter().map(|field| {
+                let size = field.size.as_i64();
+                (field.name.clone(), field.r#type.clone(), field.not_null, field.primary, field.comment.clone(), size, field.values.clone())
+            }).collect::<Vec<_>>();
+            let indices = table.indices.iter().map(|indice| (indice.name.clone(), indice.fields.clone())).collect::<Vec<_>>();
+            (table.name.clone(), (table.x, table.y), table.comment.clone(), fields, indices)
+        };
+        let mut expected = original.tables.iter().map(table_key).collect::<Vec<_>>();
+        let mut actual = exported.tables.iter().map(table_key).collect::<Vec<_>>();
+        expected.sort_by(|a, b| a.0.cmp(&b.0));
+        actual.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(actual, expected);
+        // 未设置长度时导出为空字符串
+        assert!(exported.tables.iter().flat_map(|table| &table.fields).any(|field| field.size == ""));
+
+        let endpoint_names = |document: &DiagramDocumentVo| {
+            let mut names = document.relationships.iter().map(|reference| {
+                let table = document.tables.iter().find(|table| table.id == reference.start_table_id).unwrap();
+                let field = table.fields.iter().find(|field| field.id == reference.start_field_id).unwrap();
+                (reference.name.clone(), table.name.clone(), field.name.clone(), reference.cardinality.clone())
+            }).collect::<Vec<_>>();
+            names.sort();
+            names
+        };
+        assert_eq!(endpoint_names(&exported), endpoint_names(&original));
+        assert_eq!(exported.subject_areas[0].name, original.subject_areas[0].name);
+        assert_eq!(exported.subject_areas[0].width, original.subject_areas[0].width);
+        assert_eq!(exported.types, original.types);
+        assert_eq!(exported.enums, original.enums);
+        assert_eq!(exported.todo_items, original.todo_items);
+    }
+
+    /// 后端自身由迁移创建的数据库，加上带外键的表，逆向生成图表
+    #[actix_web::test]
+    async fn test_import_sqlite() {
+        //1、在临时目录中创建数据库
+        let dir = std::env::temp_dir().join(format!("drawdb_import_{}", crate::next_id()));
+        std::fs::create_dir(&dir).unwrap();
+        let path = dir.join(format!("drawdb_{}.sqlite", crate::next_id()));
+        let path = path.to_string_lossy().to_string();
+        let source = Database::connect(format!("sqlite://{}?mode=rwc", path)).await.unwrap();
+        crate::migrations::run_migrations(&source).await.unwrap();
+        source.execute_unprepared(r#"
+            CREATE TABLE "users" (
+                "id" INTEGER PRIMARY KEY AUTOINCREMENT,
+                -- 登录邮箱
+                "email" VARCHAR(255) NOT NULL UNIQUE DEFAULT 'nobody',
+                "age" INTEGER CHECK(age > 0)
+            );
+            CREATE TABLE "orders" (
+                "id" INTEGER NOT NULL,
+                "user_id" INTEGER REFERENCES "users" ("id") ON DELETE CASCADE,
+                "price" DECIMAL(10, 2),
+                "total" DECIMAL(10,2),
+                PRIMARY KEY ("id"),
+                UNIQUE ("user_id", "price")
+            );
+        "#).await.unwrap();
+        source.close().await.unwrap();
+
+        //2、导入，白名单之外的路径不允许读取
+        let db = init_test_db().await;
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(db))
+                .app_data(web::Data::new(DeployConfig { allowed_paths: vec![dir.to_string_lossy().to_string()] }))
+                .configure(import_routes)
+        ).await;
+        let req = test::TestRequest::post().uri("/import/sqlite?path=Cargo.toml").to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 400);
+        let req = test::TestRequest::post().uri(&format!("/import/sqlite?path={}", path)).to_request();
+        let resp: CommonResponse = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(resp.code, 200);
+        let saved: DiagramSaveVo = serde_json::from_value(resp.data.unwrap()).unwrap();
+        let diagram = saved.diagram;
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(diagram.database, Some("sqlite".to_string()));
+        assert!(diagram.name.as_deref().unwrap().starts_with("drawdb_"));
+        let tables = diagram.tables.clone().unwrap();
+        // 包括schema_migrations
+        assert_eq!(tables.len(), 16);
+        assert!(tables.iter().all(|table| table.x.is_some() && table.y.is_some()));
+        let table = |name: &str| tables.iter().find(|table| table.name.as_deref() == Some(name)).unwrap();
+        let field = |table: &TableVo, name: &str| table.fields.clone().unwrap().into_iter()
+        .find(|field| field.name.as_deref() == Some(name)).unwrap();
+
+        // 迁移创建的表、主键和索引
+        let id = field(table("task"), "id");
+        assert_eq!((id.primary, id.not_null, id.unique), (Some(true), Some(true), Some(true)));
+        assert_eq!(id.comment, Some("主键".to_string()));
+        let indices = table("diagram_link").indices.clone().unwrap();
+        assert_eq!(indices[0].name, Some("diagram_link_index_0".to_string()));
+        assert_eq!(indices[0].fields.as_ref().unwrap().len(), 3);
+        assert_eq!(table("diagram_version").indices.clone().unwrap()[0].unique, Some(true));
+
+        // 类型、长度、默认值、检查约束和自增
+        let users = table("users");
+        let email = field(users, "email");
+        assert_eq!((email.r#type.as_deref(), email.size, email.unique), (Some("VARCHAR"), Some(255), Some(true)));
+        assert_eq!((email.default.as_deref(), email.comment.as_deref()), (Some("nobody"), Some("登录邮箱")));
+        assert_eq!(field(users, "age").check, Some("age > 0".to_string()));
+        assert_eq!(field(users, "id").increment, Some(true));
+        let orders = table("orders");
+        // 类型表中没有的类型按亲和性规则对应，多个参数只取第一个作为长度
+        for name in ["price", "total"] {
+            let decimal = field(orders, name);
+            assert_eq!((decimal.r#type.as_deref(), decimal.size), (Some("NUMERIC"), Some(10)));
+        }
+        assert_eq!(orders.indices.clone().unwrap()[0].fields.as_ref().unwrap().len(), 2);
+
+        // 外键
+        let references = diagram.references.clone().unwrap();
+        assert_eq!(references.len(), 1);
+        assert_eq!(references[0].start_table_id, Some(orders.id.clone()));
+        assert_eq!(references[0].end_table_id, Some(users.id.clone()));
+        assert_eq!(references[0].start_field_id, Some(field(orders, "user_id").id));
+        assert_eq!(references[0].delete_constraint, Some("Cascade".to_string()));
+
+        let req = test::TestRequest::post().uri("/import/sqlite?path=missing.sqlite").to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 400);
+    }
+}
@@ -0,0 +1,1041 @@
+use crate::entity::vo::{FieldVo, IndiceVo, ReferenceVo, TableVo};
+use crate::error::DrawDBError;
+
+// 列定义中约束的起始关键字，类型名称在遇到这些关键字时结束
+const COLUMN_KEYWORDS: [&str; 21] = [
+    "NOT", "NULL", "PRIMARY", "UNIQUE", "DEFAULT", "CHECK", "COMMENT", "REFERENCES",
+    "GENERATED", "IDENTITY", "CONSTRAINT", "COLLATE", "AUTOINCREMENT", "AUTO_INCREMENT",
+    "ON", "KEY", "AS", "UNSIGNED", "ZEROFILL", "CHARSET", "SIGNED",
+];
+// PostgreSQL的自增类型与对应的整数类型
+const SERIAL_TYPES: [(&str, &str); 3] = [("SERIAL", "INTEGER"), ("BIGSERIAL", "BIGINT"), ("SMALLSERIAL", "SMALLINT")];
+
+/// SQL词法单元
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    // 未加引号的标识符或关键字
+    Word(String),
+    // 加引号的标识符
+    Quoted(String),
+    // 字符串字面量
+    Str(String),
+    Number(String),
+    Symbol(char),
+    Comment(String),
+}
+
+impl Token {
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(self, Token::Word(word) if word.eq_ignore_ascii_case(keyword))
+    }
+
+    fn is_symbol(&self, symbol: char) -> bool {
+        matches!(self, Token::Symbol(c) if *c == symbol)
+    }
+
+    /// 还原为SQL文本
+    fn to_sql(&self) -> String {
+        match self {
+            Token::Word(word) | Token::Number(word) => word.clone(),
+            Token::Quoted(ident) => format!("\"{}\"", ident.replace('"', "\"\"")),
+            Token::Str(value) => format!("'{}'", value.replace('\'', "''")),
+            Token::Symbol(c) => c.to_string(),
+            Token::Comment(_) => String::new(),
+        }
+    }
+}
+
+/// 解析SQL DDL脚本为表和关联关系
+/// 支持CREATE TABLE、CREATE INDEX、ALTER TABLE ... ADD、COMMENT ON，其余语句忽略
+/// 返回的表、字段、关联关系使用前端风格的临时id，由batch_add_table替换为后端id
+pub fn parse_sql(sql: &str) -> Result<(Vec<TableVo>, Vec<ReferenceVo>), DrawDBError> {
+    let mut schema = Schema::default();
+    for statement in split_statements(tokenize(sql)?) {
+        schema.apply(statement)?;
+    }
+    if schema.tables.is_empty() {
+        return Err(DrawDBError::ValidationError("未解析到CREATE TABLE语句".to_string()));
+    }
+    Ok(schema.build())
+}
+
+/// 词法分析
+fn tokenize(sql: &str) -> Result<Vec<Token>, DrawDBError> {
+    let chars = sql.chars().collect::<Vec<char>>();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        if c.is_whitespace() {
+            i += 1;
+        } else if (c == '-' && next == Some('-')) || c == '#' {
+            let start = i + if c == '#' { 1 } else { 2 };
+            let end = chars[start..].iter().position(|c| *c == '\n').map_or(chars.len(), |p| start + p);
+            tokens.push(Token::Comment(chars[start..end].iter().collect::<String>().trim().to_string()));
+            i = end;
+        } else if c == '/' && next == Some('*') {
+            let end = (i + 2..chars.len().saturating_sub(1))
+            .find(|j| chars[*j] == '*' && chars[*j + 1] == '/')
+            .ok_or_else(|| DrawDBError::ValidationError("注释未闭合".to_string()))?;
+            tokens.push(Token::Comment(chars[i + 2..end].iter().collect::<String>().trim().to_string()));
+            i = end + 2;
+        } else if c == '\'' || c == '"' || c == '`' || (c == '[' && next != Some(']')) {
+            let close = if c == '[' { ']' } else { c };
+            let mut value = String::new();
+            let mut j = i + 1;
+            loop {
+                match chars.get(j) {
+                    None => return Err(DrawDBError::ValidationError(format!("引号未闭合: {}", c))),
+                    // 连续两个引号表示转义
+                    Some(ch) if *ch == close && chars.get(j + 1) == Some(&close) => {
+                        value.push(close);
+                        j += 2;
+                    }
+                    Some(ch) if *ch == close => break,
+                    Some(ch) => {
+                        value.push(*ch);
+                        j += 1;
+                    }
+                }
+            }
+            tokens.push(if c == '\'' { Token::Str(value) } else { Token::Quoted(value) });
+            i = j + 1;
+        } else if c == '[' {
+            // PostgreSQL的数组类型，如INTEGER[]
+            tokens.push(Token::Word("[]".to_string()));
+            i += 2;
+        } else if c.is_ascii_digit() || (c == '-' && next.is_some_and(|n| n.is_ascii_digit()) && !follows_value(&tokens)) {
+            let end = (i + 1..chars.len()).find(|j| !(chars[*j].is_ascii_digit() || chars[*j] == '.')).unwrap_or(chars.len());
+            tokens.push(Token::Number(chars[i..end].iter().collect()));
+            i = end;
+        } else if c.is_alphanumeric() || c == '_' || c == '$' {
+            let end = (i + 1..chars.len())
+            .find(|j| !(chars[*j].is_alphanumeric() || chars[*j] == '_' || chars[*j] == '$'))
+            .unwrap_or(chars.len());
+            tokens.push(Token::Word(chars[i..end].iter().collect()));
+            i = end;
+        } else {
+            tokens.push(Token::Symbol(c));
+            i += 1;
+        }
+    }
+    Ok(tokens)
+}
+
+/// 前一个词法单元是值时，减号是运算符而不是负数的符号
+fn follows_value(tokens: &[Token]) -> bool {
+    match tokens.last() {
+        Some(Token::Word(word)) => !word.eq_ignore_ascii_case("DEFAULT"),
+        last => matches!(last, Some(Token::Quoted(_) | Token::Str(_) | Token::Number(_) | Token::Symbol(')'))),
+    }
+}
+
+/// 语句开头的注释单独保存，作为表的注释
+struct Statement {
+    comments: Vec<String>,
+    tokens: Vec<Token>,
+}
+
+/// 按分号拆分语句，SQL Server的GO分隔符被忽略
+fn split_statements(tokens: Vec<Token>) -> Vec<Statement> {
+    let mut statements = Vec::new();
+    let mut current = Statement { comments: Vec::new(), tokens: Vec::new() };
+    for token in tokens {
+        match token {
+            Token::Symbol(';') => {
+                let finished = std::mem::replace(&mut current, Statement { comments: Vec::new(), tokens: Vec::new() });
+                if !finished.tokens.is_empty() {
+                    statements.push(finished);
+                }
+            }
+            Token::Comment(comment) if current.tokens.is_empty() => current.comments.push(comment),
+            Token::Word(word) if current.tokens.is_empty() && word.eq_ignore_ascii_case("GO") => {}
+            token => current.tokens.push(token),
+        }
+    }
+    if !current.tokens.is_empty() {
+        statements.push(current);
+    }
+    statements
+}
+
+/// 按顶层的逗号拆分
+fn split_by_comma(tokens: &[Token]) -> Vec<Vec<Token>> {
+    let mut parts = vec![Vec::new()];
+    let mut depth = 0;
+    for token in tokens {
+        match token {
+            Token::Symbol('(') => depth += 1,
+            Token::Symbol(')') => depth -= 1,
+            Token::Symbol(',') if depth == 0 => {
+                parts.push(Vec::new());
+                continue;
+            }
+            _ => {}
+        }
+        parts.last_mut().unwrap().push(token.clone());
+    }
+    parts.into_iter().filter(|part| !part.is_empty()).collect()
+}
+
+/// 将词法单元还原为SQL表达式
+fn render(tokens: &[Token]) -> String {
+    let mut sql = String::new();
+    let mut previous: Option<&Token> = None;
+    for token in tokens.iter().filter(|token| !matches!(token, Token::Comment(_))) {
+        let no_space = match (previous, token) {
+            (None, _) => true,
+            (Some(prev), _) if prev.is_symbol('(') || prev.is_symbol('.') || prev.is_symbol(':') => true,
+            (_, Token::Symbol(')' | ',' | '.' | ':')) => true,
+            (Some(Token::Word(_)), Token::Symbol('(')) => true,
+            (Some(Token::Word(_)), Token::Word(word)) if word == "[]" => true,
+            _ => false,
+        };
+        if !no_space {
+            sql.push(' ');
+        }
+        sql.push_str(&token.to_sql());
+        previous = Some(token);
+    }
+    sql
+}
+
+/// 去掉注释后的词法单元游标
+struct Cursor {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Cursor {
+    fn new(tokens: &[Token]) -> Self {
+        Self {
+            tokens: tokens.iter().filter(|token| !matches!(token, Token::Comment(_))).cloned().collect(),
+            pos: 0,
+        }
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn is_end(&self) -> bool {
+        self.pos >= self.tokens.len()
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        self.peek().is_some_and(|token| token.is_keyword(keyword))
+    }
+
+    fn is_symbol(&self, symbol: char) -> bool {
+        self.peek().is_some_and(|token| token.is_symbol(symbol))
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        let matched = self.is_keyword(keyword);
+        if matched {
+            self.pos += 1;
+        }
+        matched
+    }
+
+    /// 连续匹配多个关键字，全部匹配时才前进
+    fn eat_keywords(&mut self, keywords: &[&str]) -> bool {
+        let matched = keywords.iter().enumerate()
+        .all(|(offset, keyword)| self.tokens.get(self.pos + offset).is_some_and(|token| token.is_keyword(keyword)));
+        if matched {
+            self.pos += keywords.len();
+        }
+        matched
+    }
+
+    fn eat_symbol(&mut self, symbol: char) -> bool {
+        let matched = self.is_symbol(symbol);
+        if matched {
+            self.pos += 1;
+        }
+        matched
+    }
+
+    /// 标识符，带模式名的标识符只保留最后一段
+    fn ident(&mut self) -> Result<String, DrawDBError> {
+        let mut name = match self.next() {
+            Some(Token::Word(name) | Token::Quoted(name) | Token::Str(name)) => name,
+            token => return Err(DrawDBError::ValidationError(format!("缺少标识符: {:?}", token))),
+        };
+        while self.is_symbol('.') {
+            self.pos += 1;
+            if let Some(Token::Word(part) | Token::Quoted(part)) = self.next() {
+                name = part;
+            }
+        }
+        Ok(name)
+    }
+
+    /// 括号内的词法单元，不包含外层括号
+    fn paren_group(&mut self) -> Result<Vec<Token>, DrawDBError> {
+        if !self.eat_symbol('(') {
+            return Err(DrawDBError::ValidationError(format!("缺少左括号: {:?}", self.peek())));
+        }
+        let start = self.pos;
+        let mut depth = 1;
+        while let Some(token) = self.next() {
+            match token {
+                Token::Symbol('(') => depth += 1,
+                Token::Symbol(')') => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Ok(self.tokens[start..self.pos - 1].to_vec());
+                    }
+                }
+                _ => {}
+            }
+        }
+        Err(DrawDBError::ValidationError("括号未闭合".to_string()))
+    }
+
+    /// 括号内的列名列表，忽略排序方向和前缀长度
+    fn ident_list(&mut self) -> Result<Vec<String>, DrawDBError> {
+        Ok(split_by_comma(&self.paren_group()?)
+        .into_iter()
+        .filter_map(|part| part.into_iter().find_map(|token| match token {
+            Token::Word(name) | Token::Quoted(name) => Some(name),
+            _ => None,
+        }))
+        .collect())
+    }
+
+    /// 跳过IF NOT EXISTS / IF EXISTS
+    fn skip_if_exists(&mut self) {
+        if !self.eat_keywords(&["IF", "NOT", "EXISTS"]) {
+            self.eat_keywords(&["IF", "EXISTS"]);
+        }
+    }
+}
+
+struct ParsedTable {
+    name: String,
+    comment: Option<String>,
+    fields: Vec<FieldVo>,
+    indices: Vec<ParsedIndex>,
+}
+
+impl ParsedTable {
+    fn field_mut(&mut self, name: &str) -> Option<&mut FieldVo> {
+        let position = self.fields.iter().position(|field| field.name.as_deref() == Some(name))
+        .or_else(|| self.fields.iter().position(|field| {
+            field.name.as_deref().is_some_and(|field_name| field_name.eq_ignore_ascii_case(name))
+        }))?;
+        self.fields.get_mut(position)
+    }
+}
+
+struct ParsedIndex {
+    name: Option<String>,
+    unique: bool,
+    columns: Vec<String>,
+}
+
+struct ParsedForeignKey {
+    name: Option<String>,
+    table: String,
+    columns: Vec<String>,
+    ref_table: String,
+    ref_columns: Vec<String>,
+    on_delete: Option<String>,
+    on_update: Option<String>,
+}
+
+/// 表级别的约束
+enum TableConstraint {
+    PrimaryKey(Vec<String>),
+    Unique(Option<String>, Vec<String>),
+    Index(Option<String>, Vec<String>),
+    ForeignKey(ParsedForeignKey),
+    Ignored,
+}
+
+#[derive(Default)]
+struct Schema {
+    tables: Vec<ParsedTable>,
+    foreign_keys: Vec<ParsedForeignKey>,
+}
+
+impl Schema {
+    fn table_mut(&mut self, name: &str) -> Option<&mut ParsedTable> {
+        let position = self.tables.iter().position(|table| table.name == name)
+        .or_else(|| self.tables.iter().position(|table| table.name.eq_ignore_ascii_case(name)))?;
+        self.tables.get_mut(position)
+    }
+
+    fn apply(&mut self, statement: Statement) -> Result<(), DrawDBError> {
+        let mut cursor = Cursor::new(&statement.tokens);
+        if cursor.eat_keyword("CREATE") {
+            cursor.eat_keywords(&["OR", "REPLACE"]);
+            for modifier in ["TEMP", "TEMPORARY", "UNLOGGED", "GLOBAL", "LOCAL"] {
+                cursor.eat_keyword(modifier);
+            }
+            if cursor.eat_keyword("TABLE") {
+                let comment = (!statement.comments.is_empty()).then(|| statement.comments.join("\n"));
+                return self.create_table(&statement.tokens, comment);
+            }
+            let unique = cursor.eat_keyword("UNIQUE");
+            for modifier in ["CLUSTERED", "NONCLUSTERED", "FULLTEXT", "SPATIAL", "BITMAP"] {
+                cursor.eat_keyword(modifier);
+            }
+            if cursor.eat_keyword("INDEX") {
+                return self.create_index(cursor, unique);
+            }
+        } else if cursor.eat_keywords(&["ALTER", "TABLE"]) {
+            return self.alter_table(cursor);
+        } else if cursor.eat_keywords(&["COMMENT", "ON"]) {
+            self.comment_on(cursor)?;
+        }
+        Ok(())
+    }
+
+    /// CREATE TABLE [IF NOT EXISTS] name ( 列定义和表约束 ) [表选项]
+    fn create_table(&mut self, tokens: &[Token], comment: Option<String>) -> Result<(), DrawDBError> {
+        // 列的注释在拆分列定义前保留，CREATE TABLE ... AS SELECT等没有列定义的语句忽略
+        let Some(start) = tokens.iter().position(|token| token.is_symbol('(')) else {
+            return Ok(());
+        };
+        let mut header = Cursor::new(&tokens[..start]);
+        while !header.is_end() && !header.eat_keyword("TABLE") {
+            header.next();
+        }
+        header.skip_if_exists();
+        let name = header.ident()?;
+        let end = start + matching_paren(&tokens[start..])?;
+        let mut table = ParsedTable { name, comment, fields: Vec::new(), indices: Vec::new() };
+        let mut foreign_keys = Vec::new();
+        for item in split_by_comma(&tokens[start + 1..end]) {
+            let item_comment = item.iter()
+            .take_while(|token| matches!(token, Token::Comment(_)))
+            .filter_map(|token| match token {
+                Token::Comment(comment) => Some(comment.clone()),
+                _ => None,
+            })
+            .collect::<Vec<String>>();
+            let mut cursor = Cursor::new(&item);
+            if cursor.is_end() {
+                continue;
+            }
+            match parse_table_constraint(&mut cursor, &table.name)? {
+                Some(TableConstraint::PrimaryKey(columns)) => {
+                    for column in columns {
+                        if let Some(field) = table.field_mut(&column) {
+                            field.primary = Some(true);
+                        }
+                    }
+                }
+                Some(TableConstraint::Unique(name, columns)) => {
+                    if let [column] = columns.as_slice() {
+                        if let Some(field) = table.field_mut(column) {
+                            field.unique = Some(true);
+                            continue;
+                        }
+                    }
+                    table.indices.push(ParsedIndex { name, unique: true, columns });
+                }
+                Some(TableConstraint::Index(name, columns)) => {
+                    table.indices.push(ParsedIndex { name, unique: false, columns });
+                }
+                Some(TableConstraint::ForeignKey(foreign_key)) => foreign_keys.push(foreign_key),
+                Some(TableConstraint::Ignored) => {}
+                None => {
+                    let comment = (!item_comment.is_empty()).then(|| item_comment.join("\n"));
+                    let (field, foreign_key) = parse_column(&mut cursor, &table.name, comment)?;
+                    table.fields.push(field);
+                    foreign_keys.extend(foreign_key);
+                }
+            }
+        }
+        // MySQL的表注释写在表选项中：COMMENT='...'
+        let mut options = Cursor::new(&tokens[end + 1..]);
+        while !options.is_end() {
+            if options.eat_keyword("COMMENT") {
+                options.eat_symbol('=');
+                if let Some(Token::Str(comment)) = options.next() {
+                    table.comment = Some(comment);
+                }
+            } else {
+                options.next();
+            }
+        }
+        self.tables.push(table);
+        self.foreign_keys.extend(foreign_keys);
+        Ok(())
+    }
+
+    /// CREATE [UNIQUE] INDEX [IF NOT EXISTS] name ON table [USING method] (columns)
+    fn create_index(&mut self, mut cursor: Cursor, unique: bool) -> Result<(), DrawDBError> {
+        cursor.eat_keyword("CONCURRENTLY");
+        cursor.skip_if_exists();
+        let name = if cursor.is_keyword("ON") { None } else { Some(cursor.ident()?) };
+        if !cursor.eat_keyword("ON") {
+            return Ok(());
+        }
+        cursor.eat_keyword("ONLY");
+        let table_name = cursor.ident()?;
+        if cursor.eat_keyword("USING") {
+            cursor.next();
+        }
+        let columns = cursor.ident_list()?;
+        if let Some(table) = self.table_mut(&table_name) {
+            table.indices.push(ParsedIndex { name, unique, columns });
+        }
+        Ok(())
+    }
+
+    /// ALTER TABLE name ADD [CONSTRAINT name] 约束，多个操作以逗号分隔
+    fn alter_table(&mut self, mut cursor: Cursor) -> Result<(), DrawDBError> {
+        cursor.eat_keyword("ONLY");
+        cursor.skip_if_exists();
+        let table_name = cursor.ident()?;
+        let rest = cursor.tokens[cursor.pos..].to_vec();
+        for action in split_by_comma(&rest) {
+            let mut cursor = Cursor::new(&action);
+            if !cursor.eat_keyword("ADD") {
+                continue;
+            }
+            let constraint = parse_table_constraint(&mut cursor, &table_name)?;
+            match constraint {
+                Some(TableConstraint::ForeignKey(foreign_key)) => self.foreign_keys.push(foreign_key),
+                Some(TableConstraint::PrimaryKey(columns)) => {
+                    if let Some(table) = self.table_mut(&table_name) {
+                        for column in columns {
+                            if let Some(field) = table.field_mut(&column) {
+                                field.primary = Some(true);
+                            }
+                        }
+                    }
+                }
+                Some(TableConstraint::Unique(name, columns)) => {
+                    if let Some(table) = self.table_mut(&table_name) {
+                        table.indices.push(ParsedIndex { name, unique: true, columns });
+                    }
+                }
+                Some(TableConstraint::Index(name, columns)) => {
+                    if let Some(table) = self.table_mut(&table_name) {
+                        table.indices.push(ParsedIndex { name, unique: false, columns });
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// PostgreSQL的COMMENT ON TABLE/COLUMN
+    fn comment_on(&mut self, mut cursor: Cursor) -> Result<(), DrawDBError> {
+        let is_table = cursor.eat_keyword("TABLE");
+        if !is_table && !cursor.eat_keyword("COLUMN") {
+            return Ok(());
+        }
+        // 列名带有表名前缀，分段读取
+        let mut parts = Vec::new();
+        loop {
+            match cursor.next() {
+                Some(Token::Word(part) | Token::Quoted(part)) => parts.push(part),
+                _ => return Ok(()),
+            }
+            if !cursor.eat_symbol('.') {
+                break;
+            }
+        }
+        if !cursor.eat_keyword("IS") {
+            return Ok(());
+        }
+        let Some(Token::Str(comment)) = cursor.next() else {
+            return Ok(());
+        };
+        match (is_table, parts.as_slice()) {
+            (true, [.., table]) => {
+                if let Some(table) = self.table_mut(table) {
+                    table.comment = Some(comment);
+                }
+            }
+            (false, [.., table, column]) => {
+                if let Some(field) = self.table_mut(table).and_then(|table| table.field_mut(column)) {
+                    field.comment = Some(comment);
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// 生成前端风格id的表和关联关系：表id为表的序号，字段id为字段在表内的序号
+    fn build(self) -> (Vec<TableVo>, Vec<ReferenceVo>) {
+        let tables = self.tables.iter().enumerate().map(|(table_index, table)| {
+            let fields = table.fields.iter().enumerate().map(|(field_index, field)| FieldVo {
+                id: field_index.to_string(),
+                ..field.clone()
+            }).collect::<Vec<FieldVo>>();
+            let indices = table.indices.iter().enumerate().filter_map(|(indice_index, indice)| {
+                let field_ids = indice.columns.iter()
+                .filter_map(|column| field_id_by_name(&fields, column))
+                .collect::<Vec<String>>();
+                (!field_ids.is_empty()).then(|| IndiceVo {
+                    id: indice_index.to_string(),
+                    name: Some(indice.name.clone().unwrap_or_else(|| format!("{}_index_{}", table.name, indice_index))),
+                    unique: Some(indice.unique),
+                    table_id: None,
+                    fields: Some(field_ids),
+                })
+            }).collect();
+            TableVo {
+                id: table_index.to_string(),
+                color: None,
+                comment: table.comment.clone(),
+                locked: Some(false),
+                name: Some(table.name.clone()),
+                x: None,
+                y: None,
+                fields: Some(fields),
+                indices: Some(indices),
+                diagram_id: String::new(),
+            }
+        }).collect::<Vec<TableVo>>();
+        let table_index = |name: &str| {
+            tables.iter().position(|table| table.name.as_deref() == Some(name))
+            .or_else(|| tables.iter().position(|table| table.name.as_deref().is_some_and(|n| n.eq_ignore_ascii_case(name))))
+        };
+
+        let mut references = Vec::new();
+        for foreign_key in self.foreign_keys.iter() {
+            let (Some(start), Some(end)) = (table_index(&foreign_key.table), table_index(&foreign_key.ref_table)) else {
+                continue;
+            };
+            let start_fields = tables[start].fields.clone().unwrap_or_default();
+            let end_fields = tables[end].fields.clone().unwrap_or_default();
+            for (column, ref_column) in foreign_key.columns.iter().zip(foreign_key.ref_columns.iter()) {
+                let (Some(start_field), Some(end_field)) = (
+                    field_id_by_name(&start_fields, column),
+                    field_id_by_name(&end_fields, ref_column)
+                ) else {
+                    continue;
+                };
+                let unique = start_fields.iter()
+                .find(|field| field.id == start_field)
+                .is_some_and(|field| field.unique.unwrap_or(false));
+                references.push(ReferenceVo {
+                    id: references.len().to_string(),
+                    name: Some(foreign_key.name.clone().unwrap_or_else(|| {
+                        format!("fk_{}_{}_{}", foreign_key.table, column, foreign_key.ref_table)
+                    })),
+                    start_table_id: Some(start.to_string()),
+                    end_table_id: Some(end.to_string()),
+                    cardinality: Some(if unique { "one_to_one" } else { "many_to_one" }.to_string()),
+                    delete_constraint: Some(constraint_name(foreign_key.on_delete.as_deref())),
+                    end_field_id: Some(end_field),
+                    start_field_id: Some(start_field),
+                    update_constraint: Some(constraint_name(foreign_key.on_update.as_deref())),
+                });
+            }
+        }
+        (tables, references)
+    }
+}
+
+/// 与第一个左括号匹配的右括号的位置
+fn matching_paren(tokens: &[Token]) -> Result<usize, DrawDBError> {
+    let mut depth = 0;
+    for (index, token) in tokens.iter().enumerate() {
+        match token {
+            Token::Symbol('(') => depth += 1,
+            Token::Symbol(')') => {
+                depth -= 1;
+                if depth == 0 {
+                    return Ok(index);
+                }
+            }
+            _ => {}
+        }
+    }
+    Err(DrawDBError::ValidationError("括号未闭合".to_string()))
+}
+
+pub fn field_id_by_name(fields: &[FieldVo], name: &str) -> Option<String> {
+    fields.iter().find(|field| field.name.as_deref() == Some(name))
+    .or_else(|| fields.iter().find(|field| field.name.as_deref().is_some_and(|n| n.eq_ignore_ascii_case(name))))
+    .map(|field| field.id.clone())
+}
+
+/// 外键动作转换为前端的约束名称，如SET NULL -> Set null
+pub fn constraint_name(action: Option<&str>) -> String {
+    let action = action.unwrap_or("NO ACTION").to_lowercase();
+    let mut chars = action.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => "No action".to_string(),
+    }
+}
+
+/// 表约束，不是表约束时返回None，由调用方按列定义解析
+fn parse_table_constraint(cursor: &mut Cursor, table: &str) -> Result<Option<TableConstraint>, DrawDBError> {
+    let start = cursor.pos;
+    let name = if cursor.eat_keyword("CONSTRAINT") { Some(cursor.ident()?) } else { None };
+    if cursor.eat_keywords(&["PRIMARY", "KEY"]) {
+        cursor.eat_keyword("CLUSTERED");
+        return Ok(Some(TableConstraint::PrimaryKey(cursor.ident_list()?)));
+    }
+    if cursor.eat_keyword("UNIQUE") {
+        if !cursor.eat_keyword("KEY") {
+            cursor.eat_keyword("INDEX");
+        }
+        let index_name = if cursor.is_symbol('(') { name } else { Some(cursor.ident()?) };
+        return Ok(Some(TableConstraint::Unique(index_name, cursor.ident_list()?)));
+    }
+    if cursor.eat_keywords(&["FOREIGN", "KEY"]) {
+        let name = if cursor.is_symbol('(') { name } else { Some(cursor.ident()?) };
+        let columns = cursor.ident_list()?;
+        return Ok(parse_references(cursor, name, table, columns)?.map(TableConstraint::ForeignKey));
+    }
+    if cursor.is_keyword("CHECK") || cursor.is_keyword("EXCLUDE") {
+        return Ok(Some(TableConstraint::Ignored));
+    }
+    // MySQL的KEY/INDEX name (columns)
+    for keyword in ["FULLTEXT", "SPATIAL"] {
+        cursor.eat_keyword(keyword);
+    }
+    if name.is_none() && (cursor.is_keyword("KEY") || cursor.is_keyword("INDEX"))
+        && cursor.tokens.get(cursor.pos + 1).is_some_and(|token| !matches!(token, Token::Word(word) if is_column_keyword(word))) {
+        cursor.next();
+        let index_name = if cursor.is_symbol('(') { None } else { Some(cursor.ident()?) };
+        return Ok(Some(TableConstraint::Index(index_name, cursor.ident_list()?)));
+    }
+    cursor.pos = start;
+    Ok(None)
+}
+
+/// REFERENCES table (columns) [ON DELETE action] [ON UPDATE action]
+fn parse_references(
+    cursor: &mut Cursor,
+    name: Option<String>,
+    table: &str,
+    columns: Vec<String>
+) -> Result<Option<ParsedForeignKey>, DrawDBError> {
+    if !cursor.eat_keyword("REFERENCES") {
+        return Ok(None);
+    }
+    let ref_table = cursor.ident()?;
+    let ref_columns = if cursor.is_symbol('(') { cursor.ident_list()? } else { Vec::new() };
+    let mut foreign_key = ParsedForeignKey {
+        name,
+        table: table.to_string(),
+        columns,
+        ref_table,
+        ref_columns,
+        on_delete: None,
+        on_update: None,
+    };
+    while cursor.is_keyword("ON") || cursor.is_keyword("MATCH") {
+        if cursor.eat_keyword("MATCH") {
+            cursor.next();
+            continue;
+        }
+        cursor.next();
+        let is_delete = cursor.eat_keyword("DELETE");
+        if !is_delete && !cursor.eat_keyword("UPDATE") {
+            break;
+        }
+        let action = if cursor.eat_keywords(&["SET", "NULL"]) {
+            "SET NULL".to_string()
+        } else if cursor.eat_keywords(&["SET", "DEFAULT"]) {
+            "SET DEFAULT".to_string()
+        } else if cursor.eat_keywords(&["NO", "ACTION"]) {
+            "NO ACTION".to_string()
+        } else {
+            match cursor.next() {
+                Some(Token::Word(action)) => action.to_uppercase(),
+                _ => break,
+            }
+        };
+        if is_delete {
+            foreign_key.on_delete = Some(action);
+        } else {
+            foreign_key.on_update = Some(action);
+        }
+    }
+    Ok(Some(foreign_key))
+}
+
+fn is_column_keyword(word: &str) -> bool {
+    COLUMN_KEYWORDS.iter().any(|keyword| keyword.eq_ignore_ascii_case(word))
+}
+
+/// 列定义：名称 类型[(长度)] 约束...
+fn parse_column(
+    cursor: &mut Cursor,
+    table: &str,
+    comment: Option<String>
+) -> Result<(FieldVo, Option<ParsedForeignKey>), DrawDBError> {
+    let name = cursor.ident()?;
+    let mut field = FieldVo {
+        id: String::new(),
+        table_id: None,
+        check: None,
+        comment,
+        default: None,
+        increment: Some(false),
+        not_null: Some(false),
+        primary: Some(false),
+        size: None,
+        r#type: None,
+        unique: Some(false),
+        name: Some(name.clone()),
+        values: None,
+    };
+    //1、类型，可能由多个单词组成，如DOUBLE PRECISION、TIMESTAMP(6) WITH TIME ZONE
+    let mut type_words: Vec<String> = Vec::new();
+    loop {
+        match cursor.peek() {
+            Some(Token::Word(word)) if word == "[]" => {
+                if let Some(last) = type_words.last_mut() {
+                    last.push_str("[]");
+                }
+            }
+            // CHARACTER出现在类型之后时是CHARACTER SET
+            Some(Token::Word(word)) if !is_column_keyword(word)
+                && (type_words.is_empty() || !word.eq_ignore_ascii_case("CHARACTER")) => {
+                type_words.push(word.to_uppercase());
+            }
+            Some(Token::Quoted(word)) if type_words.is_empty() => type_words.push(word.clone()),
+            Some(Token::Symbol('(')) if !type_words.is_empty() => {
+                let args = split_by_comma(&cursor.paren_group()?);
+                if let Some([Token::Number(size)]) = args.first().map(Vec::as_slice) {
+                    field.size = size.parse().ok();
+                }
+                continue;
+            }
+            _ => break,
+        }
+        cursor.next();
+    }
+    let mut r#type = type_words.join(" ");
+    if let Some((_, integer)) = SERIAL_TYPES.iter().find(|(serial, _)| serial.eq_ignore_ascii_case(&r#type)) {
+        r#type = integer.to_string();
+        field.increment = Some(true);
+    }
+    field.r#type = (!r#type.is_empty()).then_some(r#type);
+
+    //2、列约束
+    let mut foreign_key = None;
+    while !cursor.is_end() {
+        if cursor.eat_keywords(&["NOT", "NULL"]) {
+            field.not_null = Some(true);
+        } else if cursor.eat_keywords(&["PRIMARY", "KEY"]) {
+            field.primary = Some(true);
+            if !cursor.eat_keyword("ASC") {
+                cursor.eat_keyword("DESC");
+            }
+        } else if cursor.eat_keyword("AUTOINCREMENT") || cursor.eat_keyword("AUTO_INCREMENT") {
+            field.increment = Some(true);
+        } else if cursor.eat_keyword("UNIQUE") {
+            cursor.eat_keyword("KEY");
+            field.unique = Some(true);
+        } else if cursor.eat_keyword("DEFAULT") {
+            let default = parse_default(cursor)?;
+            if default.to_lowercase().starts_with("nextval(") {
+                field.increment = Some(true);
+            } else {
+                field.default = Some(default);
+            }
+        } else if cursor.eat_keyword("CHECK") {
+            field.check = Some(render(&cursor.paren_group()?));
+        } else if cursor.eat_keyword("COMMENT") {
+            if let Some(Token::Str(comment)) = cursor.next() {
+                field.comment = Some(comment);
+            }
+        } else if cursor.is_keyword("REFERENCES") {
+            foreign_key = parse_references(cursor, None, table, vec![name.clone()])?;
+        } else if cursor.eat_keyword("GENERATED") {
+            // GENERATED {ALWAYS|BY DEFAULT} AS IDENTITY 或 GENERATED ALWAYS AS (expr) STORED
+            while !cursor.is_end() && !cursor.eat_keyword("AS") {
+                cursor.next();
+            }
+            if cursor.eat_keyword("IDENTITY") {
+                field.increment = Some(true);
+            }
+            if cursor.is_symbol('(') {
+                cursor.paren_group()?;
+            }
+        } else if cursor.eat_keyword("IDENTITY") {
+            field.increment = Some(true);
+            if cursor.is_symbol('(') {
+                cursor.paren_group()?;
+            }
+        } else if cursor.eat_keyword("CONSTRAINT") || cursor.eat_keyword("COLLATE")
+            || cursor.eat_keyword("CHARSET") || cursor.eat_keywords(&["CHARACTER", "SET"]) {
+            cursor.next();
+        } else if cursor.eat_keywords(&["ON", "UPDATE"]) {
+            // MySQL的ON UPDATE CURRENT_TIMESTAMP
+            cursor.next();
+            if cursor.is_symbol('(') {
+                cursor.paren_group()?;
+            }
+        } else {
+            cursor.next();
+        }
+    }
+    Ok((field, foreign_key))
+}
+
+/// 默认值，字符串去掉引号，PostgreSQL的类型转换后缀被去掉
+fn parse_default(cursor: &mut Cursor) -> Result<String, DrawDBError> {
+    let mut tokens = Vec::new();
+    if cursor.is_symbol('(') {
+        let group = cursor.paren_group()?;
+        return Ok(format!("({})", render(&group)));
+    }
+    if let Some(token) = cursor.next() {
+        tokens.push(token);
+    }
+    while let Some(token) = cursor.peek() {
+        match token {
+            Token::Word(word) if is_column_keyword(word) => break,
+            Token::Symbol('(') => {
+                let group = cursor.paren_group()?;
+                tokens.push(Token::Symbol('('));
+                tokens.extend(group);
+                tokens.push(Token::Symbol(')'));
+            }
+            _ => {
+                tokens.push(token.clone());
+                cursor.next();
+            }
+        }
+    }
+    if let Some(cast) = tokens.windows(2).position(|pair| pair[0].is_symbol(':') && pair[1].is_symbol(':')) {
+        tokens.truncate(cast);
+    }
+    Ok(match tokens.as_slice() {
+        [Token::Str(value)] => value.clone(),
+        tokens => render(tokens),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn field<'a>(table: &'a TableVo, name: &str) -> &'a FieldVo {
+        table.fields.as_ref().unwrap().iter().find(|field| field.name.as_deref() == Some(name)).unwrap()
+    }
+
+    #[test]
+    fn test_parse_postgres(){
+        let sql = r#"
+            CREATE TABLE public.users (
+                id SERIAL PRIMARY KEY,
+                email character varying(255) NOT NULL UNIQUE,
+                status VARCHAR(16) DEFAULT 'active'::character varying,
+                created_at TIMESTAMP WITH TIME ZONE DEFAULT now()
+            );
+            CREATE TABLE orders (
+                id BIGINT GENERATED BY DEFAULT AS IDENTITY,
+                user_id INTEGER CHECK (user_id > 0),
+                amount NUMERIC(10, 2) DEFAULT -1,
+                CONSTRAINT orders_pk PRIMARY KEY (id)
+            );
+            ALTER TABLE ONLY orders
+                ADD CONSTRAINT fk_orders_users FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE SET NULL;
+            CREATE UNIQUE INDEX idx_orders ON orders USING btree (user_id, id DESC);
+            COMMENT ON COLUMN orders.amount IS 'total';
+        "#;
+        let (tables, references) = parse_sql(sql).unwrap();
+        assert_eq!(tables.len(), 2);
+        let users = &tables[0];
+        assert_eq!(users.name, Some("users".to_string()));
+        let id = field(users, "id");
+        assert_eq!((id.r#type.as_deref(), id.increment, id.primary), (Some("INTEGER"), Some(true), Some(true)));
+        let email = field(users, "email");
+        assert_eq!((email.r#type.as_deref(), email.size), (Some("CHARACTER VARYING"), Some(255)));
+        assert_eq!((email.not_null, email.unique), (Some(true), Some(true)));
+        assert_eq!(field(users, "status").default, Some("active".to_string()));
+        assert_eq!(field(users, "created_at").r#type, Some("TIMESTAMP WITH TIME ZONE".to_string()));
+        assert_eq!(field(users, "created_at").default, Some("now()".to_string()));
+
+        let orders = &tables[1];
+        assert_eq!(field(orders, "id").increment, Some(true));
+        assert_eq!(field(orders, "id").primary, Some(true));
+        assert_eq!(field(orders, "user_id").check, Some("user_id > 0".to_string()));
+        assert_eq!(field(orders, "amount").default, Some("-1".to_string()));
+        assert_eq!(field(orders, "amount").comment, Some("total".to_string()));
+        let indices = orders.indices.as_ref().unwrap();
+        assert_eq!(indices[0].name, Some("idx_orders".to_string()));
+        assert_eq!(indices[0].fields, Some(vec!["1".to_string(), "0".to_string()]));
+
+        assert_eq!(references.len(), 1);
+        assert_eq!(references[0].name, Some("fk_orders_users".to_string()));
+        assert_eq!(references[0].start_table_id, Some("1".to_string()));
+        assert_eq!(references[0].start_field_id, Some("1".to_string()));
+        assert_eq!(references[0].end_table_id, Some("0".to_string()));
+        assert_eq!(references[0].delete_constraint, Some("Set null".to_string()));
+        assert_eq!(references[0].update_constraint, Some("No action".to_string()));
+    }
+
+    #[test]
+    fn test_parse_mysql(){
+        let sql = r#"
+            CREATE TABLE `users` (
+                `id` INT UNSIGNED NOT NULL AUTO_INCREMENT,
+                `name` VARCHAR(64) CHARACTER SET utf8mb4 NOT NULL COMMENT 'display name',
+                `updated_at` TIMESTAMP DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+                PRIMARY KEY (`id`),
+                UNIQUE KEY `uk_name` (`name`),
+                KEY `idx_updated` (`updated_at`)
+            ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COMMENT='app users';
+            CREATE TABLE `profiles` (
+                `user_id` INT UNSIGNED NOT NULL,
+                UNIQUE (`user_id`),
+                FOREIGN KEY (`user_id`) REFERENCES `users` (`id`) ON DELETE CASCADE ON UPDATE RESTRICT
+            );
+        "#;
+        let (tables, references) = parse_sql(sql).unwrap();
+        let users = &tables[0];
+        assert_eq!(users.comment, Some("app users".to_string()));
+        let id = field(users, "id");
+        assert_eq!((id.r#type.as_deref(), id.increment, id.primary), (Some("INT"), Some(true), Some(true)));
+        let name = field(users, "name");
+        assert_eq!((name.size, name.not_null), (Some(64), Some(true)));
+        assert_eq!(name.comment, Some("display name".to_string()));
+        assert_eq!(name.unique, Some(true));
+        assert_eq!(field(users, "updated_at").default, Some("CURRENT_TIMESTAMP".to_string()));
+        assert_eq!(users.indices.as_ref().unwrap().len(), 1);
+        assert_eq!(references[0].cardinality, Some("one_to_one".to_string()));
+        assert_eq!(references[0].delete_constraint, Some("Cascade".to_string()));
+        assert_eq!(references[0].update_constraint, Some("Restrict".to_string()));
+        assert_eq!(references[0].name, Some("fk_profiles_user_id_users".to_string()));
+    }
+
+    #[test]
+    fn test_parse_sqlite(){
+        let sql = r#"
+            /* 账号 */
+            CREATE TABLE IF NOT EXISTS "accounts" (
+                -- 主键
+                "id" INTEGER PRIMARY KEY AUTOINCREMENT,
+                "parent_id" INTEGER REFERENCES "accounts" ("id") ON DELETE CASCADE,
+                "tags" TEXT[] DEFAULT '{}'
+            );
+        "#;
+        let (tables, references) = parse_sql(sql).unwrap();
+        let accounts = &tables[0];
+        assert_eq!(accounts.comment, Some("账号".to_string()));
+        assert_eq!(field(accounts, "id").comment, Some("主键".to_string()));
+        assert_eq!(field(accounts, "id").increment, Some(true));
+        assert_eq!(field(accounts, "tags").r#type, Some("TEXT[]".to_string()));
+        assert_eq!(references[0].start_table_id, references[0].end_table_id);
+        assert!(parse_sql("SELECT 1;").is_err());
+        assert!(parse_sql("CREATE TABLE t (id INT").is_err());
+    }
+}
@@ -0,0 +1,266 @@
+use std::path::Path;
+use sea_orm::{ConnectionTrait, DatabaseConnection, DbBackend, DbErr, QueryResult, RuntimeErr, SqlxSqliteConnector, Statement, TransactionTrait};
+use sqlx::ConnectOptions;
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+use crate::datatypes::{unquote, DataTypes};
+use crate::entity::vo::{DiagramSaveVo, FieldVo, IndiceVo, ReferenceVo, TableVo};
+use crate::error::DrawDBError;
+use crate::export::Dialect;
+use super::{arrange_tables, constraint_name, field_id_by_name, insert_diagram, parse_sql, save_import};
+
+/// 从SQLite数据库文件逆向生成新图表，未指定名称时使用文件名
+/// 数据库文件以只读方式打开，路径由调用方校验
+pub async fn import_sqlite_file(
+    db: &DatabaseConnection,
+    file: &Path,
+    name: Option<String>
+) -> Result<DiagramSaveVo, DrawDBError> {
+    if !file.is_file() {
+        return Err(DrawDBError::ValidationError(format!("数据库文件不存在: {}", file.display())));
+    }
+    //1、读取表结构并排列表的位置
+    let source = connect_sqlite(SqliteConnectOptions::new().filename(file).read_only(true)).await?;
+    let (mut tables, references) = introspect_sqlite(&source).await?;
+    source.close().await?;
+    if tables.is_empty() {
+        return Err(DrawDBError::ValidationError("数据库中没有表".to_string()));
+    }
+    arrange_tables(&mut tables);
+    //2、在同一个事务中新建图表并保存表和关联关系
+    let name = name.or_else(|| file.file_stem().map(|stem| stem.to_string_lossy().to_string()));
+    let tx = db.begin().await?;
+    let diagram_id = insert_diagram(&tx, name, Some(Dialect::Sqlite.database().to_string())).await?;
+    let result = save_import(&tx, diagram_id, tables, references, Vec::new(), Vec::new()).await?;
+    tx.commit().await?;
+    Ok(result)
+}
+
+/// 按连接参数打开SQLite数据库文件
+/// 只使用一个连接，保证PRAGMA和事务在同一个连接上执行；文件路径不经过URL解析，可以包含?、#、%
+pub(crate) async fn connect_sqlite(options: SqliteConnectOptions) -> Result<DatabaseConnection, DrawDBError> {
+    let pool = SqlitePoolOptions::new().max_connections(1).connect_with(options.disable_statement_logging()).await
+    .map_err(|e| DbErr::Conn(RuntimeErr::SqlxError(e)))?;
+    Ok(SqlxSqliteConnector::from_sqlx_sqlite_pool(pool))
+}
+
+/// 读取SQLite数据库的表、字段、索引和外键
+/// 表来自sqlite_master，字段、索引和外键来自PRAGMA table_info、index_list、index_info和foreign_key_list
+/// 建表语句中的检查约束、字段注释和主键列的唯一约束通过解析sqlite_master.sql补充
+/// 返回的表、字段、关联关系使用前端风格的临时id，由batch_add_table替换为后端id
+pub async fn introspect_sqlite<C: ConnectionTrait>(conn: &C) -> Result<(Vec<TableVo>, Vec<ReferenceVo>), DrawDBError> {
+    let rows = query(conn, "SELECT name, sql FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%' ORDER BY rowid", None).await?;
+    let mut tables = Vec::new();
+    let mut foreign_keys = Vec::new();
+    for (table_index, row) in rows.iter().enumerate() {
+        let name = row.try_get::<String>("", "name")?;
+        let sql = row.try_get::<Option<String>>("", "sql")?.unwrap_or_default();
+        let mut fields = table_fields(conn, &name, &sql).await?;
+        let indices = table_indices(conn, &name, &mut fields).await?;
+        foreign_keys.extend(table_foreign_keys(conn, &name).await?);
+        tables.push(TableVo {
+            id: table_index.to_string(),
+            color: None,
+            comment: None,
+            locked: Some(false),
+            name: Some(name),
+            x: None,
+            y: None,
+            fields: Some(fields),
+            indices: Some(indices),
+            diagram_id: String::new(),
+        });
+    }
+    let references = build_references(&tables, foreign_keys);
+    Ok((tables, references))
+}
+
+/// 外键的一组列，复合外键的每一列对应一行
+struct ForeignKeyColumn {
+    table: String,
+    column: String,
+    ref_table: String,
+    // 为空时引用主键
+    ref_column: Option<String>,
+    on_update: String,
+    on_delete: String,
+}
+
+async fn query<C: ConnectionTrait>(conn: &C, sql: &str, table: Option<&str>) -> Result<Vec<QueryResult>, DrawDBError> {
+    let values = table.map(|table| vec![table.into()]).unwrap_or_default();
+    Ok(conn.query_all(Statement::from_sql_and_values(DbBackend::Sqlite, sql, values)).await?)
+}
+
+/// PRAGMA table_info的列，字段id为列的序号
+async fn table_fields<C: ConnectionTrait>(conn: &C, table: &str, sql: &str) -> Result<Vec<FieldVo>, DrawDBError> {
+    let rows = query(conn, "SELECT cid, name, type, \"notnull\", dflt_value, pk FROM pragma_table_info(?) ORDER BY cid", Some(table)).await?;
+    // 只有一个INTEGER主键并且建表语句中有AUTOINCREMENT时为自增列
+    let primary_count = rows.iter().filter(|row| row.try_get::<i32>("", "pk").is_ok_and(|pk| pk > 0)).count();
+    let autoincrement = primary_count == 1 && sql.to_uppercase().contains("AUTOINCREMENT");
+    let parsed = parse_sql(sql).ok()
+    .and_then(|(tables, _)| tables.into_iter().next())
+    .and_then(|table| table.fields)
+    .unwrap_or_default();
+
+    let mut fields = Vec::new();
+    for (field_index, row) in rows.iter().enumerate() {
+        let name = row.try_get::<String>("", "name")?;
+        let (r#type, size) = split_type(&row.try_get::<Option<String>>("", "type")?.unwrap_or_default());
+        let r#type = Some(affinity_type(r#type.as_deref().unwrap_or_default()));
+        let primary = row.try_get::<i32>("", "pk")? > 0;
+        let parsed_field = parsed.iter().find(|field| field.name.as_deref().is_some_and(|n| n.eq_ignore_ascii_case(&name)));
+        fields.push(FieldVo {
+            id: field_index.to_string(),
+            table_id: None,
+            check: parsed_field.and_then(|field| field.check.clone()),
+            comment: parsed_field.and_then(|field| field.comment.clone()),
+            default: row.try_get::<Option<String>>("", "dflt_value")?.and_then(|default| parse_default(&default)),
+            increment: Some(primary && autoincrement && r#type.as_deref() == Some("INTEGER")),
+            not_null: Some(row.try_get::<i32>("", "notnull")? == 1),
+            primary: Some(primary),
+            size,
+            r#type,
+            // 主键列上的UNIQUE约束不会单独创建索引
+            unique: Some(parsed_field.is_some_and(|field| field.unique.unwrap_or(false))),
+            name: Some(name),
+            values: None,
+        });
+    }
+    Ok(fields)
+}
+
+/// PRAGMA index_list的索引，主键索引忽略，单列的UNIQUE约束标记在字段上
+async fn table_indices<C: ConnectionTrait>(conn: &C, table: &str, fields: &mut [FieldVo]) -> Result<Vec<IndiceVo>, DrawDBError> {
+    let rows = query(conn, "SELECT name, \"unique\", origin FROM pragma_index_list(?) ORDER BY seq DESC", Some(table)).await?;
+    let mut indices = Vec::new();
+    for row in rows {
+        let name = row.try_get::<String>("", "name")?;
+        let unique = row.try_get::<i32>("", "unique")? == 1;
+        let origin = row.try_get::<String>("", "origin")?;
+        if origin == "pk" {
+            continue;
+        }
+        // 表达式索引的列名为空
+        let columns = query(conn, "SELECT name FROM pragma_index_info(?) ORDER BY seqno", Some(&name)).await?
+        .iter()
+        .filter_map(|column| column.try_get::<Option<String>>("", "name").ok().flatten())
+        .collect::<Vec<String>>();
+        let field_ids = columns.iter()
+        .filter_map(|column| field_id_by_name(fields, column))
+        .collect::<Vec<String>>();
+        if field_ids.is_empty() {
+            continue;
+        }
+        if origin == "u" && field_ids.len() == 1 {
+            if let Some(field) = fields.iter_mut().find(|field| field.id == field_ids[0]) {
+                field.unique = Some(true);
+            }
+            continue;
+        }
+        // UNIQUE约束自动创建的索引名称以sqlite_autoindex开头，改为按表名命名
+        let name = if name.starts_with("sqlite_autoindex_") {
+            format!("{}_index_{}", table, indices.len())
+        } else {
+            name
+        };
+        indices.push(IndiceVo {
+            id: indices.len().to_string(),
+            name: Some(name),
+            unique: Some(unique),
+            table_id: None,
+            fields: Some(field_ids),
+        });
+    }
+    Ok(indices)
+}
+
+/// PRAGMA foreign_key_list的外键
+async fn table_foreign_keys<C: ConnectionTrait>(conn: &C, table: &str) -> Result<Vec<ForeignKeyColumn>, DrawDBError> {
+    let rows = query(conn, "SELECT \"table\", \"from\", \"to\", on_update, on_delete FROM pragma_foreign_key_list(?) ORDER BY id, seq", Some(table)).await?;
+    rows.iter().map(|row| Ok(ForeignKeyColumn {
+        table: table.to_string(),
+        column: row.try_get::<String>("", "from")?,
+        ref_table: row.try_get::<String>("", "table")?,
+        ref_column: row.try_get::<Option<String>>("", "to")?,
+        on_update: row.try_get::<String>("", "on_update")?,
+        on_delete: row.try_get::<String>("", "on_delete")?,
+    })).collect()
+}
+
+/// 外键的每一列生成一个关联关系，引用的表或列不存在时忽略
+fn build_references(tables: &[TableVo], foreign_keys: Vec<ForeignKeyColumn>) -> Vec<ReferenceVo> {
+    let table_by_name = |name: &str| tables.iter()
+    .find(|table| table.name.as_deref().is_some_and(|table_name| table_name.eq_ignore_ascii_case(name)));
+    let mut references = Vec::new();
+    for foreign_key in foreign_keys {
+        let (Some(start), Some(end)) = (table_by_name(&foreign_key.table), table_by_name(&foreign_key.ref_table)) else {
+            continue;
+        };
+        let start_fields = start.fields.clone().unwrap_or_default();
+        let end_fields = end.fields.clone().unwrap_or_default();
+        let end_field = match &foreign_key.ref_column {
+            Some(ref_column) => field_id_by_name(&end_fields, ref_column),
+            None => end_fields.iter().find(|field| field.primary.unwrap_or(false)).map(|field| field.id.clone()),
+        };
+        let (Some(start_field), Some(end_field)) = (field_id_by_name(&start_fields, &foreign_key.column), end_field) else {
+            continue;
+        };
+        let unique = start_fields.iter()
+        .find(|field| field.id == start_field)
+        .is_some_and(|field| field.unique.unwrap_or(false));
+        references.push(ReferenceVo {
+            id: references.len().to_string(),
+            name: Some(format!("fk_{}_{}_{}", foreign_key.table, foreign_key.column, foreign_key.ref_table)),
+            start_table_id: Some(start.id.clone()),
+            end_table_id: Some(end.id.clone()),
+            cardinality: Some(if unique { "one_to_one" } else { "many_to_one" }.to_string()),
+            delete_constraint: Some(constraint_name(Some(&foreign_key.on_delete))),
+            end_field_id: Some(end_field),
+            start_field_id: Some(start_field),
+            update_constraint: Some(constraint_name(Some(&foreign_key.on_update))),
+        });
+    }
+    references
+}
+
+/// 类型以及长度，如VARCHAR(255)，和其他导入一样，DECIMAL(10, 2)等多个参数的类型只取第一个数字作为长度
+fn split_type(declared: &str) -> (Option<String>, Option<i32>) {
+    let declared = declared.trim().to_uppercase();
+    match declared.split_once('(') {
+        Some((r#type, args)) => {
+            let size = args.trim_end_matches(')').split(',').next().and_then(|size| size.trim().parse().ok());
+            (Some(r#type.trim().to_string()), size)
+        }
+        None => ((!declared.is_empty()).then_some(declared), None),
+    }
+}
+
+/// 类型表中没有的声明类型按SQLite的类型亲和性规则对应到类型表中的类型
+fn affinity_type(name: &str) -> String {
+    if DataTypes::new(Some(Dialect::Sqlite)).find(name).is_some() {
+        return name.to_string();
+    }
+    let r#type = if name.contains("INT") {
+        "INTEGER"
+    } else if ["CHAR", "CLOB", "TEXT"].iter().any(|affinity| name.contains(affinity)) {
+        "TEXT"
+    } else if name.is_empty() || name.contains("BLOB") {
+        "BLOB"
+    } else if ["REAL", "FLOA", "DOUB"].iter().any(|affinity| name.contains(affinity)) {
+        "REAL"
+    } else {
+        "NUMERIC"
+    };
+    r#type.to_string()
+}
+
+/// 字符串默认值去掉引号，NULL视为没有默认值
+fn parse_default(default: &str) -> Option<String> {
+    let default = default.trim();
+    if default.eq_ignore_ascii_case("NULL") {
+        return None;
+    }
+    if default.starts_with('\'') {
+        return Some(unquote(default).replace("''", "'"));
+    }
+    Some(default.to_string())
+}
@@ -1,7 +1,56 @@
-use sea_orm::{ColumnTrait, DatabaseTransaction, EntityTrait, QueryFilter};
+use std::collections::HashMap;
+use itertools::Itertools;
+use sea_orm::{ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseTransaction, EntityTrait, QueryFilter, QueryOrder};
+use crate::entity::{indice_link, prelude::IndiceLink};
+use crate::entity::vo::build_indice_link;
 use crate::{entity::vo::IndiceVo, error::DrawDBError};
 use crate::entity::indice::{ActiveModel as IndiceActiveModel, Column, Entity as Indice};
+use crate::next_ids;
+
+/// 查询表关联的索引
+/// 索引的fields按索引列顺序返回
+pub async fn query_indices<C: ConnectionTrait>(
+    conn: &C,
+    table_ids: Vec<String>
+) -> Result<Vec<IndiceVo>, DrawDBError> {
+    let indices = Indice::find()
+    .filter(Column::TableId.is_in(table_ids))
+    .all(conn)
+    .await?;
+    let indice_ids = indices.iter().map(|indice| indice.id.clone()).collect::<Vec<String>>();
+    let field_map: HashMap<String, Vec<String>> = IndiceLink::find()
+    .filter(indice_link::Column::IndiceId.is_in(indice_ids))
+    .order_by_asc(indice_link::Column::Order)
+    .all(conn)
+    .await?
+    .into_iter()
+    .filter_map(|link| link.indice_id.zip(link.field_id))
+    .into_group_map();
+    Ok(indices.iter().map(|indice| {
+        let fields = field_map.get(&indice.id).cloned().unwrap_or_default();
+        IndiceVo::build_from_indice(indice, Some(fields))
+    }).collect())
+}
+
+/// 新增索引字段的关联关系
+async fn add_indice_links(
+    tx: &DatabaseTransaction,
+    indice_id: &str,
+    field_ids: &[String]
+) -> Result<(), DrawDBError> {
+    if field_ids.is_empty() {
+        return Ok(());
+    }
+    let link_ids = next_ids(field_ids.len());
+    let link_ams = field_ids.iter().enumerate().map(|(index, field_id)| {
+        indice_link::ActiveModel::from(build_indice_link(link_ids[index].clone(), indice_id.to_string(), field_id.clone(), index as i32))
+    }).collect::<Vec<indice_link::ActiveModel>>();
+    IndiceLink::insert_many(link_ams).exec(tx).await?;
+    Ok(())
+}
+
 /// 新增索引
+/// 同时按顺序建立索引与字段的关联关系
 pub async fn add_indice(
     tx: &DatabaseTransaction,
     indice_vo: IndiceVo
@@ -9,25 +58,47 @@ pub async fn add_indice(
     let indice_model = indice_vo.convert_to_indice();
     let indice_active_model = IndiceActiveModel::from(indice_model);
     Indice::insert(indice_active_model).exec(tx).await?;
+    add_indice_links(tx, &indice_vo.id, &indice_vo.fields.unwrap_or_default()).await?;
     Ok(true)
 }
 
 /// 更新索引
+/// 重新建立索引与字段的关联关系
 pub async fn update_indice(
     tx: &DatabaseTransaction,
     indice_vo: IndiceVo
 ) -> Result<bool, DrawDBError> {
     let indice_model = indice_vo.convert_to_indice();
     let indice_active_model = IndiceActiveModel::from(indice_model);
-    Indice::update(indice_active_model).filter(Column::Id.eq(indice_vo.id)).exec(tx).await?;
+    Indice::update(indice_active_model.reset_all()).filter(Column::Id.eq(indice_vo.id.clone())).exec(tx).await?;
+    IndiceLink::delete_many().filter(indice_link::Column::IndiceId.eq(indice_vo.id.clone())).exec(tx).await?;
+    add_indice_links(tx, &indice_vo.id, &indice_vo.fields.unwrap_or_default()).await?;
     Ok(true)
 }
 
-/// 删除索引
+/// 删除索引及其与字段的关联关系
 pub async fn delete_indice(
     tx: &DatabaseTransaction,
     indice_vo: IndiceVo
 ) -> Result<bool, DrawDBError> {
+    IndiceLink::delete_many().filter(indice_link::Column::IndiceId.eq(indice_vo.id.clone())).exec(tx).await?;
     Indice::delete_many().filter(Column::Id.eq(indice_vo.id)).exec(tx).await?;
     Ok(true)
 }
+
+/// 删除表的所有索引
+pub async fn delete_table_indices(
+    tx: &DatabaseTransaction,
+    table_id: String
+) -> Result<u64, DrawDBError> {
+    let indice_ids = Indice::find()
+    .filter(Column::TableId.eq(table_id))
+    .all(tx)
+    .await?
+    .into_iter()
+    .map(|indice| indice.id)
+    .collect::<Vec<String>>();
+    IndiceLink::delete_many().filter(indice_link::Column::IndiceId.is_in(indice_ids.clone())).exec(tx).await?;
+    let result = Indice::delete_many().filter(Column::Id.is_in(indice_ids)).exec(tx).await?;
+    Ok(result.rows_affected)
+}
@@ -1,70 +1,260 @@
 use crate::error::DrawDBError;
+use crate::migrations::run_migrations;
 use once_cell::sync::OnceCell;
-use sea_orm::{ConnectionTrait, Database, DatabaseConnection, TransactionTrait};
+use sea_orm::{ConnectionTrait, Database, DatabaseConnection, DbBackend, Statement};
 use serde::{Deserialize, Serialize};
+use std::str::FromStr;
 use std::sync::RwLock;
 static SERVER_CONFIG: OnceCell<RwLock<ServerConfig>> = OnceCell::new();
+static DEPLOY_CONFIG: OnceCell<DeployConfig> = OnceCell::new();
 
-/// 初始化数据库
-pub async fn init_table(init_sql_path: &str, db: &DatabaseConnection) -> Result<(), DrawDBError> {
-    let init_sql = std::fs::read_to_string(init_sql_path)?;
-
-    // 按分号分割 SQL 语句
-    let statements: Vec<&str> = init_sql
-        .split(';')
-        .map(|s| s.trim())
-        .filter(|s| !s.is_empty())
-        .collect();
-
-    // 开始事务
-    let tx = db.begin().await?;
-
-    // 逐条执行 SQL 语句
-    for statement in statements {
-        tx.execute(sea_orm::Statement::from_sql_and_values(
-            sea_orm::DatabaseBackend::Sqlite,
-            statement,
-            vec![],
-        ))
-        .await?;
-    }
-
-    // 提交事务
-    tx.commit().await?;
-    Ok(())
-}
+/// 默认的配置文件路径，文件不存在时使用默认配置
+pub const DEFAULT_CONFIG_PATH: &str = "config.toml";
+/// 指定配置文件路径的环境变量
+const CONFIG_PATH_ENV: &str = "DRAWDB_CONFIG";
+/// app_state中记录初始化开关已执行的键
+const INITIALIZED_KEY: &str = "initialized_at";
 
 /// 配置文件结构体
-#[derive(Debug, Deserialize, Serialize, Clone)]
+/// 按默认值、配置文件、DRAWDB_*环境变量、命令行参数的顺序合并，后者覆盖前者
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq, Eq)]
+#[serde(default)]
 pub struct Config {
     pub database: DatabaseConfig,
     pub server: ServerConfig,
     pub options: OptionsConfig,
+    pub deploy: DeployConfig,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+/// 数据库配置
+/// backend为sqlite时使用path和test_path，postgres、mysql使用url
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+#[serde(default)]
 pub struct DatabaseConfig {
+    pub backend: StorageBackend,
     pub path: String,
-    pub init_sql_path: String,
     pub test_path: String,
+    pub url: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+impl Default for DatabaseConfig {
+    fn default() -> Self {
+        DatabaseConfig {
+            backend: StorageBackend::Sqlite,
+            path: "db.sqlite".to_string(),
+            test_path: "test.sqlite".to_string(),
+            url: None,
+        }
+    }
+}
+
+/// 存储后端，postgres和mysql需要启用同名的cargo特性
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum StorageBackend {
+    #[default]
+    Sqlite,
+    #[serde(alias = "postgresql")]
+    Postgres,
+    Mysql,
+}
+
+impl StorageBackend {
+    pub fn name(&self) -> &'static str {
+        match self {
+            StorageBackend::Sqlite => "sqlite",
+            StorageBackend::Postgres => "postgres",
+            StorageBackend::Mysql => "mysql",
+        }
+    }
+
+    /// 编译时是否启用了对应的驱动
+    pub fn enabled(&self) -> bool {
+        match self {
+            StorageBackend::Sqlite => true,
+            StorageBackend::Postgres => cfg!(feature = "postgres"),
+            StorageBackend::Mysql => cfg!(feature = "mysql"),
+        }
+    }
+
+    /// 连接地址允许的协议
+    fn schemes(&self) -> &'static [&'static str] {
+        match self {
+            StorageBackend::Sqlite => &["sqlite"],
+            StorageBackend::Postgres => &["postgres", "postgresql"],
+            StorageBackend::Mysql => &["mysql"],
+        }
+    }
+}
+
+impl FromStr for StorageBackend {
+    type Err = DrawDBError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "sqlite" => Ok(StorageBackend::Sqlite),
+            "postgres" | "postgresql" => Ok(StorageBackend::Postgres),
+            "mysql" => Ok(StorageBackend::Mysql),
+            other => Err(DrawDBError::ConfigError(format!("不支持的存储后端: {}，可选sqlite、postgres、mysql", other))),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+#[serde(default)]
 pub struct ServerConfig {
     pub port: u16,
     pub host: String,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+impl Default for ServerConfig {
+    fn default() -> Self {
+        ServerConfig {
+            port: 6666,
+            host: "127.0.0.1".to_string(),
+        }
+    }
+}
+
+/// 运行选项
+/// init_db为true时清空并重新创建数据库，执行后记录在数据库中，同一个数据库只执行一次
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq, Eq)]
+#[serde(default)]
 pub struct OptionsConfig {
     pub init_db: bool,
 }
 
-/// 读取配置文件config.toml
-/// 返回配置文件的配置全局变量
-pub fn read_config(config_path: &str) -> Config {
-    let config = toml::from_str::<Config>(&std::fs::read_to_string(config_path).unwrap()).unwrap();
-    config
+/// 部署配置
+/// allowed_paths为允许部署和导入的SQLite数据库文件或目录，目录下的文件都允许访问
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq, Eq)]
+pub struct DeployConfig {
+    #[serde(default)]
+    pub allowed_paths: Vec<String>,
+}
+
+/// 命令行参数指定的配置，优先级最高
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ConfigOverrides {
+    pub config_path: Option<String>,
+    pub database_path: Option<String>,
+    pub host: Option<String>,
+    pub port: Option<String>,
+    pub init_db: bool,
+}
+
+impl Config {
+    /// 读取并合并各层配置
+    /// 通过--config或DRAWDB_CONFIG指定的配置文件必须存在，默认的config.toml不存在时使用默认配置
+    pub fn load(overrides: &ConfigOverrides) -> Result<Config, DrawDBError> {
+        let env = std::env::vars().filter(|(key, _)| key.starts_with("DRAWDB_")).collect::<Vec<(String, String)>>();
+        let path = overrides.config_path.clone()
+        .or_else(|| env.iter().find(|(key, _)| key == CONFIG_PATH_ENV).map(|(_, value)| value.clone()));
+        let mut config = match &path {
+            Some(path) => Config::from_file(path)?,
+            None if std::path::Path::new(DEFAULT_CONFIG_PATH).exists() => Config::from_file(DEFAULT_CONFIG_PATH)?,
+            None => Config::default(),
+        };
+        config.apply_env(env)?;
+        config.apply_overrides(overrides)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// 读取配置文件，文件中没有的配置项使用默认值
+    pub fn from_file(path: &str) -> Result<Config, DrawDBError> {
+        let content = std::fs::read_to_string(path)
+        .map_err(|e| DrawDBError::ConfigError(format!("无法读取配置文件{}: {}", path, e)))?;
+        toml::from_str::<Config>(&content)
+        .map_err(|e| DrawDBError::ConfigError(format!("配置文件{}格式错误: {}", path, e)))
+    }
+
+    /// 环境变量覆盖配置，变量名为DRAWDB_加上大写的节名和键名，如DRAWDB_SERVER_PORT
+    /// allowed_paths用逗号分隔，未知的DRAWDB_*变量视为配置错误
+    pub fn apply_env(&mut self, vars: impl IntoIterator<Item = (String, String)>) -> Result<(), DrawDBError> {
+        for (key, value) in vars {
+            match key.as_str() {
+                CONFIG_PATH_ENV => {}
+                "DRAWDB_DATABASE_BACKEND" => self.database.backend = value.parse()?,
+                "DRAWDB_DATABASE_URL" => self.database.url = Some(value),
+                "DRAWDB_DATABASE_PATH" => self.database.path = value,
+                "DRAWDB_DATABASE_TEST_PATH" => self.database.test_path = value,
+                "DRAWDB_SERVER_HOST" => self.server.host = value,
+                "DRAWDB_SERVER_PORT" => self.server.port = parse_port(&key, &value)?,
+                "DRAWDB_OPTIONS_INIT_DB" => self.options.init_db = parse_bool(&key, &value)?,
+                "DRAWDB_DEPLOY_ALLOWED_PATHS" => self.deploy.allowed_paths = value.split(',')
+                    .map(str::trim)
+                    .filter(|path| !path.is_empty())
+                    .map(str::to_string)
+                    .collect(),
+                _ => return Err(DrawDBError::ConfigError(format!("未知的环境变量{}", key))),
+            }
+        }
+        Ok(())
+    }
+
+    /// 命令行参数覆盖配置
+    pub fn apply_overrides(&mut self, overrides: &ConfigOverrides) -> Result<(), DrawDBError> {
+        if let Some(path) = &overrides.database_path {
+            self.database.path = path.clone();
+        }
+        if let Some(host) = &overrides.host {
+            self.server.host = host.clone();
+        }
+        if let Some(port) = &overrides.port {
+            self.server.port = parse_port("--port", port)?;
+        }
+        if overrides.init_db {
+            self.options.init_db = true;
+        }
+        Ok(())
+    }
+
+    /// 校验合并后的配置
+    pub fn validate(&self) -> Result<(), DrawDBError> {
+        let backend = self.database.backend;
+        if !backend.enabled() {
+            return Err(DrawDBError::ConfigError(format!("存储后端{0}未编译，需要使用--features {0}构建", backend.name())));
+        }
+        if backend != StorageBackend::Sqlite {
+            let url = self.database.url.as_deref().unwrap_or_default();
+            if url.trim().is_empty() {
+                return Err(DrawDBError::ConfigError(format!("存储后端{}需要配置database.url", backend.name())));
+            }
+            let scheme = url.split_once("://").map(|(scheme, _)| scheme).unwrap_or_default();
+            if !backend.schemes().contains(&scheme) {
+                return Err(DrawDBError::ConfigError(format!("database.url的协议应为{}://", backend.schemes()[0])));
+            }
+            if self.options.init_db {
+                return Err(DrawDBError::ConfigError("options.init_db只支持SQLite".to_string()));
+            }
+        }
+        if self.database.path.trim().is_empty() {
+            return Err(DrawDBError::ConfigError("database.path不能为空".to_string()));
+        }
+        if self.database.test_path.trim().is_empty() {
+            return Err(DrawDBError::ConfigError("database.test_path不能为空".to_string()));
+        }
+        if self.server.host.trim().is_empty() {
+            return Err(DrawDBError::ConfigError("server.host不能为空".to_string()));
+        }
+        if self.server.port == 0 {
+            return Err(DrawDBError::ConfigError("server.port不能为0".to_string()));
+        }
+        Ok(())
+    }
+}
+
+fn parse_port(source: &str, value: &str) -> Result<u16, DrawDBError> {
+    value.trim().parse::<u16>()
+    .map_err(|_| DrawDBError::ConfigError(format!("{}不是有效的端口号: {}", source, value)))
+}
+
+fn parse_bool(source: &str, value: &str) -> Result<bool, DrawDBError> {
+    match value.trim().to_lowercase().as_str() {
+        "true" | "1" => Ok(true),
+        "false" | "0" => Ok(false),
+        _ => Err(DrawDBError::ConfigError(format!("{}应为true或false: {}", source, value))),
+    }
 }
 
 /// 获取服务器配置实例
@@ -72,47 +262,168 @@ pub fn get_config() -> &'static RwLock<ServerConfig> {
     SERVER_CONFIG.get().expect("Config not initialized")
 }
 
+/// 获取部署配置
+pub fn get_deploy_config() -> &'static DeployConfig {
+    DEPLOY_CONFIG.get().expect("Config not initialized")
+}
+
 /// 初始化全局配置
 /// mode: true 测试模式，false 生产模式
-pub async fn init(mode: bool) -> Result<Option<DatabaseConnection>, DrawDBError> {
-    let mut config = read_config("config.toml");
-    let server_config = config.server.clone();
+pub async fn init(mode: bool, config: Config) -> Result<Option<DatabaseConnection>, DrawDBError> {
     SERVER_CONFIG
-        .set(RwLock::new(server_config))
+        .set(RwLock::new(config.server.clone()))
         .expect("Failed to initialize config");
-    let path = if mode {
-        config.database.test_path.clone()
-    } else {
-        config.database.path.clone()
+    DEPLOY_CONFIG
+        .set(config.deploy.clone())
+        .expect("Failed to initialize config");
+    let db = match config.database.backend {
+        StorageBackend::Sqlite => {
+            let path = if mode {
+                config.database.test_path.clone()
+            } else {
+                config.database.path.clone()
+            };
+            open_database(&path, config.options.init_db).await?
+        }
+        // PostgreSQL和MySQL的数据库由管理员创建，启动时只执行迁移
+        backend => {
+            let url = config.database.url.as_deref()
+            .ok_or_else(|| DrawDBError::ConfigError(format!("存储后端{}需要配置database.url", backend.name())))?;
+            let db = Database::connect(url).await?;
+            run_migrations(&db).await?;
+            db
+        }
     };
-    // 如果数据库文件不存在或者初始化开关为true，则创建数据库文件
-    if !std::path::Path::new(&path).exists() || config.options.init_db {
-        // 创建数据库文件
-        std::fs::File::create(&path)?;
-    }
-    
-    // 配置连接池
-    let db = Database::connect(format!(
-        "sqlite://{}?",
-        &path,
-    )).await?;
-    
-    // 如果初始化开关为true，则初始化数据库
-    if config.options.init_db {
-        init_table(&config.database.init_sql_path, &db).await?;
-        // 初始化数据库成功
-        // 修改配置文件
-        config.options.init_db = false;
-        std::fs::write("config.toml", toml::to_string(&config).unwrap())?;
-    }
     Ok(Some(db))
 }
 
+/// 打开SQLite数据库并执行迁移，数据库文件不存在时创建
+/// init_db为true并且数据库中没有执行记录时，清空数据库后重新创建
+pub async fn open_database(path: &str, init_db: bool) -> Result<DatabaseConnection, DrawDBError> {
+    //1、连接数据库
+    let connect = || Database::connect(format!("sqlite://{}?mode=rwc", path));
+    let mut db = connect().await?;
+
+    //2、初始化开关只执行一次，执行记录保存在数据库中而不是修改配置文件
+    if init_db && initialized_at(&db).await?.is_none() {
+        db.close().await?;
+        std::fs::File::create(path)?;
+        db = connect().await?;
+    }
+
+    //3、执行未执行的迁移，新数据库和旧数据库都升级到最新结构
+    run_migrations(&db).await?;
+    if init_db {
+        db.execute(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            "INSERT OR IGNORE INTO app_state (key, value) VALUES (?, ?)",
+            vec![INITIALIZED_KEY.into(), chrono::Utc::now().to_rfc3339().into()],
+        )).await?;
+    }
+    Ok(db)
+}
+
+/// 初始化开关的执行时间，旧数据库还没有app_state表
+async fn initialized_at<C: ConnectionTrait>(conn: &C) -> Result<Option<String>, DrawDBError> {
+    let exists = conn.query_one(Statement::from_string(
+        DbBackend::Sqlite,
+        "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'app_state'",
+    )).await?.is_some();
+    if !exists {
+        return Ok(None);
+    }
+    let row = conn.query_one(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        "SELECT value FROM app_state WHERE key = ?",
+        vec![INITIALIZED_KEY.into()],
+    )).await?;
+    Ok(row.map(|row| row.try_get::<Option<String>>("", "value")).transpose()?.flatten())
+}
+
+/// 创建测试用的内存数据库并执行迁移
+#[cfg(test)]
+pub async fn init_test_db() -> DatabaseConnection {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    run_migrations(&db).await.unwrap();
+    db
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
     #[actix_web::test]
     async fn test_init() {
-        init(true).await.unwrap();
+        let config = Config::from_file(DEFAULT_CONFIG_PATH).unwrap();
+        init(true, config).await.unwrap();
+    }
+
+    /// 配置文件中缺少的项使用默认值，环境变量覆盖配置文件，命令行参数覆盖环境变量
+    #[test]
+    fn test_config_layers() {
+        let mut config: Config = toml::from_str("[server]\nport = 7000").unwrap();
+        assert_eq!(config.server.port, 7000);
+        assert_eq!(config.server.host, "127.0.0.1");
+        assert_eq!(config.database, DatabaseConfig::default());
+
+        let env = |key: &str, value: &str| (key.to_string(), value.to_string());
+        config.apply_env(vec![
+            env("DRAWDB_SERVER_HOST", "0.0.0.0"),
+            env("DRAWDB_SERVER_PORT", "8000"),
+            env("DRAWDB_OPTIONS_INIT_DB", "1"),
+            env("DRAWDB_DEPLOY_ALLOWED_PATHS", "/data, /tmp/a.sqlite,"),
+        ]).unwrap();
+        assert_eq!((config.server.host.as_str(), config.server.port, config.options.init_db), ("0.0.0.0", 8000, true));
+        assert_eq!(config.deploy.allowed_paths, vec!["/data", "/tmp/a.sqlite"]);
+
+        config.apply_overrides(&ConfigOverrides {
+            port: Some("9000".to_string()),
+            database_path: Some("/data/db.sqlite".to_string()),
+            ..Default::default()
+        }).unwrap();
+        assert_eq!((config.server.port, config.database.path.as_str()), (9000, "/data/db.sqlite"));
+        config.validate().unwrap();
+
+        // 错误的值
+        let error = config.clone().apply_env(vec![env("DRAWDB_SERVER_PORT", "http")]).unwrap_err();
+        assert!(matches!(error, DrawDBError::ConfigError(message) if message.contains("DRAWDB_SERVER_PORT")));
+        assert!(config.clone().apply_env(vec![env("DRAWDB_SERVER_PROT", "1")]).is_err());
+        let mut invalid = config.clone();
+        invalid.apply_overrides(&ConfigOverrides { port: Some("0".to_string()), ..Default::default() }).unwrap();
+        assert!(invalid.validate().is_err());
+        assert!(matches!(Config::from_file("missing.toml"), Err(DrawDBError::ConfigError(_))));
+
+        // 存储后端
+        let mut postgres = Config::default();
+        postgres.apply_env(vec![env("DRAWDB_DATABASE_BACKEND", "postgresql")]).unwrap();
+        assert_eq!(postgres.database.backend, StorageBackend::Postgres);
+        assert!(postgres.validate().is_err());
+        postgres.apply_env(vec![env("DRAWDB_DATABASE_URL", "mysql://localhost/drawdb")]).unwrap();
+        assert!(postgres.validate().is_err());
+        postgres.apply_env(vec![env("DRAWDB_DATABASE_URL", "postgres://localhost/drawdb")]).unwrap();
+        assert_eq!(postgres.validate().is_ok(), cfg!(feature = "postgres"));
+        assert!(Config::default().apply_env(vec![env("DRAWDB_DATABASE_BACKEND", "oracle")]).is_err());
     }
-}
\ No newline at end of file
+
+    /// 初始化开关清空数据库后记录在数据库中，再次启动时不再清空
+    #[actix_web::test]
+    async fn test_open_database() {
+        let path = std::env::temp_dir().join(format!("drawdb_{}.sqlite", crate::next_id()));
+        let path = path.to_string_lossy().to_string();
+        let count = |db: DatabaseConnection| async move {
+            let row = db.query_one(Statement::from_string(DbBackend::Sqlite, "SELECT COUNT(*) AS count FROM diagram")).await.unwrap().unwrap();
+            db.close().await.unwrap();
+            row.try_get::<i64>("", "count").unwrap()
+        };
+        let insert = "INSERT INTO diagram (id, name) VALUES ('1', 'shop')";
+
+        let db = open_database(&path, false).await.unwrap();
+        db.execute_unprepared(insert).await.unwrap();
+        assert_eq!(count(db).await, 1);
+        let db = open_database(&path, true).await.unwrap();
+        assert!(initialized_at(&db).await.unwrap().is_some());
+        db.execute_unprepared(insert).await.unwrap();
+        assert_eq!(count(db).await, 1);
+        assert_eq!(count(open_database(&path, true).await.unwrap()).await, 1);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -1,8 +1,10 @@
 use actix_web::{get, web, App, HttpServer, Responder};
+mod cli;
 mod common;
 mod entity;
 mod error;
 mod init;
+mod migrations;
 mod todos;
 mod diagrams;
 mod references;
@@ -11,8 +13,17 @@ mod notes;
 mod tables;
 mod indices;
 mod fields;
+mod export;
+mod import;
+mod validate;
+mod datatypes;
+mod versions;
+mod diff;
+mod deploy;
+use cli::{Cli, Command, USAGE};
 use error::DrawDBError;
-use init::{get_config, init};
+use sea_orm::DatabaseConnection;
+use init::{get_config, get_deploy_config, init, Config};
 use tracing_subscriber::fmt;
 use std::result::Result;
 use snowflake::{SnowflakeIdGenerator};
@@ -56,22 +67,51 @@ fn init_log() {
 #[actix_web::main]
 async fn main() -> Result<(), DrawDBError> {
     init_log();
-    let db = init(false).await?;
+    let Cli { command, overrides } = Cli::parse(std::env::args().skip(1))?;
+    if command == Command::Help {
+        println!("{}", USAGE);
+        return Ok(());
+    }
+    let config = Config::load(&overrides)?;
+    let db = init(false, config).await?.ok_or_else(|| DrawDBError::OtherError("数据库未初始化".to_string()))?;
+    match command {
+        Command::ImportSqlite { path, name } => {
+            let saved = import::import_sqlite_file(&db, std::path::Path::new(&path), name).await?;
+            println!(
+                "已导入图表{}：{}张表，{}个关联关系",
+                saved.diagram.id,
+                saved.diagram.tables.as_ref().map_or(0, Vec::len),
+                saved.diagram.references.as_ref().map_or(0, Vec::len)
+            );
+            Ok(())
+        }
+        Command::Serve | Command::Help => serve(db).await,
+    }
+}
+
+/// 启动HTTP服务
+async fn serve(db: DatabaseConnection) -> Result<(), DrawDBError> {
     let server_config = get_config();
-    let config = server_config
-        .read()
-        .map_err(|e| DrawDBError::OtherError(e.to_string()))?;
-    let host = config.host.clone();
-    let port = config.port.clone();
+    let (host, port) = {
+        let config = server_config
+            .read()
+            .map_err(|e| DrawDBError::OtherError(e.to_string()))?;
+        (config.host.clone(), config.port)
+    };
+    let deploy_config = get_deploy_config().clone();
 
     HttpServer::new(move || {
         App::new()
-            .app_data(web::Data::new(db.clone().unwrap()))
+            .app_data(web::Data::new(db.clone()))
+            .app_data(web::Data::new(deploy_config.clone()))
             .service(hello)
             .route("/", web::get().to(index))
             .service(web::scope("/todos").configure(todos::todos_routes))
             .service(web::scope("/tables").configure(tables::tables_routes))
-            .service(web::scope("/diagrams").configure(diagrams::diagrams_routes))
+            .service(web::scope("/diagrams").configure(diagrams::diagrams_routes).configure(export::export_routes).configure(import::import_routes).configure(validate::validate_routes).configure(versions::versions_routes).configure(diff::diff_routes).configure(deploy::deploy_routes))
+            .service(web::scope("/notes").configure(notes::notes_routes))
+            .service(web::scope("/areas").configure(areas::areas_routes))
+            .service(web::scope("/datatypes").configure(datatypes::datatypes_routes))
      
     })
     .bind(format!("{}:{}", host, port))?
@@ -0,0 +1,292 @@
+use std::collections::HashSet;
+use sea_orm::sea_query::{Alias, ColumnDef, Query, Table};
+use sea_orm::{ConnectionTrait, DatabaseConnection, DbBackend, Statement, TransactionTrait};
+use crate::error::DrawDBError;
+
+/// 后端数据库的结构迁移，脚本在编译时嵌入
+/// 按版本号顺序执行，执行过的版本记录在schema_migrations表中
+/// 每个版本分别有SQLite、PostgreSQL、MySQL的脚本，空脚本只记录版本
+pub struct SchemaMigration {
+    pub version: i64,
+    pub name: &'static str,
+    pub sqlite: &'static str,
+    pub postgres: &'static str,
+    pub mysql: &'static str,
+    // 没有迁移记录的旧SQLite数据库中，查询有结果说明该版本的结构已经存在
+    probe: &'static str,
+}
+
+impl SchemaMigration {
+    /// 对应数据库的脚本
+    pub fn sql(&self, backend: DbBackend) -> &'static str {
+        match backend {
+            DbBackend::Sqlite => self.sqlite,
+            DbBackend::Postgres => self.postgres,
+            DbBackend::MySql => self.mysql,
+        }
+    }
+}
+
+/// 全部迁移，新增迁移时追加到末尾，已发布的脚本不能修改
+/// PostgreSQL和MySQL从第6版开始支持，第1版直接创建第6版的完整结构，第2到6版为空
+/// init.sql保留最新的完整结构供查阅，由测试校验与SQLite迁移的结果一致
+pub const MIGRATIONS: [SchemaMigration; 6] = [
+    SchemaMigration {
+        version: 1,
+        name: "init",
+        sqlite: include_str!("../../migrations/sqlite/0001_init.sql"),
+        postgres: include_str!("../../migrations/postgres/0001_init.sql"),
+        mysql: include_str!("../../migrations/mysql/0001_init.sql"),
+        probe: "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'diagram'",
+    },
+    SchemaMigration {
+        version: 2,
+        name: "entity_column_types",
+        sqlite: include_str!("../../migrations/sqlite/0002_entity_column_types.sql"),
+        postgres: "",
+        mysql: "",
+        probe: "SELECT 1 FROM pragma_table_info('diagram_link') WHERE name = 'reference_id'",
+    },
+    SchemaMigration {
+        version: 3,
+        name: "indice_table_and_order",
+        sqlite: include_str!("../../migrations/sqlite/0003_indice_table_and_order.sql"),
+        postgres: "",
+        mysql: "",
+        probe: "SELECT 1 FROM pragma_table_info('indice') WHERE name = 'table_id'",
+    },
+    SchemaMigration {
+        version: 4,
+        name: "custom_types_and_enums",
+        sqlite: include_str!("../../migrations/sqlite/0004_custom_types_and_enums.sql"),
+        postgres: "",
+        mysql: "",
+        probe: "SELECT 1 FROM pragma_table_info('diagram') WHERE name = 'types'",
+    },
+    SchemaMigration {
+        version: 5,
+        name: "diagram_version",
+        sqlite: include_str!("../../migrations/sqlite/0005_diagram_version.sql"),
+        postgres: "",
+        mysql: "",
+        probe: "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'diagram_version'",
+    },
+    SchemaMigration {
+        version: 6,
+        name: "app_state",
+        sqlite: include_str!("../../migrations/sqlite/0006_app_state.sql"),
+        postgres: "",
+        mysql: "",
+        probe: "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'app_state'",
+    },
+];
+
+/// 执行尚未执行的迁移，返回本次执行的版本号
+/// 每个迁移脚本整体在一个事务中执行并记录版本，失败时回滚该版本
+/// MySQL的DDL会隐式提交，失败时需要手动处理已执行的部分
+pub async fn run_migrations(db: &DatabaseConnection) -> Result<Vec<i64>, DrawDBError> {
+    let backend = db.get_database_backend();
+    //1、创建迁移记录表
+    let table = Table::create()
+    .table(Alias::new("schema_migrations"))
+    .if_not_exists()
+    .col(ColumnDef::new(Alias::new("version")).big_integer().not_null().primary_key())
+    .col(ColumnDef::new(Alias::new("name")).string().not_null())
+    .col(ColumnDef::new(Alias::new("applied_at")).string().not_null())
+    .to_owned();
+    db.execute(backend.build(&table)).await?;
+    let mut applied = applied_versions(db).await?;
+
+    //2、由init.sql创建的旧SQLite数据库没有迁移记录，按探测结果补记已有结构对应的版本
+    if applied.is_empty() && backend == DbBackend::Sqlite {
+        for migration in MIGRATIONS.iter() {
+            let rows = db.query_all(Statement::from_string(backend, migration.probe)).await?;
+            if rows.is_empty() {
+                break;
+            }
+            record(db, migration).await?;
+            applied.insert(migration.version);
+            tracing::info!("数据库已有迁移{}的结构，补记版本", migration.version);
+        }
+    }
+
+    //3、按顺序执行未执行的迁移
+    let mut executed = Vec::new();
+    for migration in MIGRATIONS.iter().filter(|migration| !applied.contains(&migration.version)) {
+        let tx = db.begin().await?;
+        let sql = migration.sql(backend);
+        if !sql.trim().is_empty() {
+            tx.execute_unprepared(sql).await?;
+        }
+        record(&tx, migration).await?;
+        tx.commit().await?;
+        tracing::info!("执行数据库迁移{}: {}", migration.version, migration.name);
+        executed.push(migration.version);
+    }
+    Ok(executed)
+}
+
+/// 已执行的迁移版本
+pub async fn applied_versions<C: ConnectionTrait>(conn: &C) -> Result<HashSet<i64>, DrawDBError> {
+    let query = Query::select()
+    .column(Alias::new("version"))
+    .from(Alias::new("schema_migrations"))
+    .to_owned();
+    let rows = conn.query_all(conn.get_database_backend().build(&query)).await?;
+    rows.iter()
+    .map(|row| Ok(row.try_get::<i64>("", "version")?))
+    .collect()
+}
+
+async fn record<C: ConnectionTrait>(conn: &C, migration: &SchemaMigration) -> Result<(), DrawDBError> {
+    let insert = Query::insert()
+    .into_table(Alias::new("schema_migrations"))
+    .columns([Alias::new("version"), Alias::new("name"), Alias::new("applied_at")])
+    .values_panic([migration.version.into(), migration.name.into(), chrono::Utc::now().to_rfc3339().into()])
+    .to_owned();
+    conn.execute(conn.get_database_backend().build(&insert)).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use sea_orm::Database;
+    use super::*;
+
+    /// 表的列和索引，列按名称排序，ADD COLUMN追加的列与init.sql中的位置不同
+    async fn schema(conn: &DatabaseConnection) -> Vec<String> {
+        let sql = r#"
+            SELECT m.name || '.' || p.name || ' ' || p.type || ' ' || p."notnull" || ' ' || p.pk AS line
+            FROM sqlite_master m JOIN pragma_table_info(m.name) p
+            WHERE m.type = 'table' AND m.name <> 'schema_migrations'
+            UNION ALL
+            SELECT 'index ' || name || ' ON ' || tbl_name FROM sqlite_master
+            WHERE type = 'index' AND name NOT LIKE 'sqlite_%'
+            ORDER BY 1"#;
+        conn.query_all(Statement::from_string(DbBackend::Sqlite, sql)).await.unwrap()
+        .iter()
+        .map(|row| row.try_get::<String>("", "line").unwrap())
+        .collect()
+    }
+
+    /// 新数据库执行全部迁移后与init.sql的结构一致，再次执行没有变化
+    #[actix_web::test]
+    async fn test_run_migrations() {
+        let db = Database::connect("sqlite::memory:").await.unwrap();
+        assert_eq!(run_migrations(&db).await.unwrap(), vec![1, 2, 3, 4, 5, 6]);
+        assert!(run_migrations(&db).await.unwrap().is_empty());
+        assert_eq!(applied_versions(&db).await.unwrap().len(), MIGRATIONS.len());
+
+        let expected = Database::connect("sqlite::memory:").await.unwrap();
+        expected.execute_unprepared(include_str!("../../init.sql")).await.unwrap();
+        assert_eq!(schema(&db).await, schema(&expected).await);
+        // 由当前init.sql创建的旧数据库补记全部版本
+        assert!(run_migrations(&expected).await.unwrap().is_empty());
+        assert_eq!(applied_versions(&expected).await.unwrap().len(), MIGRATIONS.len());
+    }
+
+    /// 最初的init.sql创建并写入数据的旧数据库，补记第1版后升级并保留数据
+    #[actix_web::test]
+    async fn test_upgrade_legacy_database() {
+        let db = Database::connect("sqlite::memory:").await.unwrap();
+        db.execute_unprepared(MIGRATIONS[0].sqlite).await.unwrap();
+        db.execute_unprepared(r#"
+            INSERT INTO "diagram_link" ("id", "diagram_id", "reference") VALUES ('1', '2', '3');
+            INSERT INTO "table" ("id", "name", "x", "y") VALUES ('4', 'users', 12.5, 40);
+            INSERT INTO "field" ("id", "name", "size") VALUES ('5', 'email', '255'), ('6', 'age', '');
+        "#).await.unwrap();
+        assert_eq!(run_migrations(&db).await.unwrap(), vec![2, 3, 4, 5, 6]);
+
+        let row = |sql: &'static str| {
+            let db = &db;
+            async move { db.query_one(Statement::from_string(DbBackend::Sqlite, sql)).await.unwrap().unwrap() }
+        };
+        assert_eq!(row("SELECT reference_id FROM diagram_link").await.try_get::<String>("", "reference_id").unwrap(), "3");
+        let table = row("SELECT x, y FROM \"table\"").await;
+        assert_eq!(table.try_get::<String>("", "x").unwrap(), "12.5");
+        assert_eq!(table.try_get::<String>("", "y").unwrap(), "40");
+        let sizes = db.query_all(Statement::from_string(DbBackend::Sqlite, "SELECT size FROM field ORDER BY id")).await.unwrap()
+        .iter()
+        .map(|row| row.try_get::<Option<i32>>("", "size").unwrap())
+        .collect::<Vec<Option<i32>>>();
+        assert_eq!(sizes, vec![Some(255), None]);
+    }
+
+    /// PostgreSQL和MySQL在启用特性并设置测试地址时执行，建表后通过导入接口读写各实体
+    #[cfg(any(feature = "postgres", feature = "mysql"))]
+    mod server {
+        use actix_web::{test, web, App};
+        use sea_orm::{ConnectOptions, Database, DatabaseConnection};
+        use super::super::*;
+        use crate::common::CommonResponse;
+        use crate::diagrams::query_diagram_detail;
+        use crate::entity::vo::DiagramSaveVo;
+        use crate::import::import_routes;
+
+        async fn check_backend(db: DatabaseConnection) {
+            assert_eq!(run_migrations(&db).await.unwrap(), vec![1, 2, 3, 4, 5, 6]);
+            assert!(run_migrations(&db).await.unwrap().is_empty());
+            let app = test::init_service(
+                App::new()
+                    .app_data(web::Data::new(db.clone()))
+                    .configure(import_routes)
+            ).await;
+            let sql = r#"
+                CREATE TABLE "users" (
+                    "id" INTEGER PRIMARY KEY AUTOINCREMENT,
+                    "email" VARCHAR(255) NOT NULL UNIQUE DEFAULT 'nobody'
+                );
+                CREATE TABLE "orders" (
+                    "id" INTEGER NOT NULL PRIMARY KEY,
+                    "user_id" INTEGER REFERENCES "users" ("id") ON DELETE CASCADE,
+                    "price" DECIMAL(10,2)
+                );
+                CREATE INDEX "idx_orders_user" ON "orders" ("user_id");
+            "#;
+            let req = test::TestRequest::post().uri("/import/sql?dialect=sqlite").set_payload(sql).to_request();
+            let resp: CommonResponse = test::call_and_read_body_json(&app, req).await;
+            assert_eq!(resp.code, 200);
+            let saved: DiagramSaveVo = serde_json::from_value(resp.data.unwrap()).unwrap();
+            let diagram = query_diagram_detail(&db, saved.diagram.id).await.unwrap().unwrap();
+            let tables = diagram.tables.unwrap();
+            assert_eq!(tables.len(), 2);
+            let users = tables.iter().find(|table| table.name.as_deref() == Some("users")).unwrap();
+            let email = users.fields.as_ref().unwrap().iter().find(|field| field.name.as_deref() == Some("email")).unwrap();
+            assert_eq!((email.size, email.unique, email.default.as_deref()), (Some(255), Some(true), Some("nobody")));
+            let orders = tables.iter().find(|table| table.name.as_deref() == Some("orders")).unwrap();
+            assert_eq!(orders.indices.as_ref().unwrap().len(), 1);
+            assert_eq!(diagram.references.unwrap().len(), 1);
+        }
+
+        /// POSTGRES_TEST_URL如postgres://postgres@127.0.0.1:5432/postgres，在临时schema中建表
+        #[cfg(feature = "postgres")]
+        #[actix_web::test]
+        async fn test_postgres_migrations() {
+            let Ok(url) = std::env::var("POSTGRES_TEST_URL") else {
+                return;
+            };
+            let schema = format!("drawdb_{}", crate::next_id());
+            let admin = Database::connect(&url).await.unwrap();
+            admin.execute_unprepared(&format!("CREATE SCHEMA \"{}\"", schema)).await.unwrap();
+            let mut options = ConnectOptions::new(url);
+            options.set_schema_search_path(schema.clone());
+            check_backend(Database::connect(options).await.unwrap()).await;
+            admin.execute_unprepared(&format!("DROP SCHEMA \"{}\" CASCADE", schema)).await.unwrap();
+        }
+
+        /// MYSQL_TEST_URL不带数据库名，如mysql://root@127.0.0.1:3306，在临时数据库中建表
+        #[cfg(feature = "mysql")]
+        #[actix_web::test]
+        async fn test_mysql_migrations() {
+            let Ok(url) = std::env::var("MYSQL_TEST_URL") else {
+                return;
+            };
+            let database = format!("drawdb_{}", crate::next_id());
+            let admin = Database::connect(&url).await.unwrap();
+            admin.execute_unprepared(&format!("CREATE DATABASE `{}`", database)).await.unwrap();
+            let db = Database::connect(format!("{}/{}", url.trim_end_matches('/'), database)).await.unwrap();
+            check_backend(db).await;
+            admin.execute_unprepared(&format!("DROP DATABASE `{}`", database)).await.unwrap();
+        }
+    }
+}
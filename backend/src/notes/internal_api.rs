@@ -1,16 +1,44 @@
-use sea_orm::{ColumnTrait, DatabaseTransaction, EntityTrait, QueryFilter};
+use sea_orm::{ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseTransaction, EntityTrait, JoinType, QueryFilter, QuerySelect, RelationTrait};
+use crate::entity::{diagram_link, prelude::DiagramLink};
 use crate::entity::vo::NoteVo;
+use crate::next_id;
 use crate::{entity::note::{ActiveModel as NoteActiveModel}, error::DrawDBError};
-use crate::entity::note::{Column, Entity as Note};
+use crate::entity::note::{Column, Entity as Note, Relation as NoteRelation};
+
+/// 查询图表关联的注释
+pub async fn query_notes<C: ConnectionTrait>(
+    conn: &C,
+    diagram_id: String
+) -> Result<Vec<NoteVo>, DrawDBError> {
+    let notes = Note::find()
+    .join(JoinType::InnerJoin, NoteRelation::DiagramLink.def())
+    .filter(diagram_link::Column::DiagramId.eq(diagram_id))
+    .all(conn)
+    .await?;
+    Ok(notes.iter().map(NoteVo::from).collect())
+}
+
 /// 新增注释
+/// 同时建立图表与注释的关联关系
 pub async fn add_note(
     tx: &DatabaseTransaction,
+    diagram_id: String,
     note_vo: NoteVo
-) -> Result<bool, DrawDBError> {
+) -> Result<String, DrawDBError> {
     let note_model = note_vo.convert_to_note();
     let note_active_model = NoteActiveModel::from(note_model);
     Note::insert(note_active_model).exec(tx).await?;
-    Ok(true)
+    let diagram_link = diagram_link::Model::new(
+        next_id(),
+        Some(diagram_id),
+        None,
+        None,
+        None,
+        Some(note_vo.id.clone()),
+        None
+    );
+    DiagramLink::insert(diagram_link::ActiveModel::from(diagram_link)).exec(tx).await?;
+    Ok(note_vo.id)
 }
 
 /// 更新注释
@@ -20,15 +48,21 @@ pub async fn update_note(
 ) -> Result<bool, DrawDBError> {
     let note_model = note_vo.convert_to_note();
     let note_active_model = NoteActiveModel::from(note_model);
-    Note::update(note_active_model).filter(Column::Id.eq(note_vo.id)).exec(tx).await?;
-    Ok(true)
+    let result = Note::update_many()
+    .set(note_active_model.reset_all())
+    .filter(Column::Id.eq(note_vo.id))
+    .exec(tx)
+    .await?;
+    Ok(result.rows_affected > 0)
 }
 
 /// 删除注释
+/// 同时删除图表与注释的关联关系
 pub async fn delete_note(
     tx: &DatabaseTransaction,
-    note_vo: NoteVo
+    note_id: String
 ) -> Result<bool, DrawDBError> {
-    Note::delete_many().filter(Column::Id.eq(note_vo.id)).exec(tx).await?;
-    Ok(true)
+    DiagramLink::delete_many().filter(diagram_link::Column::NoteId.eq(note_id.clone())).exec(tx).await?;
+    let result = Note::delete_many().filter(Column::Id.eq(note_id)).exec(tx).await?;
+    Ok(result.rows_affected > 0)
 }
@@ -1,3 +1,162 @@
 mod internal_api;
 
 pub use internal_api::*;
+use actix_web::{delete, get, post, web};
+use sea_orm::{DatabaseConnection, TransactionTrait};
+use crate::common::{CommonResponse, ResponseCode, ResponseMessage};
+use crate::entity::vo::{NoteAddVo, NoteVo};
+use crate::error::DrawDBError;
+use crate::next_id;
+
+/// 注释模块
+pub fn notes_routes(config: &mut web::ServiceConfig) {
+    config.service(query);
+    config.service(add);
+    config.service(update);
+    config.service(delete);
+}
+
+/// 根据diagram_id获取关联的注释
+#[get("/query/{diagram_id}")]
+async fn query(
+    db: web::Data<DatabaseConnection>,
+    diagram_id: web::Path<String>
+) -> Result<CommonResponse, DrawDBError> {
+    let result = query_notes(db.get_ref(), diagram_id.into_inner()).await?;
+    Ok(CommonResponse::new(
+        ResponseCode::Success,
+        ResponseMessage::Success,
+        Some(serde_json::to_value(result).unwrap()),
+    ))
+}
+
+/// 新增注释
+#[post("/add")]
+async fn add(
+    db: web::Data<DatabaseConnection>,
+    note: web::Json<NoteAddVo>
+) -> Result<CommonResponse, DrawDBError> {
+    let tx = db.begin().await?;
+    let note = note.into_inner();
+    let note_id = add_note(&tx, note.diagram_id.clone(), note.convert_to_note_vo(next_id())).await?;
+    tx.commit().await?;
+    Ok(CommonResponse::new(
+        ResponseCode::Success,
+        ResponseMessage::Success,
+        Some(serde_json::to_value(note_id).unwrap()),
+    ))
+}
+
+/// 更新注释
+#[post("/update")]
+async fn update(
+    db: web::Data<DatabaseConnection>,
+    note: web::Json<NoteVo>
+) -> Result<CommonResponse, DrawDBError> {
+    let tx = db.begin().await?;
+    let note = note.into_inner();
+    let note_id = note.id.clone();
+    if !update_note(&tx, note).await? {
+        tx.rollback().await?;
+        return Ok(CommonResponse::new(ResponseCode::NotFound, ResponseMessage::NotFound, None));
+    }
+    tx.commit().await?;
+    Ok(CommonResponse::new(
+        ResponseCode::Success,
+        ResponseMessage::Success,
+        Some(serde_json::to_value(note_id).unwrap()),
+    ))
+}
+
+/// 删除注释
+#[delete("/delete/{id}")]
+async fn delete(
+    db: web::Data<DatabaseConnection>,
+    id: web::Path<String>
+) -> Result<CommonResponse, DrawDBError> {
+    let tx = db.begin().await?;
+    let id = id.into_inner();
+    if !delete_note(&tx, id.clone()).await? {
+        tx.rollback().await?;
+        return Ok(CommonResponse::new(ResponseCode::NotFound, ResponseMessage::NotFound, None));
+    }
+    tx.commit().await?;
+    Ok(CommonResponse::new(
+        ResponseCode::Success,
+        ResponseMessage::Success,
+        Some(serde_json::to_value(id).unwrap()),
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use actix_web::{test, web, App};
+    use sea_orm::EntityTrait;
+    use serde_json::json;
+
+    use super::*;
+    use crate::entity::prelude::*;
+    use crate::init::init_test_db;
+
+    /// 注释的增删改查
+    #[actix_web::test]
+    async fn test_notes_crud() {
+        let db = init_test_db().await;
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(db.clone()))
+                .configure(notes_routes)
+        ).await;
+        // 新增注释
+        let req = test::TestRequest::post()
+            .uri("/add")
+            .set_json(json!({
+                "diagram_id": "1",
+                "content": "test",
+                "color": "#fff",
+                "title": "test",
+                "height": "88",
+                "x": "10",
+                "y": "20"
+            }))
+            .to_request();
+        let resp: CommonResponse = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(resp.code, 200);
+        let note_id: String = serde_json::from_value(resp.data.unwrap()).unwrap();
+        assert_eq!(DiagramLink::find().all(&db).await.unwrap()[0].note_id, Some(note_id.clone()));
+
+        // 更新注释
+        let req = test::TestRequest::post()
+            .uri("/update")
+            .set_json(json!({
+                "id": note_id,
+                "content": "changed",
+                "color": "#fff",
+                "title": "test",
+                "height": "88",
+                "x": "10",
+                "y": "20"
+            }))
+            .to_request();
+        let resp: CommonResponse = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(resp.code, 200);
+
+        // 查询图表关联的注释
+        let req = test::TestRequest::get().uri("/query/1").to_request();
+        let resp: CommonResponse = test::call_and_read_body_json(&app, req).await;
+        let notes: Vec<NoteVo> = serde_json::from_value(resp.data.unwrap()).unwrap();
+        assert_eq!(notes.len(), 1);
+        assert_eq!(notes[0].content, Some("changed".to_string()));
+
+        // 删除注释
+        let req = test::TestRequest::delete().uri(&format!("/delete/{}", note_id)).to_request();
+        let resp: CommonResponse = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(resp.code, 200);
+        assert!(DiagramLink::find().all(&db).await.unwrap().is_empty());
+        assert!(Note::find().all(&db).await.unwrap().is_empty());
+
+        let req = test::TestRequest::delete().uri(&format!("/delete/{}", note_id)).to_request();
+        let resp: CommonResponse = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(resp.code, 404);
+    }
+}
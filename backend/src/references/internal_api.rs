@@ -1,10 +1,24 @@
-use sea_orm::{ColumnTrait, DatabaseTransaction, EntityTrait, QueryFilter};
+use sea_orm::{ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseTransaction, EntityTrait, JoinType, QueryFilter, QuerySelect, RelationTrait};
 
 use crate::{entity::vo::ReferenceVo, error::DrawDBError};
-use crate::entity::reference::{Column, Model as ReferenceModel};
+use crate::entity::diagram_link;
+use crate::entity::reference::{Column, Model as ReferenceModel, Relation as ReferenceRelation};
 use crate::entity::reference::ActiveModel as ReferenceActiveModel;
 use crate::entity::reference::Entity as Reference;
 
+/// 查询图表关联的引用
+pub async fn query_references<C: ConnectionTrait>(
+    conn: &C,
+    diagram_id: String
+) -> Result<Vec<ReferenceVo>, DrawDBError> {
+    let references = Reference::find()
+    .join(JoinType::InnerJoin, ReferenceRelation::DiagramLink.def())
+    .filter(diagram_link::Column::DiagramId.eq(diagram_id))
+    .all(conn)
+    .await?;
+    Ok(references.iter().map(ReferenceVo::from).collect())
+}
+
 /// 批量新增引用
 pub async fn add_references(
     tx: &DatabaseTransaction,
@@ -16,12 +30,24 @@ pub async fn add_references(
     .collect::<Vec<ReferenceModel>>();
     let references_active_models = references_models
     .into_iter()
-    .map(|model| ReferenceActiveModel::from(model))
+    .map(ReferenceActiveModel::from)
     .collect::<Vec<ReferenceActiveModel>>();
     Reference::insert_many(references_active_models).exec(tx).await?;
     Ok(true)
 }
 
+/// 批量更新引用
+pub async fn update_references(
+    tx: &DatabaseTransaction,
+    reference_vos: Vec<ReferenceVo>
+) -> Result<bool, DrawDBError> {
+    for vo in reference_vos {
+        let reference_active_model = ReferenceActiveModel::from(vo.convert_to_reference()).reset_all();
+        Reference::update(reference_active_model).filter(Column::Id.eq(vo.id)).exec(tx).await?;
+    }
+    Ok(true)
+}
+
 /// 批量删除引用
 pub async fn delete_references(
     tx: &DatabaseTransaction,
@@ -1,22 +1,24 @@
 use std::collections::HashMap;
 
-use actix_web::web;
 use itertools::Itertools;
-use sea_orm::{ActiveValue, ColumnTrait, DatabaseConnection, DatabaseTransaction, EntityTrait, Iterable, JoinType, QueryFilter, QuerySelect, RelationTrait};
+use sea_orm::{ActiveModelTrait, ActiveValue, ColumnTrait, Condition, ConnectionTrait, DatabaseTransaction, EntityTrait, Iterable, JoinType, QueryFilter, QuerySelect, RelationTrait};
 use crate::entity::dto::FieldWithTable;
 use crate::entity::table::Relation as TableRelation;
-use crate::entity::vo::{build_table_link, FieldVo};
-use crate::entity::{diagram_link, field, prelude::*, table, table_link};
+use crate::diagrams::{add_diagram_links, diff_by_id};
+use crate::datatypes::DataTypes;
+use crate::entity::vo::{build_table_link, DiagramVo, FieldVo, IdMappingVo, IndiceVo, ReferenceVo};
+use crate::entity::{diagram_link, field, indice_link, prelude::*, reference, table, table_link};
+use crate::indices::{add_indice, delete_indice, delete_table_indices, query_indices, update_indice};
 use crate::next_ids;
+use crate::references::add_references;
 use crate::{entity::vo::TableVo, error::DrawDBError, next_id};
 
 
 /// 查询关联表结构的方法
-pub async fn query_tables(
-    db: web::Data<DatabaseConnection>,
+pub async fn query_tables<C: ConnectionTrait>(
+    conn: &C,
     diagram_id: String
 ) -> Result<Vec<TableVo>, DrawDBError> {
-    let conn = db.get_ref();
     let table_models = Table::find()
     .join(JoinType::InnerJoin, TableRelation::DiagramLink.def())
     .filter(diagram_link::Column::DiagramId.eq(diagram_id.clone()))
@@ -47,126 +49,232 @@ pub async fn query_tables(
     .map(|(table_id, pairs)| (table_id, pairs.into_iter().map(|(_, vo)| vo).collect()))
     .collect();
 
+    let indice_map: HashMap<String, Vec<IndiceVo>> = query_indices(conn, table_ids.clone()).await?
+    .into_iter()
+    .filter_map(|vo| vo.table_id.clone().map(|table_id| (table_id, vo)))
+    .into_group_map();
+
     let table_vos = table_models.iter().map(|table|{
         let binding = vec![];
         let temp_fields = field_map.get(&table.id).unwrap_or(&binding);
-        TableVo::build_from_table(table.clone(), Some(temp_fields.to_vec()),diagram_id.clone())
+        let mut table_vo = TableVo::build_from_table(table.clone(), Some(temp_fields.to_vec()),diagram_id.clone());
+        table_vo.indices = Some(indice_map.get(&table.id).cloned().unwrap_or_default());
+        table_vo
     }).collect::<Vec<TableVo>>();
  
     Ok(table_vos)
 }
 
+/// 按字段id的新旧映射重建索引的字段列表
+/// 映射中不存在的字段会从索引中移除，移除后没有字段的索引将被丢弃
+fn remap_indices(
+    indices: Vec<IndiceVo>,
+    table_id: &str,
+    field_id_map: &HashMap<String, String>
+) -> Vec<IndiceVo> {
+    indices.into_iter().filter_map(|mut indice| {
+        let fields = indice.fields.unwrap_or_default().iter()
+        .filter_map(|field_id| field_id_map.get(field_id).cloned())
+        .collect::<Vec<String>>();
+        if fields.is_empty() {
+            return None;
+        }
+        indice.fields = Some(fields);
+        indice.table_id = Some(table_id.to_string());
+        Some(indice)
+    }).collect()
+}
+
+/// 新增一张表需要写入的数据
+struct TableInsert {
+    table: table::ActiveModel,
+    fields: Vec<field::ActiveModel>,
+    table_links: Vec<table_link::ActiveModel>,
+    diagram_link: diagram_link::ActiveModel,
+    indices: Vec<IndiceVo>,
+}
+
+/// 为表、字段、索引生成后端id并构建需要写入的数据
+/// 前端生成的id与后端id的对应关系记录到id_mapping中
+fn build_table_insert(table_vo: &TableVo, id_mapping: &mut IdMappingVo) -> TableInsert {
+    //1、表，覆盖前端生成的id
+    let table_id = next_id();
+    let mut table_add = table_vo.convert_to_table();
+    table_add.id = table_id.clone();
+    id_mapping.tables.insert(table_vo.id.clone(), table_id.clone());
+    //2、字段，覆盖前端生成的id
+    let field_vos = table_vo.fields.clone().unwrap_or_default();
+    let field_ids = next_ids(field_vos.len());
+    let fields = field_vos.iter().zip(field_ids.iter()).map(|(field, field_id)| {
+        let mut field = field.convert_to_field();
+        field.id = field_id.clone();
+        field::ActiveModel::from(field)
+    }).collect::<Vec<field::ActiveModel>>();
+    //3、表与字段的关联关系
+    let table_links = field_ids.iter().map(|field_id| {
+        table_link::ActiveModel::from(build_table_link(next_id(), table_id.clone(), field_id.clone()))
+    }).collect::<Vec<table_link::ActiveModel>>();
+    //4、图表与表的关联关系
+    let diagram_link = diagram_link::ActiveModel::from(diagram_link::Model::new(
+        next_id(),
+        Some(table_vo.diagram_id.clone()),
+        None,
+        Some(table_id.clone()),
+        None,
+        None,
+        None
+    ));
+    //5、索引，索引中的字段id替换为新生成的字段id
+    let field_id_map = field_vos.into_iter()
+    .map(|field| field.id)
+    .zip(field_ids)
+    .collect::<HashMap<String, String>>();
+    let indices = remap_indices(table_vo.indices.clone().unwrap_or_default(), &table_id, &field_id_map)
+    .into_iter()
+    .map(|mut indice| {
+        indice.id = next_id();
+        indice
+    })
+    .collect();
+    id_mapping.fields.insert(table_vo.id.clone(), field_id_map);
+    TableInsert { table: table::ActiveModel::from(table_add), fields, table_links, diagram_link, indices }
+}
+
+/// 按图表的数据库类型校验并规范化表中的字段
+/// 图表中自定义的类型和枚举也可以作为字段类型
+async fn normalize_fields<C: ConnectionTrait>(
+    conn: &C,
+    table_vo: &mut TableVo
+) -> Result<(), DrawDBError> {
+    let diagram = Diagram::find_by_id(table_vo.diagram_id.clone()).one(conn).await?
+    .map(|diagram| DiagramVo::from(&diagram));
+    let data_types = DataTypes::for_database(diagram.as_ref().and_then(|diagram| diagram.database.as_deref()));
+    let custom_types = diagram.map(|diagram| {
+        let types = diagram.types.unwrap_or_default().into_iter().map(|r#type| r#type.name);
+        let enums = diagram.enums.unwrap_or_default().into_iter().map(|r#enum| r#enum.name);
+        types.chain(enums).collect::<Vec<String>>()
+    }).unwrap_or_default();
+    let table_name = table_vo.name.clone().unwrap_or(table_vo.id.clone());
+    for field in table_vo.fields.iter_mut().flatten() {
+        data_types.normalize_field(field, &custom_types).map_err(|reason| {
+            let field_name = field.name.clone().unwrap_or(field.id.clone());
+            DrawDBError::ValidationError(format!("表'{}'的字段'{}'：{}", table_name, field_name, reason))
+        })?;
+    }
+    Ok(())
+}
+
 /// 新增表结构处理方法
+/// 字段按图表的数据库类型校验并规范化，返回前端id与后端id的映射
 pub async fn add_table(
     tx: &DatabaseTransaction,
-    table_vo:TableVo
-)->Result<String, DrawDBError> {
-    // 1、新增表
-    let table_id = next_id();
-    let temp_table_vo = table_vo.clone();
-    let mut table_add = temp_table_vo.convert_to_table();
-    // 覆盖前端生成的id
-    table_add.id = table_id.clone();
-    let table_am = table::ActiveModel::from(table_add);
-    Table::insert(table_am).exec(tx).await?;
-    //2、插入fields
-    let field_size = temp_table_vo.fields.clone().unwrap_or(vec![]).len();
-    if field_size > 0{
-        let field_ids = next_ids(field_size);
-        let mut fields = temp_table_vo.fields.clone().unwrap_or(vec![]).iter().map( |field|{
-             field.convert_to_field()
-        }).collect::<Vec<field::Model>>();
-        fields.iter_mut().enumerate().for_each(|(index,field)|{
-            field.id = field_ids[index].clone();
-        });
-        let field_ams = fields.iter()
-        .map(|item|field::ActiveModel::from(item.clone()))
-        .collect::<Vec<field::ActiveModel>>(); 
-        Field::insert_many(field_ams).exec(tx).await?;
-        //3、插入默认的关联关系
-        let table_link_ams = fields.iter().map(|field|{
-            table_link::ActiveModel::from(build_table_link(next_id(), table_id.clone(),field.id.clone()))
-        }).collect::<Vec<table_link::ActiveModel>>();
-         TableLink::insert_many(table_link_ams).exec(tx).await?;
+    mut table_vo:TableVo
+)->Result<IdMappingVo, DrawDBError> {
+    normalize_fields(tx, &mut table_vo).await?;
+    let mut id_mapping = IdMappingVo::default();
+    let insert = build_table_insert(&table_vo, &mut id_mapping);
+    //1、新增表
+    Table::insert(insert.table).exec(tx).await?;
+    //2、插入fields以及表与字段的关联关系
+    if !insert.fields.is_empty() {
+        Field::insert_many(insert.fields).exec(tx).await?;
+        TableLink::insert_many(insert.table_links).exec(tx).await?;
+    }
+    //3、插入图表与表的关联关系
+    DiagramLink::insert(insert.diagram_link).exec(tx).await?;
+    //4、插入索引
+    for indice in insert.indices {
+        add_indice(tx, indice).await?;
     }
-    //4、返回新增的表id
-    Ok(table_id.clone())
+    Ok(id_mapping)
 }
 
 /// 批量新增方法
+/// 字段按图表的数据库类型校验并规范化，返回所有表的前端id与后端id的映射
 pub async fn batch_add_table(
     tx: &DatabaseTransaction,
-    table_vos: Vec<TableVo>
-)->Result<Vec<String>,DrawDBError>{
-    fn get_table_fileds(table_vo:&TableVo)->(table::ActiveModel,
-        Option<Vec<field::ActiveModel>>,
-        Option<Vec<table_link::ActiveModel>>){
-           // 1、新增表
-    let table_id = next_id();
-    let temp_table_vo = table_vo.clone();
-    let mut table_add = temp_table_vo.convert_to_table();
-    // 覆盖前端生成的id
-    table_add.id = table_id.clone();
-    let table_am = table::ActiveModel::from(table_add);
-    //2、插入fields
-    let field_size = temp_table_vo.fields.clone().unwrap_or(vec![]).len();
-    if field_size > 0{
-        let field_ids = next_ids(field_size);
-        let mut fields = temp_table_vo.fields.clone().unwrap_or(vec![]).iter().map( |field|{
-             field.convert_to_field()
-        }).collect::<Vec<field::Model>>();
-        fields.iter_mut().enumerate().for_each(|(index,field)|{
-            field.id = field_ids[index].clone();
-        });
-        let field_ams = fields.iter()
-        .map(|item|field::ActiveModel::from(item.clone()))
-        .collect::<Vec<field::ActiveModel>>(); 
-        //3、插入默认的关联关系
-        let table_link_ams = fields.iter().map(|field|{
-            table_link::ActiveModel::from(build_table_link(next_id(), table_id.clone(),field.id.clone()))
-        }).collect::<Vec<table_link::ActiveModel>>();
-        return (table_am,Some(field_ams),Some(table_link_ams));
+    mut table_vos: Vec<TableVo>
+)->Result<IdMappingVo,DrawDBError>{
+    let mut id_mapping = IdMappingVo::default();
+    if table_vos.is_empty() {
+        return Ok(id_mapping);
     }
-    (table_am,None,None)
+    for table_vo in table_vos.iter_mut() {
+        normalize_fields(tx, table_vo).await?;
     }
     // 1、遍历处理表和字段数据
-    let tables_fields:Vec<(table::ActiveModel,
-        Option<Vec<field::ActiveModel>>,
-        Option<Vec<table_link::ActiveModel>>)> = table_vos
-    .iter().map(|vo|{get_table_fileds(vo)}).collect();
-    // 2、扁平化处理 - 更优雅的方式
-    let (tables, fields, links): (Vec<_>, Vec<_>, Vec<_>) = tables_fields
-        .into_iter()
-        .fold(
-            (Vec::new(), Vec::new(), Vec::new()),
-            |(mut tables, mut fields, mut links), (t, f, l)| {
-                tables.push(t);
-                if let Some(flds) = f {
-                    fields.extend(flds);
-                }
-                if let Some(lk) = l {
-                    links.extend(lk);
-                }
-                (tables, fields, links)
-            },
-        );
+    let inserts = table_vos.iter()
+    .map(|vo| build_table_insert(vo, &mut id_mapping))
+    .collect::<Vec<TableInsert>>();
+    // 2、扁平化处理
+    let mut tables = Vec::new();
+    let mut fields = Vec::new();
+    let mut table_links = Vec::new();
+    let mut diagram_links = Vec::new();
+    let mut indices = Vec::new();
+    for insert in inserts {
+        tables.push(insert.table);
+        fields.extend(insert.fields);
+        table_links.extend(insert.table_links);
+        diagram_links.push(insert.diagram_link);
+        indices.extend(insert.indices);
+    }
     // 3、插入表结构数据
     Table::insert_many(tables).exec(tx).await?;
-    Field::insert_many(fields).exec(tx).await?;
-    TableLink::insert_many(links).exec(tx).await?;
-    Ok(vec![])
+    if !fields.is_empty() {
+        Field::insert_many(fields).exec(tx).await?;
+        TableLink::insert_many(table_links).exec(tx).await?;
+    }
+    DiagramLink::insert_many(diagram_links).exec(tx).await?;
+    for indice in indices {
+        add_indice(tx, indice).await?;
+    }
+    Ok(id_mapping)
+}
+
+/// 批量新增表以及引用这些表的关联关系
+/// 表统一归属到diagram_id，关联关系中的前端id替换为后端id，返回前端id与后端id的映射
+pub async fn batch_add_table_with_references(
+    tx: &DatabaseTransaction,
+    diagram_id: &str,
+    table_vos: Vec<TableVo>,
+    references: Vec<ReferenceVo>
+)->Result<IdMappingVo,DrawDBError>{
+    //1、新增表结构
+    let table_vos = table_vos.into_iter().map(|mut table_vo| {
+        table_vo.diagram_id = diagram_id.to_string();
+        table_vo
+    }).collect();
+    let mut id_mapping = batch_add_table(tx, table_vos).await?;
+    //2、新增关联关系
+    let references = references.into_iter().map(|mut reference| {
+        id_mapping.apply_to_reference(&mut reference);
+        id_mapping.assign_reference_id(&mut reference, next_id());
+        reference
+    }).collect::<Vec<ReferenceVo>>();
+    if !references.is_empty() {
+        let ids = references.iter().map(|reference| reference.id.clone()).collect();
+        add_references(tx, references).await?;
+        add_diagram_links(tx, diagram_id, diagram_link::Column::ReferenceId, ids).await?;
+    }
+    Ok(id_mapping)
 }
 
 /// 更新表结构
+/// 按字段id对比新旧字段：保留已有字段的id并原地更新，只新增新字段、只删除被移除的字段
+/// 被关联关系引用的字段不允许删除，字段按图表的数据库类型校验并规范化
+/// indices为None时沿用已存储的索引
+/// 返回新增字段的前端id与后端id的映射，按表id分组
 pub async fn update_table(
     tx: &DatabaseTransaction,
-    table_vo:TableVo
-)->Result<bool,DrawDBError>{
+    mut table_vo:TableVo
+)->Result<IdMappingVo,DrawDBError>{
+    normalize_fields(tx, &mut table_vo).await?;
     //1、根据表的Id更新表信息
     let table_id = table_vo.id.clone();
-    let table_am = table::ActiveModel::from(table_vo.convert_to_table());
+    let table_am = table::ActiveModel::from(table_vo.convert_to_table()).reset_all();
     Table::update(table_am).filter(table::Column::Id.eq(table_id.clone())).exec(tx).await?;
-    //2、查询原本所有的字段
+    //2、查询原本所有的字段和索引
     let origin_fields = Field::find()
     .select_only()
     // 先把 field 的所有列都选一遍
@@ -180,36 +288,97 @@ pub async fn update_table(
     .filter(table_link::Column::TableId.eq(table_id.clone()))
     .into_model::<FieldWithTable>()  // 映射到我们的 DTO
     .all(tx)
+    .await?
+    .into_iter()
+    .map(FieldVo::build_from_field_with_table)
+    .collect::<Vec<FieldVo>>();
+    let origin_indices = query_indices(tx, vec![table_id.clone()]).await?;
+
+    //3、对比字段
+    let fields = table_vo.fields
+    .ok_or(DrawDBError::DeconstructError("fields is none".to_string()))?
+    .into_iter()
+    .map(|mut field| {
+        field.table_id = Some(table_id.clone());
+        field
+    })
+    .collect::<Vec<FieldVo>>();
+    let changes = diff_by_id(&origin_fields, &fields, |field| &field.id);
+
+    //4、检查被删除的字段是否仍被关联关系引用
+    let delete_field_ids = changes.deletes.iter().map(|field| field.id.clone()).collect::<Vec<String>>();
+    let referenced = Reference::find()
+    .filter(
+        Condition::any()
+        .add(reference::Column::StartFieldId.is_in(delete_field_ids.clone()))
+        .add(reference::Column::EndFieldId.is_in(delete_field_ids.clone()))
+    )
+    .all(tx)
     .await?;
-    //3、删除所有原本关联的字段
-    let origin_field_ids = origin_fields.iter().map(|field|field.id.clone()).collect::<Vec<String>>();
-    Field::delete_many()
-    .filter(field::Column::Id.is_in(origin_field_ids))
+    if !referenced.is_empty() {
+        let names = referenced.iter()
+        .map(|reference| reference.name.clone().unwrap_or(reference.id.clone()))
+        .join(", ");
+        return Err(DrawDBError::ValidationError(format!("字段仍被关联关系引用，无法删除: {}", names)));
+    }
+
+    //5、删除被移除的字段、字段与索引以及字段与表的关联关系
+    IndiceLink::delete_many()
+    .filter(indice_link::Column::FieldId.is_in(delete_field_ids.clone()))
     .exec(tx)
     .await?;
-    //4、删除表与字段构建的关联关系
     TableLink::delete_many()
-    .filter(table_link::Column::TableId.eq(table_id.clone()))
+    .filter(table_link::Column::FieldId.is_in(delete_field_ids.clone()))
+    .exec(tx)
+    .await?;
+    Field::delete_many()
+    .filter(field::Column::Id.is_in(delete_field_ids))
     .exec(tx)
     .await?;
 
-    //5、获取当前新的字段
-    let fields = table_vo.fields
-    .ok_or(DrawDBError::DeconstructError("fields is none".to_string()))?;
-    //5、新增字段
-    let field_ids = next_ids(fields.len());
-    let field_ams = fields.iter().enumerate().map(|(index,field)|{
-        let mut field_am = field::ActiveModel::from(field.convert_to_field());
-        field_am.id = ActiveValue::Set(field_ids[index].clone());
-        field_am
-    }).collect::<Vec<field::ActiveModel>>();
-    Field::insert_many(field_ams).exec(tx).await?;
-    //6、新增表与字段的关联关系
-    let table_link_ams = fields.iter().map(|item|{
-        table_link::ActiveModel::from(build_table_link(next_id(), table_id.clone(),item.id.clone()))
-    }).collect::<Vec<table_link::ActiveModel>>();
-    TableLink::insert_many(table_link_ams).exec(tx).await?;
-    Ok(true)
+    //6、原地更新发生变化的字段
+    for field in changes.updates {
+        let field_am = field::ActiveModel::from(field.convert_to_field()).reset_all();
+        Field::update(field_am).filter(field::Column::Id.eq(field.id.clone())).exec(tx).await?;
+    }
+
+    //7、新增字段以及表与字段的关联关系，保留字段的id保持不变
+    let mut field_id_map = fields.iter()
+    .filter(|field| !changes.inserts.contains(field))
+    .map(|field| (field.id.clone(), field.id.clone()))
+    .collect::<HashMap<String, String>>();
+    let mut id_mapping = IdMappingVo::default();
+    if !changes.inserts.is_empty() {
+        let field_ids = next_ids(changes.inserts.len());
+        let field_ams = changes.inserts.iter().enumerate().map(|(index,field)|{
+            let mut field_am = field::ActiveModel::from(field.convert_to_field());
+            field_am.id = ActiveValue::Set(field_ids[index].clone());
+            field_am
+        }).collect::<Vec<field::ActiveModel>>();
+        Field::insert_many(field_ams).exec(tx).await?;
+        let table_link_ams = field_ids.iter().map(|field_id|{
+            table_link::ActiveModel::from(build_table_link(next_id(), table_id.clone(),field_id.clone()))
+        }).collect::<Vec<table_link::ActiveModel>>();
+        TableLink::insert_many(table_link_ams).exec(tx).await?;
+        let inserted = changes.inserts.iter().map(|field| field.id.clone()).zip(field_ids).collect::<HashMap<String, String>>();
+        field_id_map.extend(inserted.clone());
+        id_mapping.fields.insert(table_id.clone(), inserted);
+    }
+
+    //8、同步索引
+    let indices = remap_indices(table_vo.indices.unwrap_or(origin_indices.clone()), &table_id, &field_id_map);
+    let changes = diff_by_id(&origin_indices, &indices, |indice| &indice.id);
+    for indice in changes.deletes {
+        delete_indice(tx, indice).await?;
+    }
+    for indice in changes.updates {
+        update_indice(tx, indice).await?;
+    }
+    for mut indice in changes.inserts {
+        indice.id = next_id();
+        add_indice(tx, indice).await?;
+    }
+    Ok(id_mapping)
 }
 
 /// 删除表结构
@@ -217,14 +386,7 @@ pub async fn delete_table(
     tx: &DatabaseTransaction,
     table_id: String
 )->Result<bool,DrawDBError>{
-    //1、删除表
-    Table::delete_by_id(table_id.clone()).exec(tx).await?;
-    //2、删除表与字段的关联关系
-    TableLink::delete_many()
-    .filter(table_link::Column::TableId.eq(table_id.clone()))
-    .exec(tx)
-    .await?;
-    //3、删除字段
+    //1、查询表关联的字段（需要在删除关联关系之前查询）
     let origin_fields = Field::find()
     .select_only()
     // 先把 field 的所有列都选一遍
@@ -239,20 +401,139 @@ pub async fn delete_table(
     .into_model::<FieldWithTable>()  // 映射到我们的 DTO
     .all(tx)
     .await?;
+    //2、删除表的索引以及字段与索引的关联关系
     let origin_field_ids = origin_fields.iter().map(|field|field.id.clone()).collect::<Vec<String>>();
+    delete_table_indices(tx, table_id.clone()).await?;
+    IndiceLink::delete_many()
+    .filter(indice_link::Column::FieldId.is_in(origin_field_ids.clone()))
+    .exec(tx)
+    .await?;
+    //3、删除字段
     Field::delete_many()
     .filter(field::Column::Id.is_in(origin_field_ids))
     .exec(tx)
     .await?;
+    //4、删除表与字段的关联关系
+    TableLink::delete_many()
+    .filter(table_link::Column::TableId.eq(table_id.clone()))
+    .exec(tx)
+    .await?;
+    //5、删除图表与表的关联关系
+    DiagramLink::delete_many()
+    .filter(diagram_link::Column::TableId.eq(table_id.clone()))
+    .exec(tx)
+    .await?;
+    //6、删除表
+    Table::delete_by_id(table_id.clone()).exec(tx).await?;
     Ok(true)
 }
 
 #[cfg(test)]
 mod tests {
+    use sea_orm::TransactionTrait;
+    use super::*;
+    use crate::entity::vo::fixtures::{build_field, build_table};
+    use crate::init::init_test_db;
 
+    /// 复合索引按字段顺序保存，删除字段和表时清理索引
     #[actix_web::test]
-    async fn batch_insert(){
-        //1、读取fields
+    async fn test_table_indices(){
+        let db = init_test_db().await;
+        let tx = db.begin().await.unwrap();
+        let indice = IndiceVo {
+            id: "0".to_string(),
+            name: Some("idx_b_a".to_string()),
+            unique: Some(true),
+            table_id: None,
+            fields: Some(vec!["1".to_string(), "0".to_string()]),
+        };
+        let table = build_table("0", "users", vec![build_field("0", "a", "INTEGER"), build_field("1", "b", "INTEGER")], vec![indice]);
+        let table_id = add_table(&tx, table).await.unwrap().tables["0"].clone();
+
+        let mut table = query_tables(&tx, "d1".to_string()).await.unwrap().remove(0);
+        let fields = table.fields.clone().unwrap();
+        let field_id = |name: &str| fields.iter().find(|f| f.name.as_deref() == Some(name)).unwrap().id.clone();
+        let indices = table.indices.clone().unwrap();
+        assert_eq!(indices.len(), 1);
+        assert_eq!(indices[0].table_id, Some(table_id.clone()));
+        assert_eq!(indices[0].fields, Some(vec![field_id("b"), field_id("a")]));
+
+        // 删除字段b后索引只保留字段a
+        let field_a = field_id("a");
+        table.fields = Some(fields.into_iter().filter(|f| f.id == field_a).collect());
+        update_table(&tx, table).await.unwrap();
+        let table = query_tables(&tx, "d1".to_string()).await.unwrap().remove(0);
+        let indices = table.indices.unwrap();
+        assert_eq!(indices.len(), 1);
+        assert_eq!(indices[0].fields, Some(vec![field_a]));
+
+        // 删除表后索引一并删除
+        delete_table(&tx, table_id).await.unwrap();
+        assert!(Indice::find().all(&tx).await.unwrap().is_empty());
+        assert!(IndiceLink::find().all(&tx).await.unwrap().is_empty());
+        tx.commit().await.unwrap();
     }
 
-}
\ No newline at end of file
+    /// 更新表时保留字段id，拒绝删除仍被关联关系引用的字段
+    #[actix_web::test]
+    async fn test_update_table_keeps_field_ids(){
+        let db = init_test_db().await;
+        let tx = db.begin().await.unwrap();
+        let table = build_table("0", "users", vec![build_field("0", "a", "INTEGER"), build_field("1", "b", "INTEGER")], vec![]);
+        let table_id = add_table(&tx, table).await.unwrap().tables["0"].clone();
+        let mut table = query_tables(&tx, "d1".to_string()).await.unwrap().remove(0);
+        let fields = table.fields.clone().unwrap();
+        let field_id = |name: &str| fields.iter().find(|f| f.name.as_deref() == Some(name)).unwrap().id.clone();
+        let (field_a, field_b) = (field_id("a"), field_id("b"));
+        Reference::insert(reference::ActiveModel::from(reference::Model {
+            id: "r1".to_string(),
+            cardinality: None,
+            delete_constraint: None,
+            end_field_id: Some(field_b.clone()),
+            end_table_id: Some(table_id.clone()),
+            name: Some("fk_b".to_string()),
+            start_field_id: Some(field_b.clone()),
+            start_table_id: Some(table_id.clone()),
+            update_constraint: None,
+        })).exec(&tx).await.unwrap();
+
+        // 修改字段a、新增字段c、删除字段b
+        let mut new_fields = fields.iter().filter(|f| f.id == field_a).cloned().collect::<Vec<FieldVo>>();
+        new_fields[0].r#type = Some("BIGINT".to_string());
+        new_fields.push(build_field("c", "c", "INTEGER"));
+        table.fields = Some(new_fields);
+        let result = update_table(&tx, table.clone()).await;
+        assert!(matches!(result, Err(DrawDBError::ValidationError(_))));
+
+        Reference::delete_by_id("r1").exec(&tx).await.unwrap();
+        update_table(&tx, table).await.unwrap();
+        let table = query_tables(&tx, "d1".to_string()).await.unwrap().remove(0);
+        let fields = table.fields.unwrap();
+        assert_eq!(fields.len(), 2);
+        let a = fields.iter().find(|f| f.name.as_deref() == Some("a")).unwrap();
+        assert_eq!(a.id, field_a);
+        assert_eq!(a.r#type, Some("BIGINT".to_string()));
+        let c = fields.iter().find(|f| f.name.as_deref() == Some("c")).unwrap();
+        assert_ne!(c.id, "c");
+        assert!(Field::find_by_id(field_b).one(&tx).await.unwrap().is_none());
+        assert_eq!(TableLink::find().all(&tx).await.unwrap().len(), 2);
+        tx.commit().await.unwrap();
+    }
+
+    /// 批量新增时字段按图表的数据库类型校验并规范化
+    #[actix_web::test]
+    async fn test_batch_add_table_normalizes_fields(){
+        let db = init_test_db().await;
+        let tx = db.begin().await.unwrap();
+        let invalid = build_table("1", "orders", vec![build_field("0", "id", "NOPE")], vec![]);
+        let result = batch_add_table(&tx, vec![invalid]).await;
+        assert!(matches!(result, Err(DrawDBError::ValidationError(_))));
+
+        let table = build_table("0", "users", vec![build_field("0", "id", "integer"), build_field("1", "name", "varchar")], vec![]);
+        batch_add_table(&tx, vec![table]).await.unwrap();
+        let fields = query_tables(&tx, "d1".to_string()).await.unwrap().remove(0).fields.unwrap();
+        let types = fields.iter().map(|field| (field.r#type.clone().unwrap(), field.size)).collect::<Vec<_>>();
+        assert_eq!(types, vec![("INT".to_string(), None), ("VARCHAR".to_string(), Some(255))]);
+        tx.commit().await.unwrap();
+    }
+}
@@ -1,14 +1,13 @@
 mod internal_api;
 pub use internal_api::*;
 use actix_web::{get, post, web};
-use sea_orm::{DatabaseConnection, EntityTrait, TransactionTrait};
-use crate::entity::{prelude::*, table};
-use crate::entity::vo::{TableVo, build_table_link};
-use crate::{next_id, next_ids};
+use sea_orm::{DatabaseConnection, TransactionTrait};
+use crate::entity::vo::{TableBatchAddVo, TableVo};
 use crate::{common::{CommonResponse, ResponseCode, ResponseMessage}, error::DrawDBError};
-use crate::entity::{field,table_link};
 pub fn tables_routes(config: &mut web::ServiceConfig){
     config.service(query);
+    config.service(add);
+    config.service(batch_add);
 }
 
 /// 查询与table关联的field
@@ -18,7 +17,7 @@ async fn query(
     diagram_id: web::Path<String>
 ) -> Result<CommonResponse, DrawDBError> {
     let diagram_id = diagram_id.into_inner();
-    let result = query_tables(db,diagram_id).await?;
+    let result = query_tables(db.get_ref(),diagram_id).await?;
     Ok(CommonResponse::new(
         ResponseCode::Success,
         ResponseMessage::Success,
@@ -36,12 +35,155 @@ async fn add(
     let tx = db.begin().await?;
     //2、新增图表
     let table_vo = table_vo.into_inner();
-    let table_id =  add_table(&tx,table_vo).await?;
+    let id_mapping =  add_table(&tx,table_vo).await?;
     //3、提交事务
     tx.commit().await?;
     Ok(CommonResponse::new(
         ResponseCode::Success,
         ResponseMessage::Success,
-        Some(serde_json::to_value(table_id).unwrap()),
+        Some(serde_json::to_value(id_mapping).unwrap()),
     ))
 }
+
+/// 批量新增tables
+/// 同一请求中的关联关系引用的前端id会被替换为后端id，返回前端id与后端id的映射
+#[post("/batchAdd")]
+async fn batch_add(
+   db: web::Data<DatabaseConnection>,
+   batch: web::Json<TableBatchAddVo>
+)->Result<CommonResponse, DrawDBError> {
+    //1、开启事务
+    let tx = db.begin().await?;
+    //2、新增表结构和关联关系
+    let batch = batch.into_inner();
+    let references = batch.references.unwrap_or_default();
+    let id_mapping = batch_add_table_with_references(&tx, &batch.diagram_id, batch.tables, references).await?;
+    //3、提交事务
+    tx.commit().await?;
+    Ok(CommonResponse::new(
+        ResponseCode::Success,
+        ResponseMessage::Success,
+        Some(serde_json::to_value(id_mapping).unwrap()),
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use actix_web::{test, web, App};
+    use sea_orm::EntityTrait;
+    use serde_json::json;
+
+    use super::*;
+    use crate::entity::{diagram, prelude::*};
+    use crate::entity::vo::IdMappingVo;
+    use crate::init::init_test_db;
+
+    /// 批量新增表时，同一请求中的关联关系使用后端生成的id
+    #[actix_web::test]
+    async fn test_batch_add_remaps_references() {
+        let db = init_test_db().await;
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(db.clone()))
+                .configure(tables_routes)
+        ).await;
+        let field = |id: &str, name: &str| json!({
+            "id": id, "table_id": null, "check": null, "comment": null, "default": null,
+            "increment": false, "not_null": true, "primary": id == "0", "size": null,
+            "type": "INTEGER", "unique": false, "name": name
+        });
+        let table = |id: &str, name: &str, fields: Vec<serde_json::Value>| json!({
+            "id": id, "color": null, "comment": null, "locked": false, "name": name,
+            "x": "0", "y": "0", "fields": fields, "indices": null, "diagram_id": ""
+        });
+        let req = test::TestRequest::post()
+            .uri("/batchAdd")
+            .set_json(json!({
+                "diagram_id": "1",
+                "tables": [
+                    table("0", "users", vec![field("0", "id")]),
+                    table("1", "orders", vec![field("0", "id"), field("1", "user_id")]),
+                ],
+                "references": [{
+                    "id": "0",
+                    "name": "fk_orders_users",
+                    "start_table_id": "1",
+                    "start_field_id": "1",
+                    "end_table_id": "0",
+                    "end_field_id": "0",
+                    "cardinality": "many_to_one",
+                    "delete_constraint": "Cascade",
+                    "update_constraint": "No action"
+                }]
+            }))
+            .to_request();
+        let resp: CommonResponse = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(resp.code, 200);
+        let id_mapping: IdMappingVo = serde_json::from_value(resp.data.unwrap()).unwrap();
+        assert_eq!(id_mapping.tables.len(), 2);
+        assert_eq!(id_mapping.fields["1"].len(), 2);
+
+        let references = Reference::find().all(&db).await.unwrap();
+        assert_eq!(references.len(), 1);
+        assert_eq!(references[0].id, id_mapping.references["0"]);
+        assert_eq!(references[0].start_table_id.as_ref(), Some(&id_mapping.tables["1"]));
+        assert_eq!(references[0].start_field_id.as_ref(), Some(&id_mapping.fields["1"]["1"]));
+        assert_eq!(references[0].end_table_id.as_ref(), Some(&id_mapping.tables["0"]));
+        assert_eq!(references[0].end_field_id.as_ref(), Some(&id_mapping.fields["0"]["0"]));
+        let tables = query_tables(&db, "1".to_string()).await.unwrap();
+        assert_eq!(tables.len(), 2);
+        // 图表关联了两张表和一个关联关系
+        assert_eq!(DiagramLink::find().all(&db).await.unwrap().len(), 3);
+    }
+
+    /// 新增表时按图表的数据库类型规范化字段，不支持的类型返回400
+    #[actix_web::test]
+    async fn test_add_normalizes_field_types() {
+        let db = init_test_db().await;
+        Diagram::insert(diagram::ActiveModel::from(diagram::Model {
+            id: "d1".to_string(),
+            database: Some("mysql".to_string()),
+            zoom: None,
+            name: Some("demo".to_string()),
+            pan: None,
+            last_modified: None,
+            types: None,
+            enums: None,
+        })).exec(&db).await.unwrap();
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(db.clone()))
+                .configure(tables_routes)
+        ).await;
+        let field = |id: &str, r#type: &str| json!({
+            "id": id, "table_id": null, "check": null, "comment": null, "default": null,
+            "increment": false, "not_null": false, "primary": false, "size": 11,
+            "type": r#type, "unique": false, "name": format!("f{}", id)
+        });
+        let table = |fields: Vec<serde_json::Value>| json!({
+            "id": "0", "color": null, "comment": null, "locked": false, "name": "users",
+            "x": "0", "y": "0", "fields": fields, "indices": null, "diagram_id": "d1"
+        });
+        let req = test::TestRequest::post()
+            .uri("/add")
+            .set_json(table(vec![field("0", "int"), field("1", "varchar(32)")]))
+            .to_request();
+        let resp: CommonResponse = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(resp.code, 200);
+        let id_mapping: IdMappingVo = serde_json::from_value(resp.data.unwrap()).unwrap();
+        let id = Field::find_by_id(id_mapping.fields["0"]["0"].clone()).one(&db).await.unwrap().unwrap();
+        assert_eq!(id.r#type.as_deref(), Some("INTEGER"));
+        assert_eq!(id.size, None);
+        let name = Field::find_by_id(id_mapping.fields["0"]["1"].clone()).one(&db).await.unwrap().unwrap();
+        assert_eq!(name.r#type.as_deref(), Some("VARCHAR"));
+        assert_eq!(name.size, Some(32));
+
+        let req = test::TestRequest::post()
+            .uri("/add")
+            .set_json(table(vec![field("0", "JSONB")]))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 400);
+        assert_eq!(Table::find().all(&db).await.unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,27 @@
+use sea_orm::{DatabaseTransaction, EntityTrait};
+use crate::entity::{diagram_link, task, prelude::*};
+use crate::entity::vo::TaskAddVo;
+use crate::error::DrawDBError;
+use crate::next_id;
+
+/// 新增代办事项
+/// 同时建立图表与代办事项的关联关系，返回代办事项id
+pub async fn add_task(
+    tx: &DatabaseTransaction,
+    task_vo: &TaskAddVo
+) -> Result<String, DrawDBError> {
+    let task_id = next_id();
+    let task_active_model = task::ActiveModel::from(task_vo.convert_to_task(task_id.clone()));
+    Task::insert(task_active_model).exec(tx).await?;
+    let diagram_link = diagram_link::Model::new(
+        next_id(),
+        Some(task_vo.diagram_id.clone()),
+        Some(task_id.clone()),
+        None,
+        None,
+        None,
+        None
+    );
+    DiagramLink::insert(diagram_link::ActiveModel::from(diagram_link)).exec(tx).await?;
+    Ok(task_id)
+}
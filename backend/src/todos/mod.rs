@@ -1,3 +1,6 @@
+mod internal_api;
+
+pub use internal_api::*;
 use actix_web::{get, post, delete, web, HttpResponse, Responder};
 use sea_orm::ActiveModelTrait;
 use sea_orm::ColumnTrait;
@@ -17,7 +20,6 @@ use crate::entity::prelude::*;
 use crate::entity::task;
 use crate::entity::vo::*;
 use crate::error::DrawDBError;
-use crate::next_id;
 
 pub fn todos_routes(config: &mut web::ServiceConfig) {
     config.route("/test", web::get().to(get_todos_example));
@@ -80,25 +82,8 @@ async fn add(
     // 开启事务
     let tx = db.begin().await?;
     
-    // 插入task
-    let task_id = next_id();
-    let task = todo.convert_to_task(task_id.clone());
-    let task_active_model = task::ActiveModel::from(task);
-    // 这种写法能看返回最新插入的id，但不会返回整个model
-    let task_model = Task::insert(task_active_model).exec(&tx).await?;
-    // 插入diagram_link
-    let diagram_link_id = next_id();
-    let diagram_link = diagram_link::Model::new(
-        diagram_link_id, 
-        Some(todo.diagram_id.clone()),
-        Some(task_id), 
-        None,
-        None,
-        None,
-        None
-    );
-    let diagram_link_active_model = diagram_link::ActiveModel::from(diagram_link);
-    DiagramLink::insert(diagram_link_active_model).exec(&tx).await?;
+    // 插入task以及diagram_link
+    let task_id = add_task(&tx, &todo).await?;
     
     // 提交事务
     tx.commit().await?;
@@ -106,7 +91,7 @@ async fn add(
     Ok(CommonResponse::new(
         ResponseCode::Success,
         ResponseMessage::Success,
-        Some(serde_json::to_value(task_model.last_insert_id).unwrap()),
+        Some(serde_json::to_value(task_id).unwrap()),
     ))
 }
 /// 更新todo
@@ -155,15 +140,15 @@ async fn delete(
 #[cfg(test)]
 mod test {
     use actix_web::{test, web, App};
-    use sea_orm::Database;
     use serde_json::json;
 
     use super::*;
+    use crate::init::init_test_db;
 
     #[actix_web::test]
     async fn test_query_all_todos() {
         // 创建测试数据库连接
-        let db = Database::connect("sqlite://test.sqlite").await.unwrap();
+        let db = init_test_db().await;
         let db = web::Data::new(db);
         // 创建测试应用
         let app = test::init_service(
@@ -190,7 +175,7 @@ mod test {
     /// 新增todo
     #[actix_web::test]
     async fn test_add_todo() {
-        let db = Database::connect("sqlite://test.sqlite").await.unwrap();
+        let db = init_test_db().await;
         let db = web::Data::new(db);
         let app = test::init_service(
             App::new()
@@ -217,7 +202,17 @@ mod test {
     /// 更新todo
     #[actix_web::test]
     async fn test_update_todo() {
-        let db = Database::connect("sqlite://test.sqlite").await.unwrap();
+        let db = init_test_db().await;
+        Task::insert(task::ActiveModel::from(task::Model {
+            id: "7338216606830563329".to_string(),
+            complete: Some(false),
+            order: Some(0),
+            details: Some("test".to_string()),
+            title: Some("test".to_string()),
+        }))
+        .exec(&db)
+        .await
+        .unwrap();
         let db = web::Data::new(db);
         let app = test::init_service(
             App::new()
@@ -244,7 +239,7 @@ mod test {
     /// 删除todo
     #[actix_web::test]
     async fn test_delete_todo() {
-        let db = Database::connect("sqlite://test.sqlite").await.unwrap();
+        let db = init_test_db().await;
         let db = web::Data::new(db);
         let app = test::init_service(
             App::new()
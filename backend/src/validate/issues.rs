@@ -0,0 +1,317 @@
+use std::collections::{HashMap, HashSet};
+use crate::datatypes::DataTypes;
+use crate::entity::vo::{DiagramVo, IssueSeverity, IssueVo, TableVo};
+
+/// 校验图表结构，与前端issues.js的检查项保持一致
+/// 另外检查关联关系引用的表、字段是否存在
+pub fn validate_diagram(diagram: &DiagramVo) -> Vec<IssueVo> {
+    let tables = diagram.tables.clone().unwrap_or_default();
+    let data_types = DataTypes::for_database(diagram.database.as_deref());
+    let mut issues = Vec::new();
+
+    //1、表、字段和索引
+    let mut table_names = HashSet::new();
+    for table in &tables {
+        let name = table.name.clone().unwrap_or_default();
+        if name.trim().is_empty() {
+            issues.push(IssueVo::error("table_w_no_name", "存在未命名的表".to_string()).on_table(&table.id));
+        } else if !table_names.insert(name.clone()) {
+            issues.push(IssueVo::error("duplicate_table_by_name", format!("表名'{}'重复", name)).on_table(&table.id));
+        }
+        validate_table(table, &name, data_types, &mut issues);
+    }
+
+    //2、自定义类型
+    let mut type_names = HashSet::new();
+    for r#type in diagram.types.clone().unwrap_or_default() {
+        if r#type.name.trim().is_empty() {
+            issues.push(IssueVo::error("type_with_no_name", "存在未命名的类型".to_string()));
+        } else if !type_names.insert(r#type.name.clone()) {
+            issues.push(IssueVo::error("duplicate_types", format!("类型名'{}'重复", r#type.name)));
+        }
+        if r#type.fields.is_empty() {
+            issues.push(IssueVo::error("type_w_no_fields", format!("类型'{}'没有字段", r#type.name)));
+            continue;
+        }
+        let mut field_names = HashSet::new();
+        for field in &r#type.fields {
+            if field.name.trim().is_empty() {
+                issues.push(IssueVo::error("empty_type_field_name", format!("类型'{}'中存在未命名的字段", r#type.name)));
+            } else if !field_names.insert(field.name.clone()) {
+                issues.push(IssueVo::error(
+                    "duplicate_type_fields",
+                    format!("类型'{}'中的字段名'{}'重复", r#type.name, field.name)
+                ));
+            }
+            if field.r#type.trim().is_empty() {
+                issues.push(IssueVo::error("empty_type_field_type", format!("类型'{}'中存在未指定类型的字段", r#type.name)));
+            } else if is_enum_or_set(&field.r#type) && field.values.as_ref().is_none_or(Vec::is_empty) {
+                issues.push(IssueVo::error(
+                    "no_values_for_type_field",
+                    format!("类型'{}'的字段'{}'为{}类型，但没有指定取值", r#type.name, field.name, field.r#type)
+                ));
+            }
+        }
+    }
+
+    //3、枚举
+    let mut enum_names = HashSet::new();
+    for r#enum in diagram.enums.clone().unwrap_or_default() {
+        if r#enum.name.trim().is_empty() {
+            issues.push(IssueVo::error("enum_w_no_name", "存在未命名的枚举".to_string()));
+        } else if !enum_names.insert(r#enum.name.clone()) {
+            issues.push(IssueVo::error("duplicate_enums", format!("枚举名'{}'重复", r#enum.name)));
+        }
+        if r#enum.values.is_empty() {
+            issues.push(IssueVo::error("enum_w_no_values", format!("枚举'{}'没有取值", r#enum.name)));
+        }
+    }
+
+    //4、关联关系
+    let tables_by_id = tables.iter()
+    .map(|table| (table.id.as_str(), table))
+    .collect::<HashMap<&str, &TableVo>>();
+    let references = diagram.references.clone().unwrap_or_default();
+    let mut reference_names = HashSet::new();
+    for reference in &references {
+        let name = reference.name.clone().unwrap_or_default();
+        if !name.trim().is_empty() && !reference_names.insert(name.clone()) {
+            issues.push(IssueVo::error("duplicate_reference", format!("关联关系名'{}'重复", name)));
+        }
+        let endpoints = [
+            (&reference.start_table_id, &reference.start_field_id),
+            (&reference.end_table_id, &reference.end_field_id),
+        ];
+        for (table_id, field_id) in endpoints {
+            let table = table_id.as_deref().and_then(|table_id| tables_by_id.get(table_id));
+            let field = table.zip(field_id.as_deref()).and_then(|(table, field_id)| {
+                table.fields.as_ref()?.iter().find(|field| field.id == field_id)
+            });
+            if field.is_none() {
+                let mut issue = IssueVo::error(
+                    "broken_reference",
+                    format!("关联关系'{}'引用的表或字段不存在", if name.is_empty() { &reference.id } else { &name })
+                );
+                issue.table_id = table_id.clone();
+                issue.field_id = field_id.clone();
+                issues.push(issue);
+            }
+        }
+    }
+
+    //5、循环依赖，沿起点表到终点表的方向查找环，自关联不算
+    let mut edges: HashMap<&str, Vec<&str>> = HashMap::new();
+    for reference in &references {
+        if let (Some(start), Some(end)) = (reference.start_table_id.as_deref(), reference.end_table_id.as_deref()) {
+            if start != end && tables_by_id.contains_key(end) {
+                edges.entry(start).or_default().push(end);
+            }
+        }
+    }
+    let mut visited = HashSet::new();
+    let mut circular = Vec::new();
+    for table in &tables {
+        find_cycles(&table.id, &edges, &mut Vec::new(), &mut visited, &mut circular);
+    }
+    for table_id in circular {
+        let name = tables_by_id.get(table_id).and_then(|table| table.name.clone()).unwrap_or_default();
+        issues.push(IssueVo::warning("circular_dependency", format!("表'{}'存在循环依赖", name)).on_table(table_id));
+    }
+    issues
+}
+
+/// 是否存在error级别的问题
+pub fn has_errors(issues: &[IssueVo]) -> bool {
+    issues.iter().any(|issue| issue.severity == IssueSeverity::Error)
+}
+
+fn validate_table(table: &TableVo, table_name: &str, data_types: DataTypes, issues: &mut Vec<IssueVo>) {
+    let fields = table.fields.clone().unwrap_or_default();
+    let mut field_names = HashSet::new();
+    for field in &fields {
+        let name = field.name.clone().unwrap_or_default();
+        let r#type = field.r#type.clone().unwrap_or_default();
+        let issue = |code: &str, message: String| IssueVo::error(code, message).on_field(&table.id, &field.id);
+        if name.trim().is_empty() {
+            issues.push(issue("empty_field_name", format!("表'{}'中存在未命名的字段", table_name)));
+        } else if !field_names.insert(name.clone()) {
+            issues.push(issue("duplicate_fields", format!("表'{}'中的字段名'{}'重复", table_name, name)));
+        }
+        if r#type.trim().is_empty() {
+            issues.push(issue("empty_field_type", format!("表'{}'中存在未指定类型的字段", table_name)));
+        } else if is_enum_or_set(&r#type) && field.values.as_ref().is_none_or(Vec::is_empty) {
+            issues.push(issue(
+                "no_values_for_field",
+                format!("表'{}'的字段'{}'为{}类型，但没有指定取值", table_name, name, r#type)
+            ));
+        }
+        if !data_types.check_default(field) {
+            issues.push(issue(
+                "default_doesnt_match_type",
+                format!("表'{}'的字段'{}'的默认值与类型不匹配", table_name, name)
+            ));
+        }
+        let default = field.default.clone().unwrap_or_default();
+        if field.not_null.unwrap_or(false) && default.trim().eq_ignore_ascii_case("null") {
+            issues.push(issue(
+                "not_null_is_null",
+                format!("表'{}'的字段'{}'不允许为空，但默认值为NULL", table_name, name)
+            ));
+        }
+    }
+
+    let mut indice_names = HashSet::new();
+    for indice in table.indices.clone().unwrap_or_default() {
+        let name = indice.name.clone().unwrap_or_default();
+        if name.trim().is_empty() {
+            issues.push(IssueVo::error("empty_index_name", format!("表'{}'中存在未命名的索引", table_name)).on_table(&table.id));
+        } else if !indice_names.insert(name.clone()) {
+            issues.push(IssueVo::error("duplicate_index", format!("表'{}'中的索引名'{}'重复", table_name, name)).on_table(&table.id));
+        }
+        if indice.fields.as_ref().is_none_or(Vec::is_empty) {
+            issues.push(IssueVo::error("empty_index", format!("表'{}'中存在不包含字段的索引", table_name)).on_table(&table.id));
+        }
+    }
+
+    if !fields.iter().any(|field| field.primary.unwrap_or(false)) {
+        issues.push(IssueVo::warning("no_primary_key", format!("表'{}'没有主键", table_name)).on_table(&table.id));
+    }
+}
+
+/// 深度优先遍历，记录环上的所有表
+fn find_cycles<'a>(
+    table_id: &'a str,
+    edges: &HashMap<&'a str, Vec<&'a str>>,
+    path: &mut Vec<&'a str>,
+    visited: &mut HashSet<&'a str>,
+    circular: &mut Vec<&'a str>
+) {
+    if let Some(start) = path.iter().position(|id| *id == table_id) {
+        for id in &path[start..] {
+            if !circular.contains(id) {
+                circular.push(id);
+            }
+        }
+        return;
+    }
+    if !visited.insert(table_id) {
+        return;
+    }
+    path.push(table_id);
+    for next in edges.get(table_id).into_iter().flatten() {
+        find_cycles(next, edges, path, visited, circular);
+    }
+    path.pop();
+}
+
+fn is_enum_or_set(r#type: &str) -> bool {
+    let r#type = r#type.trim().to_uppercase();
+    r#type == "ENUM" || r#type == "SET"
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::entity::vo::{FieldVo, ReferenceVo};
+    use crate::entity::vo::fixtures::{build_diagram, build_field, build_table};
+
+    fn build_reference(id: &str, start: (&str, &str), end: (&str, &str)) -> ReferenceVo {
+        ReferenceVo {
+            id: id.to_string(),
+            name: None,
+            start_table_id: Some(start.0.to_string()),
+            end_table_id: Some(end.0.to_string()),
+            cardinality: None,
+            delete_constraint: None,
+            end_field_id: Some(end.1.to_string()),
+            start_field_id: Some(start.1.to_string()),
+            update_constraint: None,
+        }
+    }
+
+    fn primary(id: &str) -> FieldVo {
+        let mut field = build_field(id, "id", "INT");
+        field.primary = Some(true);
+        field
+    }
+
+    fn codes(issues: &[IssueVo]) -> Vec<&str> {
+        issues.iter().map(|issue| issue.code.as_str()).collect()
+    }
+
+    #[test]
+    fn test_valid_diagram(){
+        let diagram = build_diagram(
+            vec![
+                build_table("t1", "users", vec![primary("u1")], vec![]),
+                build_table("t2", "orders", vec![primary("o1"), build_field("o2", "user_id", "INT")], vec![]),
+            ],
+            vec![build_reference("r1", ("t2", "o2"), ("t1", "u1"))],
+        );
+        assert!(validate_diagram(&diagram).is_empty());
+    }
+
+    #[test]
+    fn test_table_and_field_issues(){
+        let mut status = build_field("u3", "status", "ENUM");
+        status.default = Some("'active'".to_string());
+        let mut age = build_field("u4", "age", "INT");
+        age.default = Some("abc".to_string());
+        let mut code = build_field("u5", "code", "VARCHAR");
+        code.size = Some(2);
+        code.default = Some("'abcd'".to_string());
+        let mut memo = build_field("u6", "memo", "TEXT");
+        memo.not_null = Some(true);
+        memo.default = Some("NULL".to_string());
+        let diagram = build_diagram(
+            vec![
+                build_table("t1", "users", vec![
+                    primary("u1"),
+                    build_field("u2", "id", "INT"),
+                    status,
+                    age,
+                    code,
+                    memo,
+                ], vec![]),
+                build_table("t2", "users", vec![build_field("o1", "name", "")], vec![]),
+            ],
+            vec![],
+        );
+        let issues = validate_diagram(&diagram);
+        assert_eq!(codes(&issues), vec![
+            "duplicate_fields",
+            "no_values_for_field",
+            "default_doesnt_match_type",
+            "default_doesnt_match_type",
+            "default_doesnt_match_type",
+            "not_null_is_null",
+            "duplicate_table_by_name",
+            "empty_field_type",
+            "no_primary_key",
+        ]);
+        assert_eq!(issues[0].table_id.as_deref(), Some("t1"));
+        assert_eq!(issues[0].field_id.as_deref(), Some("u2"));
+        assert_eq!(issues.last().unwrap().severity, IssueSeverity::Warning);
+        assert!(has_errors(&issues));
+    }
+
+    #[test]
+    fn test_reference_issues(){
+        let diagram = build_diagram(
+            vec![
+                build_table("t1", "a", vec![primary("a1"), build_field("a2", "b_id", "INT")], vec![]),
+                build_table("t2", "b", vec![primary("b1"), build_field("b2", "a_id", "INT")], vec![]),
+            ],
+            vec![
+                build_reference("r1", ("t1", "a2"), ("t2", "b1")),
+                build_reference("r2", ("t2", "b2"), ("t1", "a1")),
+                build_reference("r3", ("t2", "b3"), ("t1", "a1")),
+            ],
+        );
+        let issues = validate_diagram(&diagram);
+        assert_eq!(codes(&issues), vec!["broken_reference", "circular_dependency", "circular_dependency"]);
+        assert_eq!(issues[0].field_id.as_deref(), Some("b3"));
+        assert!(has_errors(&issues));
+        assert!(!has_errors(&issues[1..]));
+    }
+}
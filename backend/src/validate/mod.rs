@@ -0,0 +1,33 @@
+mod issues;
+pub use issues::*;
+use actix_web::{get, web};
+use sea_orm::DatabaseConnection;
+use crate::common::{CommonResponse, ResponseCode, ResponseMessage};
+use crate::diagrams::query_diagram_detail;
+use crate::error::DrawDBError;
+
+/// 校验模块，挂载在/diagrams下
+pub fn validate_routes(config: &mut web::ServiceConfig) {
+    config.service(query_issues);
+}
+
+/// 查询图表结构中的问题
+/// 返回问题列表，每个问题包含严重程度、编码、所在的表和字段以及描述
+#[get("/{id}/issues")]
+async fn query_issues(
+    db: web::Data<DatabaseConnection>,
+    id: web::Path<String>
+) -> Result<CommonResponse, DrawDBError> {
+    let Some(diagram) = query_diagram_detail(db.get_ref(), id.into_inner()).await? else {
+        return Ok(CommonResponse::new(
+            ResponseCode::NotFound,
+            ResponseMessage::NotFound,
+            None,
+        ));
+    };
+    Ok(CommonResponse::new(
+        ResponseCode::Success,
+        ResponseMessage::Success,
+        Some(serde_json::to_value(validate_diagram(&diagram)).unwrap()),
+    ))
+}
@@ -0,0 +1,79 @@
+use sea_orm::{ColumnTrait, ConnectionTrait, DatabaseTransaction, EntityTrait, QueryFilter, QueryOrder};
+use crate::diagrams::save_diagram_detail;
+use crate::entity::{diagram_version, prelude::*};
+use crate::entity::vo::{DiagramSaveVo, DiagramVersionVo, DiagramVo};
+use crate::error::DrawDBError;
+use crate::next_id;
+
+/// 记录图表的版本
+/// diagram为保存后的完整结构，版本号为该图表已有的最大版本号加一
+pub async fn add_diagram_version<C: ConnectionTrait>(
+    conn: &C,
+    diagram: &DiagramVo,
+    author: Option<String>,
+    message: Option<String>
+) -> Result<DiagramVersionVo, DrawDBError> {
+    let latest = DiagramVersion::find()
+    .filter(diagram_version::Column::DiagramId.eq(diagram.id.clone()))
+    .order_by_desc(diagram_version::Column::Version)
+    .one(conn)
+    .await?;
+    let version = diagram_version::Model {
+        id: next_id(),
+        diagram_id: diagram.id.clone(),
+        version: latest.map(|latest| latest.version).unwrap_or_default() + 1,
+        snapshot: serde_json::to_string(diagram).map_err(|e| DrawDBError::OtherError(e.to_string()))?,
+        author,
+        message,
+        created_at: chrono::Utc::now().to_rfc3339(),
+    };
+    DiagramVersion::insert(diagram_version::ActiveModel::from(version.clone())).exec(conn).await?;
+    Ok(DiagramVersionVo::from(&version))
+}
+
+/// 查询图表的所有版本，按版本号倒序，不包含快照
+pub async fn query_diagram_versions<C: ConnectionTrait>(
+    conn: &C,
+    diagram_id: String
+) -> Result<Vec<DiagramVersionVo>, DrawDBError> {
+    let versions = DiagramVersion::find()
+    .filter(diagram_version::Column::DiagramId.eq(diagram_id))
+    .order_by_desc(diagram_version::Column::Version)
+    .all(conn)
+    .await?;
+    Ok(versions.iter().map(DiagramVersionVo::from).collect())
+}
+
+/// 查询图表的某个版本，包含快照
+pub async fn query_diagram_version<C: ConnectionTrait>(
+    conn: &C,
+    diagram_id: String,
+    version: i32
+) -> Result<Option<DiagramVersionVo>, DrawDBError> {
+    let version = DiagramVersion::find()
+    .filter(diagram_version::Column::DiagramId.eq(diagram_id))
+    .filter(diagram_version::Column::Version.eq(version))
+    .one(conn)
+    .await?;
+    Ok(version.as_ref().map(DiagramVersionVo::from_with_snapshot))
+}
+
+/// 将图表恢复到某个版本
+/// 快照中已被删除的表、关联关系等重新新增并使用新的id，代办事项不随版本恢复
+/// 恢复本身也记录为一个新版本，版本或图表不存在时返回None
+pub async fn restore_diagram_version(
+    tx: &DatabaseTransaction,
+    diagram_id: String,
+    version: i32,
+    author: Option<String>
+) -> Result<Option<DiagramSaveVo>, DrawDBError> {
+    let Some(origin) = query_diagram_version(tx, diagram_id, version).await? else {
+        return Ok(None);
+    };
+    let snapshot = origin.snapshot.ok_or(DrawDBError::DeconstructError(format!("版本{}的快照无法解析", version)))?;
+    let Some(result) = save_diagram_detail(tx, snapshot).await? else {
+        return Ok(None);
+    };
+    add_diagram_version(tx, &result.diagram, author, Some(format!("恢复到版本{}", version))).await?;
+    Ok(Some(result))
+}
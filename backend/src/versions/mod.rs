@@ -0,0 +1,177 @@
+mod internal_api;
+pub use internal_api::*;
+use actix_web::{get, post, web};
+use sea_orm::{DatabaseConnection, TransactionTrait};
+use serde::Deserialize;
+use crate::common::{CommonResponse, ResponseCode, ResponseMessage};
+use crate::error::DrawDBError;
+
+/// 版本模块，挂载在/diagrams下
+pub fn versions_routes(config: &mut web::ServiceConfig) {
+    config.service(query_versions);
+    config.service(query_version);
+    config.service(restore_version);
+}
+
+/// 恢复版本的参数
+#[derive(Debug, Deserialize)]
+pub struct RestoreQuery {
+    // 操作人
+    pub author: Option<String>,
+}
+
+/// 查询图表的版本列表
+#[get("/{id}/versions")]
+async fn query_versions(
+    db: web::Data<DatabaseConnection>,
+    id: web::Path<String>
+) -> Result<CommonResponse, DrawDBError> {
+    let versions = query_diagram_versions(db.get_ref(), id.into_inner()).await?;
+    Ok(CommonResponse::new(
+        ResponseCode::Success,
+        ResponseMessage::Success,
+        Some(serde_json::to_value(versions).unwrap()),
+    ))
+}
+
+/// 查询图表的某个版本，返回版本信息以及完整的图表结构
+#[get("/{id}/versions/{version}")]
+async fn query_version(
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<(String, i32)>
+) -> Result<CommonResponse, DrawDBError> {
+    let (id, version) = path.into_inner();
+    let Some(version) = query_diagram_version(db.get_ref(), id, version).await? else {
+        return Ok(CommonResponse::new(
+            ResponseCode::NotFound,
+            ResponseMessage::NotFound,
+            None,
+        ));
+    };
+    Ok(CommonResponse::new(
+        ResponseCode::Success,
+        ResponseMessage::Success,
+        Some(serde_json::to_value(version).unwrap()),
+    ))
+}
+
+/// 将图表恢复到某个版本
+/// 在同一个事务中保存快照并记录新版本，返回保存结果
+#[post("/{id}/versions/{version}/restore")]
+async fn restore_version(
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<(String, i32)>,
+    query: web::Query<RestoreQuery>
+) -> Result<CommonResponse, DrawDBError> {
+    let (id, version) = path.into_inner();
+    let tx = db.begin().await?;
+    let Some(result) = restore_diagram_version(&tx, id, version, query.into_inner().author).await? else {
+        tx.rollback().await?;
+        return Ok(CommonResponse::new(
+            ResponseCode::NotFound,
+            ResponseMessage::NotFound,
+            None,
+        ));
+    };
+    tx.commit().await?;
+    Ok(CommonResponse::new(
+        ResponseCode::Success,
+        ResponseMessage::Success,
+        Some(serde_json::to_value(result).unwrap()),
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use actix_web::{test, App};
+    use sea_orm::EntityTrait;
+    use serde_json::json;
+    use super::*;
+    use crate::diagrams::{diagrams_routes, query_diagram_detail};
+    use crate::entity::{diagram, prelude::*};
+    use crate::entity::vo::{DiagramSaveVo, DiagramVersionVo};
+    use crate::init::init_test_db;
+
+    /// 删除表后恢复到删除前的版本
+    #[actix_web::test]
+    async fn test_restore_version() {
+        let db = init_test_db().await;
+        Diagram::insert(diagram::ActiveModel::from(diagram::Model {
+            id: "d1".to_string(),
+            database: Some("generic".to_string()),
+            zoom: None,
+            name: Some("demo".to_string()),
+            pan: None,
+            last_modified: None,
+            types: None,
+            enums: None,
+        })).exec(&db).await.unwrap();
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(db.clone()))
+                .configure(diagrams_routes)
+                .configure(versions_routes)
+        ).await;
+        let table = json!({
+            "id": "0", "color": null, "comment": null, "locked": false, "name": "users",
+            "x": "0", "y": "0", "indices": [], "diagram_id": "d1",
+            "fields": [{
+                "id": "0", "table_id": null, "check": null, "comment": null, "default": null,
+                "increment": false, "not_null": true, "primary": true, "size": null,
+                "type": "INT", "unique": false, "name": "id"
+            }]
+        });
+        let diagram = |tables: Vec<serde_json::Value>| json!({
+            "id": "d1", "zoom": null, "database": null, "name": null, "tables": tables,
+            "areas": null, "references": null, "indices": null, "notes": null, "tasks": null,
+            "pan": null, "lastModified": null, "types": null, "enums": null
+        });
+        //1、新增表，再删除表
+        let req = test::TestRequest::post().uri("/update?author=alice&message=init").set_json(diagram(vec![table])).to_request();
+        let resp: CommonResponse = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(resp.code, 200);
+        let req = test::TestRequest::post().uri("/update").set_json(diagram(vec![])).to_request();
+        let resp: CommonResponse = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(resp.code, 200);
+        assert!(Table::find().all(&db).await.unwrap().is_empty());
+
+        //2、版本列表不包含快照
+        let req = test::TestRequest::get().uri("/d1/versions").to_request();
+        let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+        let versions = body["data"].as_array().unwrap();
+        assert_eq!(versions.len(), 2);
+        assert_eq!(versions[0]["version"], 2);
+        assert_eq!(versions[1]["author"], "alice");
+        assert_eq!(versions[1]["message"], "init");
+        assert!(versions[0].get("snapshot").is_none());
+
+        let req = test::TestRequest::get().uri("/d1/versions/1").to_request();
+        let resp: CommonResponse = test::call_and_read_body_json(&app, req).await;
+        let version: DiagramVersionVo = serde_json::from_value(resp.data.unwrap()).unwrap();
+        assert_eq!(version.snapshot.unwrap().tables.unwrap().len(), 1);
+
+        //3、恢复到版本1
+        let req = test::TestRequest::post().uri("/d1/versions/1/restore?author=bob").to_request();
+        let resp: CommonResponse = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(resp.code, 200);
+        let saved: DiagramSaveVo = serde_json::from_value(resp.data.unwrap()).unwrap();
+        let tables = saved.diagram.tables.unwrap();
+        assert_eq!(tables.len(), 1);
+        assert_eq!(tables[0].name.as_deref(), Some("users"));
+        assert_eq!(tables[0].fields.as_ref().unwrap().len(), 1);
+        let detail = query_diagram_detail(&db, "d1".to_string()).await.unwrap().unwrap();
+        assert_eq!(detail.tables.unwrap().len(), 1);
+        let versions = query_diagram_versions(&db, "d1".to_string()).await.unwrap();
+        assert_eq!(versions.len(), 3);
+        assert_eq!(versions[0].author.as_deref(), Some("bob"));
+        assert_eq!(versions[0].message.as_deref(), Some("恢复到版本1"));
+
+        //4、版本不存在
+        let req = test::TestRequest::get().uri("/d1/versions/9").to_request();
+        let resp: CommonResponse = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(resp.code, 404);
+        let req = test::TestRequest::post().uri("/d1/versions/9/restore").to_request();
+        let resp: CommonResponse = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(resp.code, 404);
+    }
+}